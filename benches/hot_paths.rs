@@ -0,0 +1,164 @@
+#[macro_use]
+extern crate criterion;
+extern crate my_bot;
+extern crate serde_json;
+
+use std::sync::Once;
+
+use criterion::{black_box, Criterion};
+
+use my_bot::hlt::danger::DangerMap;
+use my_bot::hlt::engine::Engine;
+use my_bot::hlt::inspiration::InspirationMap;
+use my_bot::hlt::kernel::Kernel;
+use my_bot::hlt::{constants, Board, Game, Position, StringIo};
+
+/// Recorded engine frames for a 2 player Game on a 48x48 map.
+const FIXTURE: &str = include_str!("../tests/fixtures/2p-48x48.txt");
+
+static CONSTANTS: Once = Once::new();
+
+/// Parse the fixture and play it to the end.
+fn game() -> Game {
+    CONSTANTS.call_once(|| {
+        let line = FIXTURE.lines().next().unwrap();
+        constants::set(serde_json::from_str(line).unwrap());
+    });
+    let mut io = StringIo::new(FIXTURE);
+    io.input.pop_front();
+    let mut game = Game::init_with(&mut io).unwrap();
+    while !io.input.is_empty() {
+        game.update_with(&mut io).unwrap();
+    }
+    game
+}
+
+fn bench_board_indexing(c: &mut Criterion) {
+    let game = game();
+    let board = &game.board;
+    c.bench_function("board indexing", move |b| {
+        b.iter(|| {
+            let mut total = 0;
+            for y in -1..board.height + 1 {
+                for x in -1..board.width + 1 {
+                    total += board[Position::new(x, y)].halite;
+                }
+            }
+            black_box(total)
+        })
+    });
+}
+
+fn bench_distance(c: &mut Criterion) {
+    let board = Board::new(64, 64);
+    let from = Position::new(3, 60);
+    c.bench_function("distance", move |b| {
+        b.iter(|| {
+            let mut total = 0;
+            for y in 0..board.height {
+                for x in 0..board.width {
+                    total += board.distance(black_box(from), Position::new(x, y));
+                }
+            }
+            black_box(total)
+        })
+    });
+}
+
+fn bench_halite_within(c: &mut Criterion) {
+    let game = game();
+    let board = &game.board;
+    c.bench_function("halite within radius 6", move |b| {
+        b.iter(|| board.halite_within(black_box(Position::new(10, 10)), 6))
+    });
+}
+
+fn bench_convolve(c: &mut Criterion) {
+    let game = game();
+    let board = game.board.clone();
+    let kernel = Kernel::falloff(4, 0.5);
+    c.bench_function("convolve radius 4", move |b| b.iter(|| board.convolve(&kernel)));
+}
+
+fn bench_density_maps(c: &mut Criterion) {
+    let game = game();
+    let enemies: Vec<_> = game
+        .ships
+        .values()
+        .filter(|ship| ship.player_id != game.my_id)
+        .cloned()
+        .collect();
+    let structures = game.structure_positions();
+    let constants = constants::get();
+
+    let (board, inspiration_enemies) = (game.board.clone(), enemies.clone());
+    c.bench_function("inspiration map", move |b| {
+        b.iter(|| {
+            InspirationMap::compute(
+                &board,
+                &inspiration_enemies,
+                constants.inspiration_radius,
+                constants.inspiration_ship_count,
+                constants.inspired_bonus_multiplier,
+                constants.extract_ratio,
+                constants.inspired_extract_ratio,
+            )
+        })
+    });
+
+    let board = game.board.clone();
+    c.bench_function("danger map", move |b| {
+        b.iter(|| DangerMap::compute(&board, &enemies, &structures, constants.max_halite))
+    });
+}
+
+fn bench_tokenizer(c: &mut Criterion) {
+    c.bench_function("tokenizer", |b| {
+        b.iter(|| {
+            let mut io = StringIo::new(FIXTURE);
+            io.input.pop_front();
+            let mut engine = Engine::new(&mut io);
+            let mut total = 0;
+            while let Ok(token) = engine.next_token::<usize>() {
+                total += token;
+            }
+            black_box(total)
+        })
+    });
+}
+
+fn bench_update(c: &mut Criterion) {
+    let mut io = StringIo::new(FIXTURE);
+    io.input.pop_front();
+    let start = Game::init_with(&mut io).unwrap();
+    c.bench_function("update", move |b| {
+        b.iter(|| {
+            let mut io = io.clone();
+            let mut game = start.clone();
+            while !io.input.is_empty() {
+                game.update_with(&mut io).unwrap();
+            }
+            black_box(game.turn)
+        })
+    });
+}
+
+fn bench_snapshot(c: &mut Criterion) {
+    let game = game();
+    let json_game = game.clone();
+    c.bench_function("snapshot to json", move |b| b.iter(|| json_game.to_json()));
+    c.bench_function("snapshot to bytes", move |b| b.iter(|| game.to_bytes()));
+}
+
+criterion_group!(
+    benches,
+    bench_board_indexing,
+    bench_distance,
+    bench_halite_within,
+    bench_convolve,
+    bench_density_maps,
+    bench_tokenizer,
+    bench_update,
+    bench_snapshot
+);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate my_bot;
+extern crate serde_json;
+
+use std::str;
+use std::sync::Once;
+
+use my_bot::hlt::{constants, Game, StringIo};
+
+/// The constants are not part of the fuzzed input, because they can only be set once.
+static CONSTANTS: Once = Once::new();
+
+fuzz_target!(|data: &[u8]| {
+    CONSTANTS.call_once(|| {
+        let line = include_str!("../../tests/fixtures/2p-32x32.txt")
+            .lines()
+            .next()
+            .unwrap();
+        constants::set(serde_json::from_str(line).unwrap());
+    });
+
+    let input = match str::from_utf8(data) {
+        Ok(input) => input,
+        Err(_) => return,
+    };
+
+    // Parse the init frame, and then as many update frames as there are.
+    let mut io = StringIo::new(input);
+    if let Ok(mut game) = Game::init_with(&mut io) {
+        while !io.input.is_empty() {
+            if game.update_with(&mut io).is_err() {
+                break;
+            }
+        }
+    }
+});
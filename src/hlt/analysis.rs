@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::board::Structure;
+use super::replay::{Replay, ReplayFrame};
+use super::{PlayerId, Position, Result};
+
+/// How a Player played a Game, worked out from its replay.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerReport {
+    /// The Player's identifier.
+    pub id: PlayerId,
+    /// The name of the bot.
+    pub name: String,
+    /// The number of Ships built.
+    pub ships_built: usize,
+    /// The number of turns all the Ships were alive for, added up.
+    pub ship_turns: usize,
+    /// The total halite the Ships mined.
+    pub halite_mined: usize,
+    /// The total halite the Ships delivered to a Shipyard or Dropoff.
+    pub halite_delivered: usize,
+    /// The number of Ships that were destroyed in collisions, rather than converted.
+    pub collisions: usize,
+    /// The turns on which Dropoffs were built.
+    pub dropoffs: Vec<usize>,
+    /// The halite banked at the end of the Game.
+    pub final_halite: usize,
+}
+
+impl PlayerReport {
+    /// Return the average halite mined per Ship per turn.
+    pub fn mining_efficiency(&self) -> f64 {
+        if self.ship_turns == 0 {
+            0.0
+        } else {
+            self.halite_mined as f64 / self.ship_turns as f64
+        }
+    }
+}
+
+/// Return whether the Cell at the given Position has a structure owned by the given Player.
+fn is_owned_structure(frame: &ReplayFrame, position: Position, player_id: PlayerId) -> bool {
+    match frame.board[position].structure {
+        Some(Structure::Shipyard(id)) => usize::from(id) == usize::from(player_id),
+        Some(Structure::Dropoff(id)) => frame
+            .dropoffs
+            .get(&id)
+            .is_some_and(|dropoff| dropoff.player_id == player_id),
+        None => false,
+    }
+}
+
+impl Replay {
+    /// Work out how every Player played the Game, ordered by identifier.
+    ///
+    /// Ships only appear in the replay on turns they are alive, so a Ship that disappears
+    /// without a Dropoff being built where it was is counted as destroyed in a collision. A Ship
+    /// whose cargo goes down on one of its Player's structures has delivered its cargo, less the
+    /// cost of moving there.
+    pub fn analyze(&self) -> Vec<PlayerReport> {
+        let mut reports: BTreeMap<_, _> = self
+            .players
+            .iter()
+            .map(|player| {
+                let report = PlayerReport {
+                    id: player.id,
+                    name: player.name.clone(),
+                    ships_built: 0,
+                    ship_turns: 0,
+                    halite_mined: 0,
+                    halite_delivered: 0,
+                    collisions: 0,
+                    dropoffs: Vec::new(),
+                    final_halite: 0,
+                };
+                (player.id, report)
+            }).collect();
+
+        for (turn, pair) in self.frames.windows(2).enumerate() {
+            let (previous, current) = (&pair[0], &pair[1]);
+            let turn = turn + 1;
+
+            for ship in current.ships.values() {
+                let report = match reports.get_mut(&ship.player_id) {
+                    Some(report) => report,
+                    None => continue,
+                };
+                report.ship_turns += 1;
+                match previous.ships.get(&ship.id) {
+                    None => report.ships_built += 1,
+                    Some(old) if ship.halite > old.halite => {
+                        report.halite_mined += ship.halite - old.halite;
+                    }
+                    Some(old)
+                        if ship.halite < old.halite
+                            && is_owned_structure(current, ship.position, ship.player_id) =>
+                    {
+                        let cost =
+                            previous.board[old.position].halite / self.constants.move_cost_ratio;
+                        report.halite_delivered += old.halite.saturating_sub(cost);
+                    }
+                    Some(_) => {}
+                }
+            }
+
+            for ship in previous.ships.values() {
+                if current.ships.contains_key(&ship.id) {
+                    continue;
+                }
+                let converted = current
+                    .dropoffs
+                    .values()
+                    .any(|d| d.position == ship.position && d.player_id == ship.player_id);
+                if !converted {
+                    if let Some(report) = reports.get_mut(&ship.player_id) {
+                        report.collisions += 1;
+                    }
+                }
+            }
+
+            for dropoff in current.dropoffs.values() {
+                if !previous.dropoffs.contains_key(&dropoff.id) {
+                    if let Some(report) = reports.get_mut(&dropoff.player_id) {
+                        report.dropoffs.push(turn);
+                    }
+                }
+            }
+        }
+
+        if let Some(last) = self.last_frame() {
+            for (id, halite) in &last.halite {
+                if let Some(report) = reports.get_mut(id) {
+                    report.final_halite = *halite;
+                }
+            }
+        }
+
+        reports.into_values().collect()
+    }
+}
+
+/// Analyze the replay in the file at the given path, and format the reports as a table.
+pub fn analyze_file<P: AsRef<Path>>(path: P) -> Result<String> {
+    Ok(format_reports(&Replay::open(path)?.analyze()))
+}
+
+/// Format the reports as a table, one Player per row.
+pub fn format_reports(reports: &[PlayerReport]) -> String {
+    let mut table = format!(
+        "{:<4} {:<20} {:>8} {:>6} {:>10} {:>10} {:>10} {:>10}  {}\n",
+        "id", "name", "halite", "ships", "mined", "delivered", "per turn", "collisions", "dropoffs"
+    );
+    for r in reports {
+        let dropoffs: Vec<_> = r.dropoffs.iter().map(|t| t.to_string()).collect();
+        let row = format!(
+            "{:<4} {:<20} {:>8} {:>6} {:>10} {:>10} {:>10.2} {:>10}  {}",
+            r.id,
+            r.name,
+            r.final_halite,
+            r.ships_built,
+            r.halite_mined,
+            r.halite_delivered,
+            r.mining_efficiency(),
+            r.collisions,
+            dropoffs.join(", ")
+        );
+        table.push_str(row.trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures::REPLAY;
+    use super::super::ShipId;
+    use super::*;
+
+    #[test]
+    fn test_analyze() {
+        let mut replay = Replay::from_json(REPLAY.as_bytes()).unwrap();
+        let reports = replay.analyze();
+        assert_eq!(reports.len(), 2);
+
+        // Ship 0 disappears, but the Dropoff is built somewhere else.
+        assert_eq!(reports[0].name, "MyBot");
+        assert_eq!(reports[0].ships_built, 1);
+        assert_eq!(reports[0].collisions, 1);
+        assert_eq!(reports[0].dropoffs, vec![2]);
+        assert_eq!(reports[0].final_halite, 10);
+
+        assert_eq!(reports[1].ship_turns, 2);
+        assert_eq!(reports[1].halite_mined, 30);
+        assert_eq!(reports[1].mining_efficiency(), 15.0);
+        assert_eq!(reports[1].collisions, 0);
+
+        // If Ship 0 was where the Dropoff is built it was converted instead.
+        replay.frames[1]
+            .ships
+            .get_mut(&ShipId::new(0))
+            .unwrap()
+            .position = Position::new(2, 1);
+        assert_eq!(replay.analyze()[0].collisions, 0);
+
+        let table = format_reports(&reports);
+        assert_eq!(table.lines().count(), 3);
+    }
+}
@@ -0,0 +1,164 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use serde_json;
+
+use super::{Game, PlayerId, Position, Result};
+
+/// Statistics about one Player on one turn.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PlayerStats {
+    /// The Player's identifier.
+    pub id: PlayerId,
+    /// The amount of halite the Player has banked.
+    pub halite: usize,
+    /// The number of Ships the Player has.
+    pub ships: usize,
+    /// The number of Dropoffs the Player has, not counting the Shipyard.
+    pub dropoffs: usize,
+    /// The total halite carried by the Player's Ships.
+    pub halite_carried: usize,
+    /// An estimate of the total halite the Player's Ships have mined.
+    pub halite_mined: usize,
+}
+
+/// A summary of the value of every Cell to us, which is the halite plus any inspiration bonus.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ValueSummary {
+    /// The mean value of a Cell.
+    pub mean: f64,
+    /// The highest value of a Cell.
+    pub max: f64,
+    /// Where the highest value is.
+    pub best: Position,
+}
+
+/// The statistics for one turn, as written on one line of the analytics export.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TurnStats {
+    /// The turn.
+    pub turn: usize,
+    /// Statistics for every Player, ordered by identifier.
+    pub players: Vec<PlayerStats>,
+    /// The halite left on the Board.
+    pub halite_remaining: usize,
+    /// The value of the Cells to us.
+    pub value: ValueSummary,
+}
+
+impl TurnStats {
+    /// Collect the statistics for the current turn of the Game.
+    pub fn new(game: &Game) -> Self {
+        let mut players: Vec<_> = game
+            .players
+            .values()
+            .map(|player| {
+                let score = game.scoreboard.get(player.id);
+                PlayerStats {
+                    id: player.id,
+                    halite: player.halite,
+                    ships: player.ship_ids.len(),
+                    dropoffs: player.dropoff_ids.len(),
+                    halite_carried: player.ships(game).map(|ship| ship.halite).sum(),
+                    halite_mined: score.map_or(0, |s| s.halite_mined),
+                }
+            }).collect();
+        players.sort_by_key(|stats| stats.id);
+
+        let mut halite_remaining = 0;
+        let mut total = 0.0;
+        let mut best = (Position::new(0, 0), 0.0);
+        for cell in game.board.cells.iter().flat_map(|row| row.iter()) {
+            halite_remaining += cell.halite;
+            let value = game.cell_value(cell.position);
+            total += value;
+            if value > best.1 {
+                best = (cell.position, value);
+            }
+        }
+        let cells = (game.board.width * game.board.height) as f64;
+
+        TurnStats {
+            turn: game.turn,
+            players,
+            halite_remaining,
+            value: ValueSummary {
+                mean: total / cells,
+                max: best.1,
+                best: best.0,
+            },
+        }
+    }
+}
+
+/// Writes the statistics for every turn as JSON lines, for analysis across many Games.
+///
+/// Each line is a `TurnStats` object, so the output can be loaded directly with pandas'
+/// `read_json(..., lines=True)` or filtered with jq.
+pub struct Analytics<W: Write = BufWriter<File>> {
+    writer: W,
+}
+
+impl Analytics<BufWriter<File>> {
+    /// Create an Analytics export that writes to the file at the given path.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Analytics::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> Analytics<W> {
+    /// Create an Analytics export that writes to the given writer.
+    pub fn new(writer: W) -> Self {
+        Analytics { writer }
+    }
+
+    /// Write the statistics for the current turn of the Game.
+    ///
+    /// Every line is flushed, so the export is complete up to the last turn even if the bot is
+    /// killed.
+    pub fn record(&mut self, game: &Game) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, &TurnStats::new(game))?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures;
+    use super::*;
+
+    #[test]
+    fn test_analytics() {
+        let mut game = fixtures::game(
+            "
+            .    a100  .
+            .    Y     .
+            50   .     .
+            ",
+        );
+        let mut analytics = Analytics::new(Vec::new());
+        analytics.record(&game).unwrap();
+        game.turn += 1;
+        analytics.record(&game).unwrap();
+
+        let output = String::from_utf8(analytics.into_inner()).unwrap();
+        let lines: Vec<TurnStats> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].turn, game.turn);
+        assert_eq!(lines[0].halite_remaining, 150);
+        assert_eq!(lines[0].players.len(), 1);
+        assert_eq!(lines[0].players[0].ships, 1);
+        assert_eq!(lines[0].value.best, Position::new(1, 0));
+    }
+}
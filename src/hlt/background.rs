@@ -0,0 +1,175 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::Board;
+
+/// A job that computes something from the Board, given a flag that is set when it is cancelled.
+type Job = Arc<dyn Fn(&Board, &AtomicBool) -> Box<dyn Any + Send> + Send + Sync>;
+
+/// Jobs that run on a worker thread between turns, while we wait for the next frame.
+///
+/// Register jobs once, call `start` after ending a turn, and `collect` after the next update.
+/// Results of jobs that finished in time can then be taken, and jobs that didn't finish are
+/// cancelled. The jobs that haven't started are skipped, and the running job should check the
+/// cancel flag it is given and return early once it is set, since its result is thrown away.
+pub struct Background {
+    /// The registered jobs, by name.
+    jobs: Vec<(String, Job)>,
+    /// Where results come from while the worker is running.
+    running: Option<Receiver<(String, Box<dyn Any + Send>)>>,
+    /// The results from the last run, by name.
+    results: HashMap<String, Box<dyn Any + Send>>,
+    /// The cancel flag of the current run.
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::new()
+    }
+}
+
+impl Background {
+    /// Create a new Background with no jobs.
+    pub fn new() -> Self {
+        Background {
+            jobs: Vec::new(),
+            running: None,
+            results: HashMap::new(),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Register a job to run between every turn.
+    pub fn register<F, T>(&mut self, name: &str, job: F)
+    where
+        F: Fn(&Board, &AtomicBool) -> T + Send + Sync + 'static,
+        T: Any + Send,
+    {
+        let job: Job = Arc::new(move |board, cancelled| Box::new(job(board, cancelled)));
+        self.jobs.push((name.to_string(), job));
+    }
+
+    /// Start running all the jobs on a copy of the Board, cancelling the previous run.
+    pub fn start(&mut self, board: &Board) {
+        self.cancel();
+        if self.jobs.is_empty() {
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        let jobs = self.jobs.clone();
+        let board = board.clone();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.cancelled = cancelled.clone();
+        thread::spawn(move || {
+            for (name, job) in jobs {
+                if cancelled.load(Ordering::Relaxed) {
+                    break;
+                }
+                // Stop once nobody is waiting for the results anymore.
+                if sender.send((name, job(&board, &cancelled))).is_err() {
+                    break;
+                }
+            }
+        });
+        self.running = Some(receiver);
+    }
+
+    /// Tell the running jobs to stop.
+    fn cancel(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.running = None;
+    }
+
+    /// Collect the results of the jobs that have finished, and cancel the rest.
+    ///
+    /// Returns whether all the jobs finished.
+    pub fn collect(&mut self) -> bool {
+        self.wait(Duration::from_secs(0))
+    }
+
+    /// Wait up to the given time for the jobs to finish, then collect the results of the jobs that
+    /// have finished, and cancel the rest.
+    ///
+    /// Returns whether all the jobs finished.
+    pub fn wait(&mut self, timeout: Duration) -> bool {
+        self.results.clear();
+        let receiver = match self.running.take() {
+            Some(receiver) => receiver,
+            None => return true,
+        };
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline
+                .checked_duration_since(Instant::now())
+                .unwrap_or_default();
+            match receiver.recv_timeout(remaining) {
+                Ok((name, result)) => {
+                    self.results.insert(name, result);
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    warn!(
+                        "Cancelling background jobs, only {} of {} finished",
+                        self.results.len(),
+                        self.jobs.len()
+                    );
+                    self.cancel();
+                    return false;
+                }
+                Err(RecvTimeoutError::Disconnected) => return true,
+            }
+        }
+    }
+
+    /// Take the result of the job with the given name, if it finished in time.
+    ///
+    /// Returns `None` if there is no result or it has a different type.
+    pub fn take<T: Any>(&mut self, name: &str) -> Option<T> {
+        let result = self.results.remove(name)?;
+        result.downcast().ok().map(|result| *result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Position;
+    use super::*;
+
+    #[test]
+    fn test_background_jobs() {
+        let mut background = Background::new();
+        background.register("halite", |board: &Board, _: &AtomicBool| {
+            board.halite_within(Position::new(0, 0), 1)
+        });
+        assert!(background.collect());
+
+        let mut board = Board::new(4, 4);
+        board[Position::new(1, 0)].halite = 10;
+        background.start(&board);
+        assert!(background.wait(Duration::from_secs(10)));
+        assert_eq!(background.take::<String>("halite"), None);
+        assert_eq!(background.take::<usize>("halite"), None);
+
+        background.start(&board);
+        assert!(background.wait(Duration::from_secs(10)));
+        assert_eq!(background.take::<usize>("halite"), Some(10));
+
+        // Slow jobs are cancelled.
+        let (sender, receiver) = mpsc::channel();
+        background.register("slow", move |_: &Board, cancelled: &AtomicBool| {
+            while !cancelled.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(1));
+            }
+            sender.send(()).unwrap();
+        });
+        background.start(&board);
+        assert!(!background.wait(Duration::from_millis(100)));
+        assert_eq!(background.take::<()>("slow"), None);
+        assert!(receiver.recv_timeout(Duration::from_secs(10)).is_ok());
+    }
+}
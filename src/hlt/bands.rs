@@ -0,0 +1,108 @@
+use super::{Board, Grid, Position};
+
+/// The distances from our structures that lists of Positions are precomputed for.
+pub const BAND_DISTANCES: [usize; 4] = [1, 2, 4, 8];
+
+/// Masks of the Cells within various distances of any of our structures.
+///
+/// This is useful for defending against harassment, checking whether it is safe to spawn, and
+/// mining close to home first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bands {
+    /// The distance from each Position to the closest structure.
+    pub distances: Grid<usize>,
+    /// For each of the `BAND_DISTANCES`, the Positions within that distance, closest first.
+    pub positions: Vec<Vec<Position>>,
+}
+
+impl Bands {
+    /// Create new Bands with no structures, so that nothing is within any distance.
+    pub fn new(width: isize, height: isize) -> Self {
+        Bands {
+            distances: Grid::new(width, height, usize::MAX),
+            positions: vec![Vec::new(); BAND_DISTANCES.len()],
+        }
+    }
+
+    /// Compute the Bands around the given structures.
+    pub fn compute(board: &Board, structures: &[Position]) -> Self {
+        let mut bands = Bands::new(board.width, board.height);
+
+        let mut positions = Vec::new();
+        for row in &board.cells {
+            for cell in row {
+                let distance = structures
+                    .iter()
+                    .map(|s| board.distance(cell.position, *s))
+                    .min();
+                if let Some(distance) = distance {
+                    bands.distances[cell.position] = distance;
+                    positions.push((distance, cell.position));
+                }
+            }
+        }
+        positions.sort_by_key(|(distance, p)| (*distance, p.y, p.x));
+
+        for (band, max) in bands.positions.iter_mut().zip(&BAND_DISTANCES) {
+            band.extend(
+                positions
+                    .iter()
+                    .take_while(|(distance, _)| distance <= max)
+                    .map(|(_, p)| *p),
+            );
+        }
+
+        bands
+    }
+
+    /// Return the distance from the given Position to the closest structure.
+    pub fn distance(&self, position: Position) -> usize {
+        self.distances[position]
+    }
+
+    /// Return whether the given Position is within a distance of any structure.
+    pub fn is_within(&self, position: Position, distance: usize) -> bool {
+        self.distance(position) <= distance
+    }
+
+    /// Return the Positions within one of the `BAND_DISTANCES` of any structure, closest first.
+    ///
+    /// Returns `None` if the distance is not one of the `BAND_DISTANCES`.
+    pub fn within(&self, distance: usize) -> Option<&[Position]> {
+        BAND_DISTANCES
+            .iter()
+            .position(|d| *d == distance)
+            .map(|i| &self.positions[i][..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bands_compute() {
+        let board = Board::new(20, 20);
+        let structures = [Position::new(2, 2), Position::new(12, 12)];
+
+        let bands = Bands::compute(&board, &structures);
+        assert_eq!(bands.distance(Position::new(2, 2)), 0);
+        assert_eq!(bands.distance(Position::new(10, 12)), 2);
+        assert!(bands.is_within(Position::new(-1, 2), 3));
+        assert!(!bands.is_within(Position::new(7, 7), 9));
+
+        let adjacent = bands.within(1).unwrap();
+        assert_eq!(adjacent.len(), 10);
+        assert_eq!(adjacent[0], Position::new(2, 2));
+        assert!(bands
+            .within(2)
+            .unwrap()
+            .iter()
+            .all(|p| bands.distance(*p) <= 2));
+        assert_eq!(bands.within(3), None);
+
+        let empty = Bands::compute(&board, &[]);
+        assert!(!empty.is_within(Position::new(0, 0), 100));
+        assert!(empty.within(8).unwrap().is_empty());
+    }
+}
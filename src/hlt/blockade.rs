@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use super::{Game, ShipId};
+
+/// The default number of consecutive turns an enemy Ship must stay next to our Shipyard to be
+/// considered camping.
+const DEFAULT_CAMP_TURNS: usize = 3;
+
+/// Detects enemy Ships camping on or next to our Shipyard.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Blockade {
+    /// The number of consecutive turns an enemy Ship must stay next to our Shipyard to be
+    /// considered camping. Enemy Ships on our Shipyard are always considered camping.
+    pub camp_turns: usize,
+    /// For each enemy Ship near our Shipyard, the number of consecutive turns it has been there.
+    pub nearby: HashMap<ShipId, usize>,
+    /// The enemy Ships currently camping.
+    pub campers: Vec<ShipId>,
+}
+
+impl Default for Blockade {
+    fn default() -> Self {
+        Blockade::new()
+    }
+}
+
+impl Blockade {
+    /// Create a new Blockade detector.
+    pub fn new() -> Self {
+        Blockade {
+            camp_turns: DEFAULT_CAMP_TURNS,
+            nearby: HashMap::new(),
+            campers: Vec::new(),
+        }
+    }
+
+    /// Return whether our Shipyard is currently blockaded.
+    pub fn is_active(&self) -> bool {
+        !self.campers.is_empty()
+    }
+
+    /// Update the detector with the current state of the Game.
+    pub fn record(&mut self, game: &Game) {
+        let shipyard = game.me().shipyard.position;
+
+        let mut nearby = HashMap::new();
+        let mut campers = Vec::new();
+        for enemy in game.enemy_ships() {
+            let distance = game.board.distance(enemy.position, shipyard);
+            if distance > 1 {
+                continue;
+            }
+            let turns = self.nearby.get(&enemy.id).cloned().unwrap_or(0) + 1;
+            nearby.insert(enemy.id, turns);
+            if distance == 0 || turns >= self.camp_turns {
+                campers.push(enemy.id);
+            }
+        }
+        campers.sort();
+
+        self.nearby = nearby;
+        self.campers = campers;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixtures, PlayerId, Position, Ship};
+    use super::*;
+
+    #[test]
+    fn test_blockade_record() {
+        let mut game = fixtures::empty_game(5, 3, Position::new(2, 1));
+        let them = PlayerId::new(1);
+        let beside = Ship::new(ShipId::new(5), them, Position::new(3, 1), 0);
+        game.ships.insert(beside.id, beside);
+        let mut blockade = Blockade::new();
+
+        // Enemy Ships next to our Shipyard have to stay a while to be camping.
+        for _ in 1..blockade.camp_turns {
+            blockade.record(&game);
+            assert!(!blockade.is_active());
+        }
+        blockade.record(&game);
+        assert_eq!(blockade.campers, vec![beside.id]);
+
+        // Enemy Ships on our Shipyard are always camping.
+        let on = Ship::new(ShipId::new(6), them, Position::new(2, 1), 0);
+        game.ships.insert(on.id, on);
+        blockade.record(&game);
+        assert_eq!(blockade.campers, vec![beside.id, on.id]);
+
+        // Enemy Ships that leave are forgotten.
+        game.ships.get_mut(&beside.id).unwrap().position = Position::new(4, 1);
+        game.ships.remove(&on.id);
+        blockade.record(&game);
+        assert!(!blockade.is_active());
+        assert!(blockade.nearby.is_empty());
+    }
+}
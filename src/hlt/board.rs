@@ -1,3 +1,5 @@
+use std::cmp;
+use std::collections::VecDeque;
 use std::ops::{Add, Index, IndexMut, Sub};
 
 use super::{Direction, DropoffId, Result, ShipId, ShipyardId};
@@ -18,7 +20,7 @@ fn invert(value: isize, dimension: isize) -> isize {
 }
 
 /// A point on the Board.
-#[derive(Clone, Constructor, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Constructor, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct Position {
     pub x: isize,
     pub y: isize,
@@ -37,6 +39,11 @@ impl Offset {
         (self.dx.abs() + self.dy.abs()) as usize
     }
 
+    /// Whether this Offset is between a Position and itself.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Return the unit direction for this Offset.
     pub fn signum(self) -> Offset {
         Offset {
@@ -81,11 +88,13 @@ impl Offset {
 
     /// Reduce an Offset to the smallest possible version, taking into account dimensions.
     pub fn reduce(self, width: isize, height: isize) -> Offset {
+        // Offsets spanning more than the whole Board are first brought within the dimensions.
+        let offset = Offset::new(self.dx % width, self.dy % height);
         *[
-            self.inverted(width, height),
-            self.inverted_dx(width),
-            self.inverted_dy(height),
-            self,
+            offset.inverted(width, height),
+            offset.inverted_dx(width),
+            offset.inverted_dy(height),
+            offset,
         ]
             .iter()
             .min_by_key(|o| o.len())
@@ -129,6 +138,7 @@ impl From<Direction> for Offset {
             Direction::East => (1, 0),
             Direction::South => (0, 1),
             Direction::West => (-1, 0),
+            Direction::Still => (0, 0),
         };
         Offset::new(dx, dy)
     }
@@ -190,14 +200,16 @@ pub enum Structure {
     Dropoff(DropoffId),
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Cell {
     /// The position of this Cell.
     pub position: Position,
     /// A structure that could be present in this Cell.
     pub structure: Option<Structure>,
-    /// A ship that could be present in this Cell.
-    pub ship: Option<ShipId>,
+    /// The Ships present in this Cell.
+    ///
+    /// There is normally at most one, but many of our Ships may stack onto our own structures.
+    pub ships: Vec<ShipId>,
     /// The amount of halite in this Cell.
     pub halite: usize,
 }
@@ -208,14 +220,41 @@ impl Cell {
         Cell {
             position,
             structure: None,
-            ship: None,
+            ships: Vec::new(),
             halite,
         }
     }
 
+    /// Return the first Ship in this Cell, if any.
+    pub fn ship(&self) -> Option<ShipId> {
+        self.ships.first().cloned()
+    }
+
     /// Whether this Cell has a Ship.
     pub fn is_occupied(&self) -> bool {
-        self.ship.is_some()
+        !self.ships.is_empty()
+    }
+
+    /// Whether this Cell has the given Ship.
+    pub fn has_ship(&self, ship_id: ShipId) -> bool {
+        self.ships.contains(&ship_id)
+    }
+
+    /// Whether this Cell has a Ship other than the given Ship.
+    pub fn has_other_ship(&self, ship_id: ShipId) -> bool {
+        self.ships.iter().any(|id| *id != ship_id)
+    }
+
+    /// Add a Ship to this Cell.
+    pub fn add_ship(&mut self, ship_id: ShipId) {
+        if !self.has_ship(ship_id) {
+            self.ships.push(ship_id);
+        }
+    }
+
+    /// Remove a Ship from this Cell, if it is present.
+    pub fn remove_ship(&mut self, ship_id: ShipId) {
+        self.ships.retain(|id| *id != ship_id);
     }
 
     /// Whether this Cell has a Shipyard or Dropoff.
@@ -256,6 +295,281 @@ impl Board {
             cells,
         }
     }
+
+    /// Return whether the given Position is on the Board, without wrapping around at the edges.
+    pub fn contains(&self, position: Position) -> bool {
+        self.get(position).is_some()
+    }
+
+    /// Return the Cell at the given Position, or `None` if it is outside the Board.
+    ///
+    /// Unlike indexing, this doesn't wrap around at the edges, so it catches Positions that should
+    /// have been normalized.
+    pub fn get(&self, position: Position) -> Option<&Cell> {
+        if position.x < 0 || position.y < 0 {
+            return None;
+        }
+        self.cells
+            .get(position.y as usize)
+            .and_then(|row| row.get(position.x as usize))
+    }
+
+    /// Return the Cell at the given Position mutably, or `None` if it is outside the Board.
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut Cell> {
+        if position.x < 0 || position.y < 0 {
+            return None;
+        }
+        self.cells
+            .get_mut(position.y as usize)
+            .and_then(|row| row.get_mut(position.x as usize))
+    }
+
+    /// Create a Board from a compact text grid, which is mostly useful for tests.
+    ///
+    /// Each non-empty line is a row, and each whitespace separated token is a Cell. A token is an
+    /// optional marker followed by an optional amount of halite, where `.` means no halite. The
+    /// markers are `Y` for a Shipyard, `D` for a Dropoff, and a lowercase letter for a Ship, where
+    /// `a` is Ship 0, `b` is Ship 1, and so on. Shipyards and Dropoffs are numbered in reading
+    /// order.
+    ///
+    /// ```text
+    /// .  10  Y   .
+    /// a5 .   b   D100
+    /// ```
+    pub fn from_ascii(ascii: &str) -> Result<Board> {
+        let rows: Vec<Vec<&str>> = ascii
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+            .filter(|tokens| !tokens.is_empty())
+            .collect();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        if height == 0 || rows.iter().any(|row| row.len() != width) {
+            return Err(format_err!("Board rows must be non-empty and of equal length"));
+        }
+
+        let mut board = Board::new(width as isize, height as isize);
+        let (mut shipyards, mut dropoffs) = (0, 0);
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, token) in row.iter().enumerate() {
+                let cell = &mut board.cells[y][x];
+                let mut halite = *token;
+                match token.chars().next() {
+                    Some('Y') => {
+                        cell.structure = Some(Structure::Shipyard(ShipyardId::new(shipyards)));
+                        shipyards += 1;
+                        halite = &token[1..];
+                    }
+                    Some('D') => {
+                        cell.structure = Some(Structure::Dropoff(DropoffId::new(dropoffs)));
+                        dropoffs += 1;
+                        halite = &token[1..];
+                    }
+                    Some(c) if c.is_ascii_lowercase() => {
+                        cell.add_ship(ShipId::new(c as usize - 'a' as usize));
+                        halite = &token[1..];
+                    }
+                    _ => {}
+                }
+                cell.halite = match halite {
+                    "" | "." => 0,
+                    _ => halite
+                        .parse()
+                        .map_err(|_| format_err!("invalid Cell {:?}", token))?,
+                };
+            }
+        }
+
+        Ok(board)
+    }
+
+    /// Return the shortest Offset from one Position to another, taking wrapping into account.
+    pub fn offset(&self, from: Position, to: Position) -> Offset {
+        (to - from).reduce(self.width, self.height)
+    }
+
+    /// Return the shortest distance between two Positions, taking wrapping into account.
+    pub fn distance(&self, a: Position, b: Position) -> usize {
+        self.offset(a, b).len()
+    }
+
+    /// Return all the Positions within the given distance of the center Position.
+    pub fn positions_within(&self, center: Position, radius: usize) -> Vec<Position> {
+        let radius = radius as isize;
+        let mut positions = Vec::new();
+        for dy in -radius..=radius {
+            let remaining = radius - dy.abs();
+            for dx in -remaining..=remaining {
+                let position = (center + Offset::new(dx, dy)).normalized(self.width, self.height);
+                if !positions.contains(&position) {
+                    positions.push(position);
+                }
+            }
+        }
+        positions
+    }
+
+    /// Return the total halite in all the Cells within the given distance of the center Position.
+    pub fn halite_within(&self, center: Position, radius: usize) -> usize {
+        self.positions_within(center, radius)
+            .into_iter()
+            .map(|p| self[p].halite)
+            .sum()
+    }
+
+    /// Return an iterator over every Position on the Board, in order of distance from the center.
+    ///
+    /// Positions at the same distance are ordered from north to south, then west to east. The
+    /// Positions are generated a ring at a time, so searches that stop early are cheap.
+    pub fn spiral_from(&self, center: Position) -> Spiral {
+        Spiral {
+            width: self.width,
+            height: self.height,
+            center: center.normalized(self.width, self.height),
+            radius: 0,
+            ring: Vec::new(),
+        }
+    }
+
+    /// Return the closest Position to the center whose Cell matches a predicate.
+    pub fn nearest<F>(&self, center: Position, mut predicate: F) -> Option<Position>
+    where
+        F: FnMut(&Cell) -> bool,
+    {
+        self.spiral_from(center).find(|p| predicate(&self[*p]))
+    }
+
+    /// Return the Positions connected to the start Position through Cells matching a predicate.
+    ///
+    /// The result is in breadth first order, starting with the start Position, and is empty if the
+    /// start Cell doesn't match.
+    pub fn flood_fill<F>(&self, start: Position, mut predicate: F) -> Vec<Position>
+    where
+        F: FnMut(&Cell) -> bool,
+    {
+        let mut visited = vec![vec![false; self.width as usize]; self.height as usize];
+        self.fill(start, &mut predicate, &mut visited)
+    }
+
+    /// Flood fill from the start Position, skipping and marking Positions already visited.
+    fn fill<F>(
+        &self,
+        start: Position,
+        predicate: &mut F,
+        visited: &mut [Vec<bool>],
+    ) -> Vec<Position>
+    where
+        F: FnMut(&Cell) -> bool,
+    {
+        let start = start.normalized(self.width, self.height);
+        if visited[start.y as usize][start.x as usize] {
+            return Vec::new();
+        }
+        let mut component = Vec::new();
+        let mut queue = VecDeque::new();
+
+        visited[start.y as usize][start.x as usize] = true;
+        queue.push_back(start);
+        while let Some(position) = queue.pop_front() {
+            if !predicate(&self[position]) {
+                continue;
+            }
+            component.push(position);
+            for neighbor in position.surrounding() {
+                let neighbor = neighbor.normalized(self.width, self.height);
+                let seen = &mut visited[neighbor.y as usize][neighbor.x as usize];
+                if !*seen {
+                    *seen = true;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        component
+    }
+
+    /// Return all the connected regions of Cells matching a predicate, largest first.
+    pub fn regions<F>(&self, mut predicate: F) -> Vec<Vec<Position>>
+    where
+        F: FnMut(&Cell) -> bool,
+    {
+        let mut visited = vec![vec![false; self.width as usize]; self.height as usize];
+        let mut regions = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let region = self.fill(Position::new(x, y), &mut predicate, &mut visited);
+                if !region.is_empty() {
+                    regions.push(region);
+                }
+            }
+        }
+        regions.sort_by_key(|region| cmp::Reverse(region.len()));
+        regions
+    }
+
+    /// Return the Directions that would bring a Ship at one Position closer to another.
+    ///
+    /// The result is empty if the Positions are the same.
+    pub fn directions_toward(&self, from: Position, to: Position) -> Vec<Direction> {
+        let offset = self.offset(from, to);
+        [offset.dx(), offset.dy()]
+            .iter()
+            .filter_map(|o| o.into_direction().ok())
+            .collect()
+    }
+}
+
+/// An iterator over the Positions on a Board in order of distance from a center Position.
+///
+/// Created by `Board::spiral_from`.
+#[derive(Clone, Debug)]
+pub struct Spiral {
+    width: isize,
+    height: isize,
+    center: Position,
+    radius: isize,
+    /// The remaining Positions at the current radius, in reverse order.
+    ring: Vec<Position>,
+}
+
+impl Spiral {
+    /// Fill the ring with the Positions at the current radius.
+    ///
+    /// Offsets are limited to half the Board in each dimension, so that every Position appears
+    /// exactly once, at its wrapped distance.
+    fn fill_ring(&mut self) {
+        let (min_dx, max_dx) = (-(self.width - 1) / 2, self.width / 2);
+        let (min_dy, max_dy) = (-(self.height - 1) / 2, self.height / 2);
+        let radius = self.radius;
+        for dy in (-radius..=radius).rev() {
+            let remaining = radius - dy.abs();
+            let dxs = if remaining == 0 {
+                vec![0]
+            } else {
+                vec![remaining, -remaining]
+            };
+            for dx in dxs {
+                if min_dx <= dx && dx <= max_dx && min_dy <= dy && dy <= max_dy {
+                    let position = self.center + Offset::new(dx, dy);
+                    self.ring.push(position.normalized(self.width, self.height));
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for Spiral {
+    type Item = Position;
+
+    fn next(&mut self) -> Option<Position> {
+        let max_radius = self.width / 2 + self.height / 2;
+        while self.ring.is_empty() && self.radius <= max_radius {
+            self.fill_ring();
+            self.radius += 1;
+        }
+        self.ring.pop()
+    }
 }
 
 /// Allow indexing the Board with Positions.
@@ -280,6 +594,19 @@ impl IndexMut<Position> for Board {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_board_get() {
+        let mut board = Board::new(4, 3);
+        board.get_mut(Position::new(3, 2)).unwrap().halite = 7;
+        assert_eq!(board.get(Position::new(3, 2)).unwrap().halite, 7);
+        assert_eq!(board[Position::new(-1, -1)].halite, 7);
+        assert!(board.get(Position::new(-1, -1)).is_none());
+        assert!(board.get(Position::new(4, 0)).is_none());
+        assert!(board.get_mut(Position::new(0, 3)).is_none());
+        assert!(board.contains(Position::new(0, 0)));
+        assert!(!board.contains(Position::new(0, -1)));
+    }
+
     #[test]
     fn test_normalize() {
         assert_eq!(normalize(-5, 10), 5);
@@ -419,6 +746,160 @@ mod tests {
         assert_eq!(input.mirrored_dy(), Offset::new(3, -4));
     }
 
+    #[test]
+    fn test_board_distance() {
+        let board = Board::new(5, 5);
+        assert_eq!(board.distance(Position::new(0, 0), Position::new(0, 0)), 0);
+        assert_eq!(board.distance(Position::new(0, 0), Position::new(4, 4)), 2);
+        assert_eq!(board.distance(Position::new(1, 1), Position::new(3, 2)), 3);
+    }
+
+    #[test]
+    fn test_board_positions_within() {
+        let board = Board::new(10, 10);
+        assert_eq!(board.positions_within(Position::new(0, 0), 0).len(), 1);
+        assert_eq!(board.positions_within(Position::new(0, 0), 1).len(), 5);
+        assert_eq!(board.positions_within(Position::new(0, 0), 2).len(), 13);
+
+        // The whole Board is covered and no Position is repeated.
+        let board = Board::new(3, 3);
+        assert_eq!(board.positions_within(Position::new(1, 1), 5).len(), 9);
+    }
+
+    #[test]
+    fn test_board_from_ascii() {
+        let board = Board::from_ascii(
+            "
+            .   10  Y   .
+            a5  .   b   D100
+            ",
+        ).unwrap();
+        assert_eq!((board.width, board.height), (4, 2));
+        assert_eq!(board[Position::new(1, 0)].halite, 10);
+        assert_eq!(
+            board[Position::new(2, 0)].structure,
+            Some(Structure::Shipyard(ShipyardId::new(0)))
+        );
+        assert_eq!(board[Position::new(0, 1)].ship(), Some(ShipId::new(0)));
+        assert_eq!(board[Position::new(0, 1)].halite, 5);
+        assert_eq!(board[Position::new(2, 1)].ship(), Some(ShipId::new(1)));
+        assert_eq!(
+            board[Position::new(3, 1)].structure,
+            Some(Structure::Dropoff(DropoffId::new(0)))
+        );
+        assert_eq!(board[Position::new(3, 1)].halite, 100);
+
+        assert!(Board::from_ascii("1 2\n3").is_err());
+        assert!(Board::from_ascii("1 X").is_err());
+        assert!(Board::from_ascii("").is_err());
+    }
+
+    #[test]
+    fn test_board_flood_fill() {
+        let board = Board::from_ascii(
+            "
+            100 100 .   .   100
+            .   100 .   .   .
+            .   .   .   100 .
+            100 .   .   100 .
+            ",
+        ).unwrap();
+        let rich = |cell: &Cell| cell.halite > 0;
+
+        let mut region = board.flood_fill(Position::new(1, 1), rich);
+        region.sort_by_key(|p| (p.y, p.x));
+        assert_eq!(
+            region,
+            vec![
+                Position::new(0, 0),
+                Position::new(1, 0),
+                Position::new(4, 0),
+                Position::new(1, 1),
+                Position::new(0, 3),
+            ]
+        );
+        assert!(board.flood_fill(Position::new(2, 2), rich).is_empty());
+
+        let sizes: Vec<_> = board.regions(rich).iter().map(|r| r.len()).collect();
+        assert_eq!(sizes, vec![5, 2]);
+        assert_eq!(board.regions(|_| true).len(), 1);
+    }
+
+    #[test]
+    fn test_board_spiral_from() {
+        for &(width, height) in &[(5, 5), (6, 4), (1, 3)] {
+            let board = Board::new(width, height);
+            let center = Position::new(1, 2);
+            let spiral: Vec<_> = board.spiral_from(center).collect();
+
+            let mut sorted = spiral.clone();
+            sorted.sort_by_key(|p| (p.y, p.x));
+            sorted.dedup();
+            assert_eq!(sorted.len(), (width * height) as usize);
+            assert_eq!(spiral.len(), sorted.len());
+            assert_eq!(spiral[0], center.normalized(width, height));
+            assert!(
+                spiral
+                    .windows(2)
+                    .all(|w| board.distance(center, w[0]) <= board.distance(center, w[1]))
+            );
+        }
+
+        let board = Board::new(5, 5);
+        let first: Vec<_> = board.spiral_from(Position::new(0, 0)).take(3).collect();
+        assert_eq!(
+            first,
+            vec![Position::new(0, 0), Position::new(0, 4), Position::new(4, 0)]
+        );
+    }
+
+    #[test]
+    fn test_board_nearest() {
+        let board = Board::from_ascii(
+            "
+            .  .  .  .  .
+            .  .  .  .  .
+            .  .  .  .  50
+            .  .  .  .  .
+            .  .  20 .  .
+            ",
+        ).unwrap();
+        let center = Position::new(0, 2);
+        assert_eq!(board.nearest(center, |c| c.halite > 0), Some(Position::new(4, 2)));
+        assert_eq!(board.nearest(center, |c| c.halite > 20), Some(Position::new(4, 2)));
+        assert_eq!(board.nearest(center, |c| c.halite > 50), None);
+    }
+
+    #[test]
+    fn test_cell_ships() {
+        let mut cell = Cell::new(Position::new(0, 0), 0);
+        cell.add_ship(1.into());
+        cell.add_ship(2.into());
+        cell.add_ship(2.into());
+        assert_eq!(cell.ships, vec![1.into(), 2.into()]);
+        assert_eq!(cell.ship(), Some(1.into()));
+        assert!(cell.has_other_ship(1.into()));
+
+        cell.remove_ship(1.into());
+        assert_eq!(cell.ship(), Some(2.into()));
+        assert!(!cell.has_other_ship(2.into()));
+        assert!(cell.is_occupied());
+    }
+
+    #[test]
+    fn test_board_directions_toward() {
+        let board = Board::new(5, 5);
+        assert!(
+            board
+                .directions_toward(Position::new(2, 2), Position::new(2, 2))
+                .is_empty()
+        );
+        assert_eq!(
+            board.directions_toward(Position::new(0, 0), Position::new(4, 1)),
+            vec![Direction::West, Direction::South]
+        );
+    }
+
     #[test]
     fn test_offset_reduce() {
         let width = 5;
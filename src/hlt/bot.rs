@@ -0,0 +1,344 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use rand::Rng;
+
+use super::analytics::Analytics;
+use super::background::Background;
+use super::flog::FlogWriter;
+use super::heatmap::Heatmaps;
+use super::kernel::Kernel;
+use super::metrics::CsvMetrics;
+#[cfg(feature = "debug-server")]
+use super::server::DebugServer;
+use super::trajectory::Trajectory;
+use super::transcript::TranscriptIo;
+use super::util::configure_logger;
+use super::*;
+
+/// The maximum distance a Ship goes out of its way for a pile of halite dropped in a collision.
+const MAX_PILE_DISTANCE: usize = 5;
+
+/// How to play, usually from the command line.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Options {
+    /// The name of the bot.
+    pub name: String,
+    /// Whether to enable logging and visualizer annotations.
+    pub debug: bool,
+    /// Override the name of the log file.
+    pub log_file: Option<String>,
+    /// Write statistics for every turn to this JSON lines file.
+    pub analytics: Option<String>,
+    /// Write metrics for every turn to this CSV file.
+    pub metrics: Option<String>,
+    /// Write heatmap images for every turn to this directory.
+    pub heatmaps: Option<String>,
+    /// Write features and rewards for every turn to this JSON lines file.
+    pub trajectory: Option<String>,
+    /// Load strategy parameters from this JSON file.
+    pub params: Option<String>,
+    /// Log phases of a turn that take longer than this.
+    pub slow: Option<Duration>,
+}
+
+/// Our bot: the policies, the background jobs, and everything written out during a Game.
+pub struct Bot {
+    /// How the bot was asked to play.
+    options: Options,
+    /// Talks to the Halite engine, shared with the Watchdog.
+    io: SharedIo<TranscriptIo>,
+    /// Annotations for the Halite visualizer.
+    flog: FlogWriter,
+    /// Where statistics for every turn are written.
+    analytics: Option<Analytics>,
+    /// Where metrics for every turn are written.
+    metrics: Option<CsvMetrics>,
+    /// Where heatmap images for every turn are written.
+    heatmaps: Option<Heatmaps>,
+    /// Where features and rewards for every turn are written.
+    trajectory: Option<Trajectory>,
+    /// Streams the state of the Game to viewers.
+    #[cfg(feature = "debug-server")]
+    server: DebugServer,
+    /// Automatically builds Dropoffs when it is worthwhile.
+    dropoff_builder: policy::DropoffBuilder,
+    /// Brings every Ship home at the end of the Game.
+    recall: policy::Recall,
+    /// Breaks blockades of our Shipyard.
+    blockade_breaker: policy::BlockadeBreaker,
+    /// Denies halite to our opponents in hopeless endgames.
+    denial: policy::Denial,
+    /// Rams enemy Ships when it is a profitable trade.
+    aggression: policy::Aggression,
+    /// Jobs to run between turns, while waiting for the next frame.
+    background: Background,
+    /// Sends fallback commands if a turn runs long, rather than timing out of the Game.
+    watchdog: Watchdog<SharedIo<TranscriptIo>>,
+}
+
+impl Bot {
+    /// Set up a Bot for the given Game, which has just been started through the given SharedIo.
+    pub fn new(game: &mut Game, io: SharedIo<TranscriptIo>, options: Options) -> Result<Self> {
+        // Configure the logger, so that we can use debug! and other log macros.
+        // It will log to a file called "MyBot-<game-seed>-<my-id>.log"
+        let prefix = format!(
+            "{}-{}-{}",
+            options.name,
+            constants::get().game_seed,
+            game.my_id
+        );
+        if options.debug {
+            let log_filename = format!("{}.log", prefix);
+            configure_logger(options.log_file.as_ref().unwrap_or(&log_filename))?;
+            isolation::install_panic_logger();
+        }
+
+        // Annotations for the Halite visualizer, added with `flog.log(game.turn, position, msg)`.
+        // With logging enabled they are written to "MyBot-<game-seed>-<my-id>.flog" at the end of
+        // the Game, or when the bot panics.
+        let flog = FlogWriter::new(format!("{}.flog", prefix));
+        if options.debug {
+            flog.install_panic_hook();
+        }
+
+        // Use tuned strategy parameters, if given.
+        if let Some(ref filename) = options.params {
+            game.params = Params::load(filename)?;
+            info!("Loaded parameters from {}: {:?}", filename, game.params);
+        }
+
+        // Log slow phases of a turn as they happen, if requested.
+        if options.slow.is_some() {
+            timing::set_slow(options.slow);
+        }
+
+        // Export statistics and metrics for every turn, if requested.
+        let analytics = match options.analytics {
+            Some(ref filename) => Some(Analytics::create(filename)?),
+            None => None,
+        };
+        let metrics = match options.metrics {
+            Some(ref filename) => Some(CsvMetrics::create(filename)?),
+            None => None,
+        };
+        let heatmaps = match options.heatmaps {
+            Some(ref directory) => Some(Heatmaps::create(directory)?),
+            None => None,
+        };
+        let trajectory = match options.trajectory {
+            Some(ref filename) => Some(Trajectory::create(filename)?),
+            None => None,
+        };
+
+        // Stream the state of the Game to viewers, one port per Player so local opponents don't
+        // clash.
+        #[cfg(feature = "debug-server")]
+        let server = DebugServer::bind(("127.0.0.1", 8700 + usize::from(game.my_id) as u16))?;
+
+        // At this point "game" variable is populated with initial map data.
+        // This is a good place to do computationally expensive start-up pre-processing.
+        // ...
+        // ...
+        // ...
+
+        // Automatically build Dropoffs when it is worthwhile.
+        let dropoff_builder = policy::DropoffBuilder::new();
+
+        // Jobs to run between turns, while waiting for the next frame. Register them here and take
+        // their results with `background.take(name)` during a turn.
+        let mut background = Background::new();
+        let radius = dropoff_builder.radius;
+        background.register("density", move |board: &Board, _: &AtomicBool| {
+            board.convolve(&Kernel::diamond(radius))
+        });
+        if options.debug {
+            let rich = constants::get().max_halite / 4;
+            background.register("regions", move |board: &Board, cancelled: &AtomicBool| {
+                board.regions(|cell| !cancelled.load(Ordering::Relaxed) && cell.halite >= rich)
+            });
+        }
+
+        Ok(Bot {
+            options,
+            watchdog: Watchdog::with_io(game.budget.turn(), io.clone()),
+            io,
+            flog,
+            analytics,
+            metrics,
+            heatmaps,
+            trajectory,
+            #[cfg(feature = "debug-server")]
+            server,
+            dropoff_builder,
+            recall: policy::Recall::new(),
+            blockade_breaker: policy::BlockadeBreaker::new(),
+            denial: policy::Denial::new(),
+            aggression: policy::Aggression::new(),
+            background,
+        })
+    }
+
+    /// Play a turn of the Game, which has just been updated from the engine.
+    pub fn turn(&mut self, game: &mut Game) -> Result<()> {
+        // Record the state before any commands change it.
+        if let Some(ref mut trajectory) = self.trajectory {
+            trajectory.record(game)?;
+        }
+        // The Watchdog goes by the real time, so it can't be replayed.
+        if !self.io.lock().is_replaying() {
+            self.watchdog.arm(game);
+        }
+        self.background.collect();
+        self.dropoff_builder.density = self.background.take("density");
+        // Highlight the largest rich regions in the visualizer.
+        if let Some(regions) = self.background.take::<Vec<Vec<Position>>>("regions") {
+            for position in regions.iter().take(3).flat_map(|region| region.iter()) {
+                self.flog.color(game.turn, *position, "#FFD700");
+            }
+        }
+
+        // Run the strategy. If it panics, our Ships collect this turn instead.
+        let finished = game.run_strategy(|game| self.strategy(game));
+        if !finished {
+            self.watchdog.offer(game);
+        }
+
+        // Make sure our Ships don't run into each other, including the ones the policies moved.
+        game.resolve_conflicts();
+
+        // Do less work on the next turns if this one ran long.
+        let elapsed = if self.watchdog.disarm() {
+            game.end_turn_with(&mut self.io)?.elapsed
+        } else {
+            self.io.lock().record_fallback(game.turn)?;
+            game.elapsed()
+        };
+        let elapsed = self.io.lock().elapsed(elapsed)?;
+        game.effort.record(elapsed);
+        if let Some(ref mut analytics) = self.analytics {
+            analytics.record(game)?;
+        }
+        if let Some(ref mut metrics) = self.metrics {
+            metrics.record(game)?;
+        }
+        if let Some(ref heatmaps) = self.heatmaps {
+            heatmaps.record(game)?;
+        }
+        #[cfg(feature = "debug-server")]
+        self.server.publish(game)?;
+        if self.options.debug && game.turn >= constants::get().max_turns {
+            self.flog.write()?;
+        }
+        self.background.start(&game.board);
+        Ok(())
+    }
+
+    /// Queue the commands for this turn.
+    fn strategy(&mut self, game: &mut Game) {
+        // Let the policies command their Ships first.
+        self.dropoff_builder.step(game);
+        self.recall.step(game);
+        self.blockade_breaker.step(game);
+        self.denial.step(game);
+        self.aggression.step(game);
+        self.watchdog.offer(game);
+
+        // Get our Player.
+        let me = game.me().clone();
+
+        // Loop through all of our Ships, sending the ones with enough cargo home, and the rest to
+        // nearby piles of dropped halite, or else in a random direction.
+        timed!{ "navigation" => {
+            for ship_id in me.ship_ids {
+                if game.has_command(ship_id) {
+                    continue;
+                }
+
+                let ship = game.ships[&ship_id];
+                let cell = &game.board[ship.position];
+
+                if game.should_return(ship_id) {
+                    game.ship(ship_id).return_home();
+                } else if cell.halite < constants::get().max_halite / 10 {
+                    if let Some(pile) = game.nearest_halite_pile(ship.position, MAX_PILE_DISTANCE) {
+                        game.ship(ship_id).move_toward(pile);
+                    } else {
+                        // This is a random bot, so we use the Game's randomizer to pick the
+                        // direction.
+                        let direction = Direction::all()[game.rng().gen_range(0, 4)];
+                        game.ship(ship_id).move_in(direction);
+                    }
+                } else {
+                    game.ship(ship_id).collect();
+                }
+            }
+        }}
+
+        // If we have enough halite, spawn a new ship!
+        let cost = constants::get().new_entity_halite_cost + self.dropoff_builder.reserved();
+        if game.turn <= 400 && me.halite >= cost {
+            game.try_spawn();
+        }
+    }
+
+    /// Write out everything that is left once the Game is over.
+    pub fn finish(&mut self, game: &Game) -> Result<()> {
+        // Write the annotations again in case the Game ended before the last turn.
+        if self.options.debug {
+            self.flog.write()?;
+        }
+        if let Some(ref mut trajectory) = self.trajectory {
+            trajectory.finish(game)?;
+        }
+        let fired_turns = self.watchdog.fired_turns();
+        if !fired_turns.is_empty() {
+            warn!("The Watchdog sent the commands on turns {:?}", fired_turns);
+        }
+        Ok(())
+    }
+}
+
+/// Play a whole Game through the given TranscriptIo, and return it once the engine ends it.
+///
+/// Replaying a transcript gives the same commands as when it was recorded, since the randomness
+/// is seeded from the Game.
+pub fn play(io: &SharedIo<TranscriptIo>, options: Options) -> Result<Game> {
+    let mut io = io.clone();
+
+    // Start a new Game, by reading the game information from the Halite engine.
+    let mut game = Game::start_with(&mut io)?;
+    // Skip malformed frames, rather than giving up on the whole Game.
+    game.resync = true;
+
+    let name = options.name.clone();
+    let mut bot = Bot::new(&mut game, io.clone(), options)?;
+
+    // Call "ready" function below, the 2 second per turn timer will start now.
+    game.ready_with(&mut io, &name)?;
+    info!(
+        "Successfully initialized {}! Player ID is {}",
+        name, game.my_id
+    );
+
+    loop {
+        // Get the updated Game from the Halite engine, until it closes its output.
+        if let Err(err) = game.update_with(&mut io) {
+            match Error::find(&err) {
+                Some(Error::GameOver) => {
+                    info!("The engine has ended the game after turn {}", game.turn);
+                    break;
+                }
+                Some(Error::SkippedFrame(_)) => {
+                    game.skip_turn_with(&mut io)?;
+                    continue;
+                }
+                _ => return Err(err),
+            }
+        }
+        bot.turn(&mut game)?;
+    }
+
+    bot.finish(&game)?;
+    Ok(game)
+}
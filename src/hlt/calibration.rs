@@ -0,0 +1,198 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::{constants, Game, Position, Ship, ShipId};
+
+/// The default number of mining turns that calibration is measured over.
+const DEFAULT_WINDOW: usize = 200;
+
+/// A prediction of what a Ship will mine if it stays where it is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Prediction {
+    /// Where the Ship was.
+    position: Position,
+    /// The halite the Ship had.
+    halite: usize,
+    /// The prediction before it was scaled.
+    raw: f64,
+    /// The prediction after it was scaled.
+    predicted: f64,
+}
+
+/// Compares the halite our Ships actually mine against what `Game::expected_yield` predicted.
+///
+/// Every turn each of our Ships gets a prediction for staying where it is, and the Ships that did
+/// stay are compared against it on the next turn. If the learning rate is set, the scale applied
+/// to the predictions is moved towards the ratio that would have been right over the window.
+#[derive(Clone, Debug, PartialEq)]
+pub struct YieldCalibration {
+    /// The number of mining turns that calibration is measured over.
+    pub window: usize,
+    /// How far to move the scale towards the measured ratio each turn, 0 to never adjust it.
+    pub learning_rate: f64,
+    /// The factor that predictions are multiplied by.
+    pub scale: f64,
+    /// The predictions for our Ships on the previous turn.
+    predictions: HashMap<ShipId, Prediction>,
+    /// The raw prediction, scaled prediction, and the halite actually mined, for recent turns.
+    samples: VecDeque<(f64, f64, f64)>,
+}
+
+impl Default for YieldCalibration {
+    fn default() -> Self {
+        YieldCalibration::new()
+    }
+}
+
+impl YieldCalibration {
+    /// Create a new YieldCalibration that doesn't adjust the predictions.
+    pub fn new() -> Self {
+        YieldCalibration {
+            window: DEFAULT_WINDOW,
+            learning_rate: 0.0,
+            scale: 1.0,
+            predictions: HashMap::new(),
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Record a turn, given our current Ships with their unscaled predictions for this turn.
+    pub fn record(&mut self, ships: &[(Ship, f64)]) {
+        for (ship, _) in ships {
+            let previous = match self.predictions.get(&ship.id) {
+                Some(previous) => *previous,
+                None => continue,
+            };
+            // Ships that moved didn't mine, and Ships that lost halite deposited it.
+            if previous.position != ship.position || ship.halite < previous.halite {
+                continue;
+            }
+            let realized = (ship.halite - previous.halite) as f64;
+            self.samples
+                .push_back((previous.raw, previous.predicted, realized));
+        }
+        while self.samples.len() > self.window {
+            self.samples.pop_front();
+        }
+
+        if self.learning_rate > 0.0 {
+            let raw: f64 = self.samples.iter().map(|s| s.0).sum();
+            let realized: f64 = self.samples.iter().map(|s| s.2).sum();
+            if raw > 0.0 {
+                self.scale += self.learning_rate * (realized / raw - self.scale);
+            }
+        }
+
+        let scale = self.scale;
+        self.predictions = ships
+            .iter()
+            .map(|(ship, raw)| {
+                let prediction = Prediction {
+                    position: ship.position,
+                    halite: ship.halite,
+                    raw: *raw,
+                    predicted: raw * scale,
+                };
+                (ship.id, prediction)
+            }).collect();
+    }
+
+    /// Return the number of mining turns within the window.
+    pub fn samples(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Return the mean absolute difference between the predicted and mined halite.
+    pub fn mean_absolute_error(&self) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = self.samples.iter().map(|s| (s.1 - s.2).abs()).sum();
+        total / self.samples.len() as f64
+    }
+
+    /// Return the mean of the predicted minus the mined halite, positive if we are optimistic.
+    pub fn bias(&self) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = self.samples.iter().map(|s| s.1 - s.2).sum();
+        total / self.samples.len() as f64
+    }
+}
+
+impl Game {
+    /// Return the halite the given Ship is expected to mine if it stays where it is this turn.
+    ///
+    /// This is the value of the Cell, including the expected inspiration bonus, divided by the
+    /// extract ratio, limited by the space left in the Ship and multiplied by the calibrated scale.
+    pub fn expected_yield(&self, ship_id: ShipId) -> f64 {
+        self.yields.scale * self.raw_yield(&self.ships[&ship_id])
+    }
+
+    /// Return the expected yield of a Ship before it is scaled.
+    fn raw_yield(&self, ship: &Ship) -> f64 {
+        let constants = constants::get();
+        if self.board[ship.position].has_structure() {
+            return 0.0;
+        }
+        let mined = self.cell_value(ship.position) / constants.extract_ratio as f64;
+        mined.min(ship.cargo_space() as f64)
+    }
+
+    /// Record the predicted yields of our Ships for this turn, and check last turn's.
+    pub fn record_yields(&mut self) {
+        let ships: Vec<_> = self
+            .me()
+            .ship_ids
+            .iter()
+            .map(|id| {
+                let ship = self.ships[id];
+                (ship, self.raw_yield(&ship))
+            }).collect();
+        self.yields.record(&ships);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixtures, PlayerId};
+    use super::*;
+
+    #[test]
+    fn test_yield_calibration() {
+        let mut calibration = YieldCalibration::new();
+        let ship = |halite| Ship::new(ShipId(0), PlayerId(0), Position::new(0, 0), halite);
+        calibration.record(&[(ship(0), 100.0)]);
+        calibration.record(&[(ship(80), 60.0)]);
+        calibration.record(&[(ship(140), 45.0)]);
+        assert_eq!(calibration.samples(), 2);
+        assert_eq!(calibration.mean_absolute_error(), 10.0);
+        assert_eq!(calibration.bias(), 10.0);
+
+        // Moving or depositing isn't mining.
+        let mut moved = ship(140);
+        moved.position = Position::new(1, 0);
+        calibration.record(&[(moved, 0.0)]);
+        calibration.record(&[(ship(0), 0.0)]);
+        assert_eq!(calibration.samples(), 2);
+
+        let mut calibration = YieldCalibration::new();
+        calibration.learning_rate = 0.5;
+        calibration.record(&[(ship(0), 100.0)]);
+        calibration.record(&[(ship(50), 100.0)]);
+        assert_eq!(calibration.scale, 0.75);
+        calibration.record(&[(ship(100), 100.0)]);
+        assert_eq!(calibration.bias(), 37.5);
+    }
+
+    #[test]
+    fn test_game_expected_yield() {
+        let mut game = fixtures::game("a Y");
+        game.board[Position::new(0, 0)].halite = 400;
+        assert_eq!(game.expected_yield(ShipId(0)), 100.0);
+        game.ships.get_mut(&ShipId(0)).unwrap().halite = 950;
+        assert_eq!(game.expected_yield(ShipId(0)), 50.0);
+        game.yields.scale = 0.5;
+        assert_eq!(game.expected_yield(ShipId(0)), 25.0);
+    }
+}
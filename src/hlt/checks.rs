@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use super::board::Structure;
+use super::constants;
+use super::{Dropoff, DropoffId, Error, Game, Result};
+
+impl Game {
+    /// Return descriptions of every broken invariant in the Game.
+    ///
+    /// This checks that the Board and the Ships agree on where every Ship is, that every Player's
+    /// Ships exist, that none of our Ships share a Cell unless they may stack there, and that every
+    /// queued command is legal.
+    /// It is too slow to run every turn in an uploaded bot, see `check_invariants`.
+    pub fn invariant_violations(&self) -> Vec<String> {
+        let (width, height) = (self.board.width, self.board.height);
+        let mut violations = Vec::new();
+
+        for (id, ship) in &self.ships {
+            match self.board.get(ship.position) {
+                Some(cell) if cell.ships.contains(id) => {}
+                Some(_) => {
+                    violations.push(format!("Ship {} is missing from {:?}", id, ship.position))
+                }
+                None => violations.push(format!(
+                    "Ship {} is outside the Board at {:?}",
+                    id, ship.position
+                )),
+            }
+        }
+
+        for row in &self.board.cells {
+            for cell in row {
+                let mut ours = 0;
+                for id in &cell.ships {
+                    match self.ships.get(id) {
+                        Some(ship) if ship.position.normalized(width, height) == cell.position => {
+                            if ship.player_id == self.my_id {
+                                ours += 1;
+                            }
+                        }
+                        Some(ship) => violations.push(format!(
+                            "Ship {} is in {:?} on the Board but at {:?}",
+                            id, cell.position, ship.position
+                        )),
+                        None => violations
+                            .push(format!("unknown Ship {} is in {:?}", id, cell.position)),
+                    }
+                }
+                if ours > 1 && !self.can_stack(cell.position) {
+                    violations.push(format!(
+                        "{} of our Ships are in {:?}: {:?}",
+                        ours, cell.position, cell.ships
+                    ));
+                }
+            }
+        }
+
+        for player in self.players.values() {
+            for id in &player.ship_ids {
+                match self.ships.get(id) {
+                    Some(ship) if ship.player_id == player.id => {}
+                    Some(ship) => violations.push(format!(
+                        "Ship {} of Player {} belongs to Player {}",
+                        id, player.id, ship.player_id
+                    )),
+                    None => violations
+                        .push(format!("Ship {} of Player {} doesn't exist", id, player.id)),
+                }
+            }
+        }
+
+        let legal = self.legal_commands();
+        for command in &self.commands {
+            if !legal.contains(command) {
+                violations.push(format!("illegal command {:?}", command));
+            }
+        }
+
+        violations
+    }
+
+    /// Return descriptions of everything wrong with a frame that has just been parsed.
+    ///
+    /// On top of `invariant_violations`, this checks that every Ship is in exactly one Player's
+    /// list, and that Dropoffs never move or disappear, given the Dropoffs on the previous turn.
+    /// Ships guessed by `spawn_ship` aren't in any list, so this only holds right after parsing.
+    pub fn frame_violations(&self, previous_dropoffs: &HashMap<DropoffId, Dropoff>) -> Vec<String> {
+        let mut violations = self.invariant_violations();
+
+        let mut lists = HashMap::new();
+        for player in self.players.values() {
+            for id in &player.ship_ids {
+                *lists.entry(*id).or_insert(0) += 1;
+            }
+        }
+        for id in self.ships.keys() {
+            match lists.get(id).cloned().unwrap_or(0) {
+                1 => {}
+                n => violations.push(format!("Ship {} is in {} Players' lists", id, n)),
+            }
+        }
+
+        for (id, previous) in previous_dropoffs {
+            match self.dropoffs.get(id) {
+                Some(dropoff) if dropoff.position != previous.position => violations.push(format!(
+                    "Dropoff {} moved from {:?} to {:?}",
+                    id, previous.position, dropoff.position
+                )),
+                Some(_) => {}
+                None => violations.push(format!("Dropoff {} has disappeared", id)),
+            }
+        }
+        for (id, dropoff) in &self.dropoffs {
+            if self.board[dropoff.position].structure != Some(Structure::Dropoff(*id)) {
+                violations.push(format!(
+                    "Dropoff {} is missing from {:?}",
+                    id, dropoff.position
+                ));
+            }
+        }
+
+        violations
+    }
+
+    /// Return an Error if a frame that has just been parsed is broken, when strict errors are on.
+    ///
+    /// Strict errors are turned on by the engine's constants, or by the `strict-checks` feature.
+    pub fn verify_frame(&self, previous_dropoffs: &HashMap<DropoffId, Dropoff>) -> Result<()> {
+        let strict_errors = constants::try_get().is_some_and(|c| c.strict_errors);
+        if !cfg!(feature = "strict-checks") && !strict_errors {
+            return Ok(());
+        }
+        let violations = self.frame_violations(previous_dropoffs);
+        if violations.is_empty() {
+            return Ok(());
+        }
+        let msg = format!(
+            "broken invariants on turn {}: {}",
+            self.turn,
+            violations.join("; ")
+        );
+        Err(Error::InvalidFrame(msg).into())
+    }
+
+    /// Panic if any invariant is broken, when the `strict-checks` feature is enabled.
+    ///
+    /// Without the feature this compiles to nothing, so it can be called freely.
+    #[cfg(feature = "strict-checks")]
+    pub fn check_invariants(&self) {
+        let violations = self.invariant_violations();
+        if !violations.is_empty() {
+            panic!(
+                "broken invariants on turn {}:\n{}",
+                self.turn,
+                violations.join("\n")
+            );
+        }
+    }
+
+    /// Panic if any invariant is broken, when the `strict-checks` feature is enabled.
+    ///
+    /// Without the feature this compiles to nothing, so it can be called freely.
+    #[cfg(not(feature = "strict-checks"))]
+    #[inline(always)]
+    pub fn check_invariants(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixtures, Direction, PlayerId, Position, Ship, ShipId};
+    use super::*;
+
+    #[test]
+    fn test_invariant_violations() {
+        let mut game = fixtures::game(". a . b Y");
+        assert!(game.invariant_violations().is_empty());
+
+        game.move_ship(ShipId::new(0), Direction::East);
+        assert!(game.invariant_violations().is_empty());
+        game.move_ship(ShipId::new(1), Direction::West);
+        assert_eq!(game.invariant_violations().len(), 1);
+
+        game.board.cells[0][0].ships.push(ShipId::new(7));
+        assert_eq!(game.invariant_violations().len(), 2);
+
+        // Our Ships can only stack on our structures during the end-game recall.
+        let mut game = fixtures::game(". a Y b .");
+        game.move_ship(ShipId::new(0), Direction::East);
+        game.move_ship(ShipId::new(1), Direction::West);
+        assert_eq!(game.invariant_violations().len(), 1);
+        game.navigation.allow_stacking = true;
+        assert!(game.invariant_violations().is_empty());
+    }
+
+    #[test]
+    fn test_frame_violations() {
+        let mut game = fixtures::game(". a Y D .");
+        let id = DropoffId::new(0);
+        let dropoff = Dropoff::new(id, PlayerId::new(0), Position::new(3, 0));
+        game.dropoffs.insert(id, dropoff);
+        let mut previous = game.dropoffs.clone();
+        assert!(game.frame_violations(&previous).is_empty());
+
+        previous.get_mut(&id).unwrap().position = Position::new(4, 0);
+        assert_eq!(game.frame_violations(&previous).len(), 1);
+        game.dropoffs.clear();
+        assert_eq!(game.frame_violations(&HashMap::new()).len(), 0);
+        assert_eq!(game.frame_violations(&previous).len(), 1);
+
+        let ship = Ship::new(ShipId::new(5), PlayerId::new(0), Position::new(0, 0), 0);
+        game.ships.insert(ship.id, ship);
+        game.board.cells[0][0].ships.push(ship.id);
+        assert_eq!(game.frame_violations(&HashMap::new()).len(), 1);
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use super::{Board, Dropoff, DropoffId, Game, Position, Ship, ShipId};
+
+/// Halite dropped in a Cell by colliding Ships.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct HalitePile {
+    /// Where the halite was dropped.
+    pub position: Position,
+    /// The amount of halite that was dropped.
+    pub halite: usize,
+    /// The halite in the Cell before the collision.
+    pub baseline: usize,
+    /// The turn on which the collision happened.
+    pub turn: usize,
+}
+
+/// Detect collisions since the previous turn and return where halite was dropped.
+///
+/// Ships that disappeared close to each other at the same time are assumed to have collided. The
+/// collision site is the Cell next to all of them with the largest increase in halite.
+pub fn detect(
+    game: &Game,
+    previous_ships: &HashMap<ShipId, Ship>,
+    previous_dropoffs: &HashMap<DropoffId, Dropoff>,
+    previous_board: &Board,
+) -> Vec<HalitePile> {
+    let vanished = game.vanished_ships(previous_ships, previous_dropoffs);
+
+    let mut piles: Vec<HalitePile> = Vec::new();
+    for (i, a) in vanished.iter().enumerate() {
+        for b in vanished.iter().skip(i + 1) {
+            if game.board.distance(a.position, b.position) > 2 {
+                continue;
+            }
+
+            // The site must be reachable by both Ships in a single move.
+            let mut candidates = vec![a.position];
+            candidates.extend(a.position.surrounding());
+            let site = candidates
+                .into_iter()
+                .map(|p| p.normalized(game.board.width, game.board.height))
+                .filter(|p| game.board.distance(*p, b.position) <= 1)
+                .max_by_key(|p| {
+                    game.board[*p]
+                        .halite
+                        .saturating_sub(previous_board[*p].halite)
+                });
+
+            if let Some(position) = site {
+                if piles.iter().any(|pile| pile.position == position) {
+                    continue;
+                }
+                let baseline = previous_board[position].halite;
+                let halite = game.board[position].halite.saturating_sub(baseline);
+                if halite > 0 {
+                    piles.push(HalitePile {
+                        position,
+                        halite,
+                        baseline,
+                        turn: game.turn,
+                    });
+                }
+            }
+        }
+    }
+    piles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixtures, PlayerId};
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let mut game = fixtures::empty_game(8, 3, Position::new(7, 2));
+        for (id, x) in [(0, 1), (1, 2), (2, 6)].iter() {
+            let ship = Ship::new(ShipId::new(*id), PlayerId::new(0), Position::new(*x, 1), 0);
+            game.ships.insert(ship.id, ship);
+        }
+        let previous_ships = game.ships.clone();
+        let previous_board = game.board.clone();
+
+        // All the Ships are gone, but only two of them were close enough to collide.
+        game.ships.clear();
+        game.board[Position::new(2, 1)].halite = 300;
+        game.board[Position::new(6, 1)].halite = 200;
+
+        let piles = detect(&game, &previous_ships, &HashMap::new(), &previous_board);
+        assert_eq!(
+            piles,
+            vec![HalitePile {
+                position: Position::new(2, 1),
+                halite: 300,
+                baseline: 0,
+                turn: game.turn,
+            }]
+        );
+    }
+}
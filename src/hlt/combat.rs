@@ -0,0 +1,133 @@
+use super::{constants, Direction, Game, Ship, ShipId};
+
+impl Game {
+    /// Return all our opponents' Ships, carrying the most halite first, then ordered by id.
+    pub fn enemy_ships(&self) -> Vec<&Ship> {
+        let mut ships: Vec<_> = self
+            .ships
+            .values()
+            .filter(|ship| ship.player_id != self.my_id)
+            .collect();
+        ships.sort_by_key(|ship| (!ship.halite, ship.id));
+        ships
+    }
+
+    /// Estimate the net value to us of deliberately colliding the given Ship with an enemy Ship.
+    ///
+    /// Both Ships are destroyed and their cargo is dropped at the enemy's Position. The chance that
+    /// we recover the dropped halite depends on how much closer that Position is to our deposit
+    /// points than to the enemy's structures. We always lose our Ship, and the enemy always loses
+    /// theirs, so the hulls cancel out and an even trade of empty Ships is worth nothing.
+    pub fn collision_trade(&self, ship_id: ShipId, enemy_id: ShipId) -> f64 {
+        let ship = self.ships[&ship_id];
+        let enemy = self.ships[&enemy_id];
+        let site = enemy.position;
+
+        let my_distance = self.board.distance(site, self.nearest_deposit(site)) as f64;
+        let their_distance = self
+            .enemy_structures()
+            .into_iter()
+            .filter(|(player_id, _)| *player_id == enemy.player_id)
+            .map(|(_, position)| self.board.distance(site, position))
+            .min()
+            .unwrap_or(0) as f64;
+        let recovery = if my_distance + their_distance > 0.0 {
+            their_distance / (my_distance + their_distance)
+        } else {
+            0.5
+        };
+
+        let cost = constants::get().new_entity_halite_cost as f64;
+        let dropped = (ship.halite + enemy.halite) as f64;
+        recovery * dropped + cost - (ship.halite as f64 + cost)
+    }
+
+    /// Estimate the value of sacrificing the given Ship to destroy an enemy Ship.
+    ///
+    /// This compares the expected value destroyed, the enemy's cargo and hull, against what we
+    /// lose, our cargo and the cost of replacing our Ship. Within reach, the chance of hitting the
+    /// enemy Ship is the best chance of it moving onto a Cell our Ship can get to next turn. Further
+    /// away, it is the chance of the enemy Ship staying still, halved for every extra turn.
+    pub fn sacrifice_value(&self, ship_id: ShipId, enemy_id: ShipId) -> f64 {
+        let ship = self.ships[&ship_id];
+        let enemy = self.ships[&enemy_id];
+        let cost = constants::get().new_entity_halite_cost as f64;
+
+        let distance = self.board.distance(ship.position, enemy.position);
+        let hit = if distance <= 2 {
+            Direction::all_with_still()
+                .into_iter()
+                .map(|d| {
+                    self.occupancy
+                        .ship_probability(self, &enemy, ship.position + d)
+                }).fold(0.0, f64::max)
+        } else {
+            let stay = self
+                .occupancy
+                .ship_probability(self, &enemy, enemy.position);
+            stay * 0.5f64.powi(distance as i32 - 2)
+        };
+
+        hit * (enemy.halite as f64 + cost) - (ship.halite as f64 + cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::super::occupancy::Occupancy;
+    use super::super::{fixtures, Player, PlayerId, Position, Shipyard, ShipyardId};
+    use super::*;
+
+    #[test]
+    fn test_collision_trade() {
+        let mut game = fixtures::empty_game(12, 1, Position::new(1, 0));
+        let (me, them, a) = (game.my_id, PlayerId::new(1), ShipId::new(0));
+        fixtures::add_ship(&mut game, Ship::new(a, me, Position::new(4, 0), 0));
+        let shipyard = Shipyard {
+            id: ShipyardId::new(1),
+            player_id: them,
+            position: Position::new(9, 0),
+        };
+        game.players.insert(them, Player::new(them, shipyard));
+        let enemy = Ship::new(ShipId::new(5), them, Position::new(3, 0), 0);
+        fixtures::add_ship(&mut game, enemy);
+
+        // Trading an empty Ship for an empty enemy Ship costs both sides a hull.
+        assert_eq!(game.collision_trade(a, enemy.id), 0.0);
+
+        // The dropped cargo is 2 turns from our Shipyard and 6 from theirs.
+        game.ships.get_mut(&enemy.id).unwrap().halite = 400;
+        assert_eq!(game.collision_trade(a, enemy.id), 300.0);
+    }
+
+    #[test]
+    fn test_sacrifice_value() {
+        let mut game = fixtures::game(". . a . . Y");
+        let enemy = Ship::new(ShipId(5), PlayerId(1), Position::new(3, 0), 900);
+        game.ships.insert(enemy.id, enemy);
+        game.board[enemy.position].add_ship(enemy.id);
+
+        // An enemy Ship that has always stayed still is likely to be hit.
+        let mut previous: HashMap<_, _> = game.ships.clone();
+        let mut occupancy = Occupancy::new(game.board.width, game.board.height);
+        for _ in 0..20 {
+            occupancy.record(&game, &previous);
+        }
+        game.occupancy = occupancy;
+        let staying = game.sacrifice_value(ShipId(0), enemy.id);
+        assert!(staying > 0.0);
+
+        // An enemy Ship that has always moved is likely to get away.
+        previous.get_mut(&enemy.id).unwrap().position = Position::new(4, 0);
+        let mut occupancy = Occupancy::new(game.board.width, game.board.height);
+        for _ in 0..20 {
+            occupancy.record(&game, &previous);
+        }
+        game.occupancy = occupancy;
+        let moving = game.sacrifice_value(ShipId(0), enemy.id);
+        assert!(moving < 0.0);
+        assert!(moving < staying);
+    }
+}
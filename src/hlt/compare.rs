@@ -0,0 +1,180 @@
+use std::fmt;
+
+use super::runner::{self, MatchResult, MatchRunner};
+use super::Result;
+
+/// The z-score for a 95% confidence interval.
+pub const Z_95: f64 = 1.96;
+
+/// The record of a candidate bot against a baseline bot over a series of matches.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Comparison {
+    /// The number of matches the candidate won.
+    pub wins: usize,
+    /// The number of matches the baseline won.
+    pub losses: usize,
+}
+
+impl Comparison {
+    /// Create a new empty Comparison.
+    pub fn new() -> Self {
+        Comparison::default()
+    }
+
+    /// Record the result of a match where the candidate was the given Player.
+    pub fn record(&mut self, result: &MatchResult, candidate: usize) -> Result<()> {
+        match result.winner() {
+            Some(winner) if winner == candidate => self.wins += 1,
+            Some(_) => self.losses += 1,
+            None => {
+                return Err(format_err!(
+                    "match on seed {} has no winner",
+                    result.map_seed
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the number of matches recorded.
+    pub fn games(&self) -> usize {
+        self.wins + self.losses
+    }
+
+    /// Return the fraction of matches the candidate won.
+    pub fn win_rate(&self) -> f64 {
+        if self.games() == 0 {
+            0.0
+        } else {
+            self.wins as f64 / self.games() as f64
+        }
+    }
+
+    /// Return the Wilson score interval for the candidate's true win rate, for the given z-score.
+    ///
+    /// Unlike the normal approximation, this stays between 0 and 1 and is sensible for the small
+    /// numbers of matches that are practical to run locally.
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        let n = self.games() as f64;
+        if n == 0.0 {
+            return (0.0, 1.0);
+        }
+        let p = self.win_rate();
+        let z2 = z * z;
+        let denominator = 1.0 + z2 / n;
+        let center = (p + z2 / (2.0 * n)) / denominator;
+        let margin = z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denominator;
+        ((center - margin).max(0.0), (center + margin).min(1.0))
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let (low, high) = self.confidence_interval(Z_95);
+        write!(
+            fmt,
+            "candidate won {} of {} matches: {:.1}% (95% CI {:.1}% to {:.1}%)",
+            self.wins,
+            self.games(),
+            self.win_rate() * 100.0,
+            low * 100.0,
+            high * 100.0
+        )
+    }
+}
+
+/// Play a candidate bot against a baseline bot on each of the given map seeds.
+///
+/// The runner sets everything but the bots and the seed, for example the map size. Each seed is
+/// played twice with the bots swapping places, so that neither gets the better starting position
+/// more often. The `report` function is called after every match, for showing progress.
+pub fn compare<I, F>(
+    runner: &MatchRunner,
+    candidate: &str,
+    baseline: &str,
+    seeds: I,
+    mut report: F,
+) -> Result<Comparison>
+where
+    I: IntoIterator<Item = u64>,
+    F: FnMut(&MatchResult, &Comparison),
+{
+    // The bots in each seat, and which seat the candidate is in.
+    let seatings = [(candidate, baseline, 0), (baseline, candidate, 1)];
+    let mut comparison = Comparison::new();
+    for seed in seeds {
+        for &(first, second, candidate_id) in &seatings {
+            let mut runner = runner.clone().seed(seed);
+            runner.bots = vec![first.to_string(), second.to_string()];
+            let result = runner.run()?;
+            comparison.record(&result, candidate_id)?;
+            report(&result, &comparison);
+        }
+    }
+    Ok(comparison)
+}
+
+/// Play a candidate bot against a baseline bot on map seeds 1 to `seeds` with the Halite binary
+/// from `runner::find_halite`.
+///
+/// Mini-games are played if `mini` is set, on maps of the given size if there is one. The `report`
+/// function is called after every match, like for `compare`.
+pub fn compare_locally<F>(
+    candidate: &str,
+    baseline: &str,
+    seeds: u64,
+    mini: bool,
+    size: Option<usize>,
+    report: F,
+) -> Result<Comparison>
+where
+    F: FnMut(&MatchResult, &Comparison),
+{
+    let mut runner = MatchRunner::new(runner::find_halite()?);
+    if mini {
+        runner = runner.mini_game();
+    }
+    if let Some(size) = size {
+        runner = runner.size(size, size);
+    }
+    compare(&runner, candidate, baseline, 1..=seeds, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(winner: usize) -> MatchResult {
+        MatchResult::from_json(&format!(
+            r#"{{
+                "map_width": 32, "map_height": 32, "map_seed": 1,
+                "stats": {{"{}": {{"rank": 1, "score": 5000}}, "{}": {{"rank": 2, "score": 10}}}}
+            }}"#,
+            winner,
+            1 - winner
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_comparison() {
+        let mut comparison = Comparison::new();
+        assert_eq!(comparison.confidence_interval(Z_95), (0.0, 1.0));
+        for i in 0..10 {
+            // The candidate plays as Player 0 on even matches.
+            let candidate = i % 2;
+            let winner = if i < 6 { candidate } else { 1 - candidate };
+            comparison.record(&result(winner), candidate).unwrap();
+        }
+        assert_eq!(comparison, Comparison { wins: 6, losses: 4 });
+        assert_eq!(comparison.win_rate(), 0.6);
+
+        let (low, high) = comparison.confidence_interval(Z_95);
+        assert!((low - 0.3127).abs() < 1e-3, "{}", low);
+        assert!((high - 0.8318).abs() < 1e-3, "{}", high);
+        assert_eq!(
+            comparison.to_string(),
+            "candidate won 6 of 10 matches: 60.0% (95% CI 31.3% to 83.2%)"
+        );
+    }
+}
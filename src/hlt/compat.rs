@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+
+use super::board::Cell;
+pub use super::entity::Entity;
+use super::{Board, Command, Direction, Game, Position, Result, Ship, ShipId, Shipyard};
+
+/// The official GameMap, which is the Board plus the Cells marked unsafe this turn.
+///
+/// This module has the names from the official Halite III Rust starter kit, so that code and
+/// tutorials written against it port over without rewrites. Import everything with
+/// `use hlt::compat::*` to get the official method names on the types of this crate. These are
+/// thin wrappers, so new code should use the methods they wrap instead.
+///
+/// Create a new GameMap each turn with `GameMap::new(&mut game.board)`, so that the Cells marked
+/// unsafe on the previous turn are forgotten.
+#[derive(Debug)]
+pub struct GameMap<'a> {
+    board: &'a mut Board,
+    /// The Ship that has claimed each Position for the turn with `GameMap::mark_unsafe`.
+    reserved: HashMap<Position, ShipId>,
+}
+
+impl<'a> GameMap<'a> {
+    /// Create a new GameMap with no Cells marked unsafe.
+    pub fn new(board: &'a mut Board) -> Self {
+        GameMap {
+            board,
+            reserved: HashMap::new(),
+        }
+    }
+
+    /// Mark the Cell at the given Position as claimed by the given Ship for this turn.
+    pub fn mark_unsafe(&mut self, position: &Position, ship: &Ship) {
+        let position = self.normalize(position);
+        self.reserved.insert(position, ship.id);
+    }
+
+    /// Return the Ship that has claimed the Cell at the given Position, if any.
+    pub fn reserved(&self, position: &Position) -> Option<ShipId> {
+        self.reserved.get(&self.normalize(position)).cloned()
+    }
+
+    /// Return the first Direction toward the destination that leads to a Cell that is unoccupied
+    /// and not marked unsafe, and mark that Cell unsafe, or `Direction::Still` if there isn't one.
+    ///
+    /// The Ship itself isn't moved, so give it the returned Direction with `Game::move_ship`.
+    pub fn naive_navigate(&mut self, ship: &Ship, destination: &Position) -> Direction {
+        for direction in self.get_unsafe_moves(&ship.position, destination) {
+            let target = ship.position + direction;
+            if !self[target].is_occupied() && self.reserved(&target).is_none() {
+                self.mark_unsafe(&target, ship);
+                return direction;
+            }
+        }
+        Direction::Still
+    }
+}
+
+impl<'a> Deref for GameMap<'a> {
+    type Target = Board;
+
+    fn deref(&self) -> &Board {
+        self.board
+    }
+}
+
+impl<'a> DerefMut for GameMap<'a> {
+    fn deref_mut(&mut self) -> &mut Board {
+        self.board
+    }
+}
+
+/// The official name for a Cell.
+pub type MapCell = Cell;
+
+/// The official GameMap methods that only need the Board.
+pub trait GameMapCompat {
+    /// Return the distance between two Positions. Wraps `Board::distance`.
+    fn calculate_distance(&self, source: &Position, target: &Position) -> usize;
+
+    /// Return the Position wrapped onto the Board. Wraps `Position::normalized`.
+    fn normalize(&self, position: &Position) -> Position;
+
+    /// Return the Cell at the given Position.
+    fn at_position(&self, position: &Position) -> &Cell;
+
+    /// Return the Cell at the given Position mutably.
+    fn at_position_mut(&mut self, position: &Position) -> &mut Cell;
+
+    /// Return the Cell the given Entity is in.
+    fn at_entity<E: Entity>(&self, entity: &E) -> &Cell;
+
+    /// Return the Directions that bring the source closer to the destination, without checking
+    /// whether they are safe. Wraps `Board::directions_toward`.
+    fn get_unsafe_moves(&self, source: &Position, destination: &Position) -> Vec<Direction>;
+}
+
+impl GameMapCompat for Board {
+    fn calculate_distance(&self, source: &Position, target: &Position) -> usize {
+        self.distance(*source, *target)
+    }
+
+    fn normalize(&self, position: &Position) -> Position {
+        position.normalized(self.width, self.height)
+    }
+
+    fn at_position(&self, position: &Position) -> &Cell {
+        &self[*position]
+    }
+
+    fn at_position_mut(&mut self, position: &Position) -> &mut Cell {
+        &mut self[*position]
+    }
+
+    fn at_entity<E: Entity>(&self, entity: &E) -> &Cell {
+        &self[entity.position()]
+    }
+
+    fn get_unsafe_moves(&self, source: &Position, destination: &Position) -> Vec<Direction> {
+        self.directions_toward(*source, *destination)
+    }
+}
+
+/// The official Position methods.
+pub trait PositionCompat {
+    /// Return the adjacent Position in the given Direction.
+    fn directional_offset(&self, direction: Direction) -> Position;
+
+    /// Return the four adjacent Positions. Wraps `Position::surrounding`.
+    fn get_surrounding_cardinals(&self) -> Vec<Position>;
+}
+
+impl PositionCompat for Position {
+    fn directional_offset(&self, direction: Direction) -> Position {
+        *self + direction
+    }
+
+    fn get_surrounding_cardinals(&self) -> Vec<Position> {
+        self.surrounding()
+    }
+}
+
+/// The official Direction methods.
+pub trait DirectionCompat {
+    /// Return the four cardinal Directions. Wraps `Direction::all`.
+    fn get_all_cardinals() -> Vec<Direction>;
+
+    /// Return the opposite Direction. Wraps `Direction::opposite`.
+    fn invert_direction(&self) -> Direction;
+}
+
+impl DirectionCompat for Direction {
+    fn get_all_cardinals() -> Vec<Direction> {
+        Direction::all()
+    }
+
+    fn invert_direction(&self) -> Direction {
+        self.opposite()
+    }
+}
+
+/// The official Ship methods, which create Commands instead of queueing them.
+pub trait ShipCompat {
+    /// Return a Command to move the Ship in the given Direction.
+    fn move_ship(&self, direction: Direction) -> Command;
+
+    /// Return a Command to keep the Ship still and collect halite.
+    fn stay_still(&self) -> Command;
+
+    /// Return a Command to convert the Ship into a Dropoff.
+    fn make_dropoff(&self) -> Command;
+}
+
+impl ShipCompat for Ship {
+    fn move_ship(&self, direction: Direction) -> Command {
+        Command::Move(self.id, direction).normalized()
+    }
+
+    fn stay_still(&self) -> Command {
+        Command::Collect(self.id)
+    }
+
+    fn make_dropoff(&self) -> Command {
+        Command::ConvertToDropoff(self.id)
+    }
+}
+
+/// The official Shipyard methods.
+pub trait ShipyardCompat {
+    /// Return a Command to spawn a Ship.
+    fn spawn(&self) -> Command;
+}
+
+impl ShipyardCompat for Shipyard {
+    fn spawn(&self) -> Command {
+        Command::Spawn
+    }
+}
+
+/// Queue the given Commands and end the turn, like the official `Game::end_turn`.
+///
+/// Commands already queued on the Game are kept, unless one of the given Commands replaces them.
+pub fn end_turn(game: &mut Game, commands: &[Command]) -> Result<()> {
+    for command in commands {
+        match *command {
+            Command::Spawn => game.spawn_ship(),
+            Command::ConvertToDropoff(id) => game.convert_to_dropoff(id),
+            Command::Collect(id) => game.collect_halite(id),
+            Command::Move(id, direction) => game.move_ship(id, direction),
+        }
+    }
+    game.end_turn()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures;
+    use super::*;
+
+    #[test]
+    fn test_official_tutorial() {
+        let mut game = fixtures::game(
+            "
+            .  .  .  .  .
+            .  a  b  .  .
+            .  .  Y  .  .
+            ",
+        );
+        let a = game.ships[&ShipId::new(0)];
+        let b = game.ships[&ShipId::new(1)];
+        let target = Position::new(3, 1);
+
+        let mut game_map = GameMap::new(&mut game.board);
+        assert_eq!(game_map.calculate_distance(&a.position, &target), 2);
+        assert_eq!(
+            game_map.normalize(&Position::new(-1, 0)),
+            Position::new(4, 0)
+        );
+        assert!(game_map.at_entity(&a).is_occupied());
+
+        // Ship b is in the way, so Ship a stays still, and then Ship b moves.
+        assert_eq!(game_map.naive_navigate(&a, &target), Direction::Still);
+        assert_eq!(game_map.naive_navigate(&b, &target), Direction::East);
+        assert_eq!(game_map.reserved(&target), Some(b.id));
+        assert!(game_map.at_entity(&b).has_ship(b.id));
+        assert!(!game_map.at_position(&target).is_occupied());
+        game.move_ship(b.id, Direction::East);
+        assert!(game.invariant_violations().is_empty());
+        assert_eq!(GameMap::new(&mut game.board).reserved(&target), None);
+
+        assert_eq!(
+            a.move_ship(Direction::East),
+            Command::Move(a.id, Direction::East)
+        );
+        assert_eq!(a.move_ship(Direction::Still), a.stay_still());
+        assert_eq!(Direction::get_all_cardinals().len(), 4);
+        assert_eq!(Direction::North.invert_direction(), Direction::South);
+        assert_eq!(
+            a.position.directional_offset(Direction::North),
+            Position::new(1, 0)
+        );
+        assert_eq!(a.owner(), game.my_id);
+    }
+}
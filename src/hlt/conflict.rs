@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use super::{Command, Game, Position, ShipId};
+
+/// A problem with the commands queued for our Ships, where they would destroy each other.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Conflict {
+    /// Several of our Ships would end up in the same Cell.
+    Collision {
+        position: Position,
+        ship_ids: Vec<ShipId>,
+    },
+    /// Two of our Ships would move through each other.
+    Swap(ShipId, ShipId),
+}
+
+impl Game {
+    /// Return where each of our Ships starts and ends this turn, given the queued commands.
+    ///
+    /// Ships that were told to move but can't afford to leave their Cell stay where they are.
+    /// Ships that are converting into a Dropoff are left out.
+    pub fn destinations(&self) -> HashMap<ShipId, (Position, Position)> {
+        let (width, height) = (self.board.width, self.board.height);
+        let mut destinations: HashMap<_, _> = self
+            .me()
+            .ship_ids
+            .iter()
+            .filter_map(|id| {
+                // `move_ship` has already moved the Ship, so work out where it came from.
+                let from = self.origin(*id)?;
+                let to = match self.command(*id) {
+                    Some(Command::Move(_, direction))
+                        if self
+                            .legal_actions(*id)
+                            .is_some_and(|actions| actions.can_move(direction)) =>
+                    {
+                        (from + direction).normalized(width, height)
+                    }
+                    _ => from,
+                };
+                Some((*id, (from, to)))
+            })
+            .collect();
+
+        for command in &self.commands {
+            if let Command::ConvertToDropoff(id) = *command {
+                destinations.remove(&id);
+            }
+        }
+
+        destinations
+    }
+
+    /// Return the Conflicts between the commands queued for our Ships.
+    ///
+    /// Ships are allowed to end up together on our own structures while stacking is allowed, see
+    /// `Game::can_stack`.
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let destinations = self.destinations();
+        let mut ship_ids: Vec<_> = destinations.keys().cloned().collect();
+        ship_ids.sort();
+
+        let mut arrivals: HashMap<Position, Vec<ShipId>> = HashMap::new();
+        for id in &ship_ids {
+            let (_, to) = destinations[id];
+            arrivals.entry(to).or_default().push(*id);
+        }
+
+        let mut conflicts: Vec<_> = arrivals
+            .into_iter()
+            .filter(|(position, ids)| ids.len() > 1 && !self.can_stack(*position))
+            .map(|(position, ship_ids)| Conflict::Collision { position, ship_ids })
+            .collect();
+        conflicts.sort_by_key(|conflict| match conflict {
+            Conflict::Collision { ship_ids, .. } => ship_ids[0],
+            Conflict::Swap(id, _) => *id,
+        });
+
+        for (i, a) in ship_ids.iter().enumerate() {
+            for b in &ship_ids[i + 1..] {
+                let (a_from, a_to) = destinations[a];
+                let (b_from, b_to) = destinations[b];
+                if a_from != a_to && a_from == b_to && b_from == a_to {
+                    conflicts.push(Conflict::Swap(*a, *b));
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    /// Fix the Conflicts between the commands queued for our Ships, and return what was fixed.
+    ///
+    /// Conflicts are fixed by making Ships stay where they are instead of moving. In a Collision,
+    /// Ships that were already there stay, or else the first Ship that was commanded keeps its
+    /// move. In a Swap, both Ships stay. This repeats until there are no Conflicts left, since
+    /// making a Ship stay can cause a new Collision, or until the remaining Conflicts can't be
+    /// fixed because the commands have already been streamed.
+    pub fn resolve_conflicts(&mut self) -> Vec<Conflict> {
+        let mut resolved = Vec::new();
+        let mut changed = true;
+        while changed {
+            let conflicts = self.conflicts();
+            changed = false;
+
+            let destinations = self.destinations();
+            for conflict in &conflicts {
+                warn!("Resolving conflict between our Ships: {:?}", conflict);
+                let cancel: Vec<_> = match conflict {
+                    Conflict::Collision { ship_ids, .. } => {
+                        let is_moving = |id: &ShipId| {
+                            let (from, to) = destinations[id];
+                            from != to
+                        };
+                        let movers: Vec<_> = self
+                            .commands
+                            .iter()
+                            .filter_map(|command| match *command {
+                                Command::Move(id, _) if ship_ids.contains(&id) => Some(id),
+                                _ => None,
+                            })
+                            .filter(is_moving)
+                            .collect();
+                        let skip = if movers.len() == ship_ids.len() { 1 } else { 0 };
+                        movers.into_iter().skip(skip).collect()
+                    }
+                    Conflict::Swap(a, b) => vec![*a, *b],
+                };
+                for ship_id in cancel {
+                    if self.is_revocable(ship_id) {
+                        self.collect_halite(ship_id);
+                        changed = true;
+                    }
+                }
+            }
+            if changed {
+                resolved.extend(conflicts);
+            }
+        }
+        resolved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixtures, Direction};
+    use super::*;
+
+    #[test]
+    fn test_resolve_collision() {
+        let mut game = fixtures::game(
+            "
+            .  .  .  .  .
+            .  a  .  b  .
+            .  .  c  .  .
+            .  .  Y  .  .
+            ",
+        );
+        let (a, b, c) = (ShipId::new(0), ShipId::new(1), ShipId::new(2));
+        game.move_ship(a, Direction::East);
+        game.move_ship(b, Direction::West);
+        game.move_ship(c, Direction::North);
+        assert_eq!(
+            game.conflicts(),
+            vec![Conflict::Collision {
+                position: Position::new(2, 1),
+                ship_ids: vec![a, b, c],
+            }]
+        );
+
+        let resolved = game.resolve_conflicts();
+        assert_eq!(resolved.len(), 1);
+        assert!(game.conflicts().is_empty());
+        assert_eq!(game.ships[&a].position, Position::new(2, 1));
+        assert_eq!(game.ships[&b].position, Position::new(3, 1));
+        assert_eq!(game.ships[&c].position, Position::new(2, 2));
+        assert!(game.board[Position::new(3, 1)].has_ship(b));
+        assert_eq!(
+            game.commands.to_vec(),
+            vec![
+                Command::Move(a, Direction::East),
+                Command::Collect(b),
+                Command::Collect(c),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_swap_and_cascade() {
+        let mut game = fixtures::game(
+            "
+            a  b  c  .
+            .  .  .  .
+            .  .  Y  .
+            ",
+        );
+        let (a, b, c) = (ShipId::new(0), ShipId::new(1), ShipId::new(2));
+        game.move_ship(a, Direction::East);
+        game.move_ship(b, Direction::West);
+        game.move_ship(c, Direction::West);
+        assert_eq!(game.conflicts().len(), 2);
+
+        game.resolve_conflicts();
+        assert!(game.conflicts().is_empty());
+        for (id, x) in &[(a, 0), (b, 1), (c, 2)] {
+            assert_eq!(game.ships[id].position, Position::new(*x, 0));
+        }
+
+        // Ships may only end up together on our structures while stacking is allowed.
+        let mut game = fixtures::game(". a Y b .");
+        game.move_ship(ShipId::new(0), Direction::East);
+        game.move_ship(ShipId::new(1), Direction::West);
+        assert_eq!(game.conflicts().len(), 1);
+        game.navigation.allow_stacking = true;
+        assert!(game.conflicts().is_empty());
+    }
+
+    #[test]
+    fn test_resolve_underfunded_collision() {
+        let mut game = fixtures::game("b a100 . . Y");
+        let (a, b) = (ShipId::new(0), ShipId::new(1));
+
+        // Ship a can't afford to leave, so Ship b would run into it.
+        game.move_ship(a, Direction::East);
+        game.move_ship(b, Direction::East);
+        let origin = Position::new(1, 0);
+        assert_eq!(game.destinations()[&a], (origin, origin));
+        assert_eq!(
+            game.conflicts(),
+            vec![Conflict::Collision {
+                position: origin,
+                ship_ids: vec![a, b],
+            }]
+        );
+
+        game.resolve_conflicts();
+        assert!(game.conflicts().is_empty());
+        assert_eq!(game.command(b), Some(Command::Collect(b)));
+        assert_eq!(game.ships[&b].position, Position::new(0, 0));
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::{Board, Position};
+
+/// The default number of turns that congestion is measured over.
+const DEFAULT_WINDOW: usize = 20;
+
+/// Congestion metrics for our structures, measured over a sliding window of turns.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Congestion {
+    /// The number of turns that congestion is measured over.
+    pub window: usize,
+    /// For each structure, the turns on which one of our Ships deposited there.
+    deposits: HashMap<Position, VecDeque<usize>>,
+    /// For each structure, the number of blocked adjacent Cells on each turn.
+    blocked: HashMap<Position, VecDeque<(usize, usize)>>,
+}
+
+impl Default for Congestion {
+    fn default() -> Self {
+        Congestion::new()
+    }
+}
+
+impl Congestion {
+    /// Create a new empty Congestion tracker.
+    pub fn new() -> Self {
+        Congestion {
+            window: DEFAULT_WINDOW,
+            deposits: HashMap::new(),
+            blocked: HashMap::new(),
+        }
+    }
+
+    /// Record a turn.
+    ///
+    /// `structures` are the Positions of our structures and `deposits` are the Positions where our
+    /// Ships deposited halite this turn.
+    pub fn record(
+        &mut self,
+        turn: usize,
+        board: &Board,
+        structures: &[Position],
+        deposits: &[Position],
+    ) {
+        for position in deposits {
+            self.deposits.entry(*position).or_default().push_back(turn);
+        }
+
+        for position in structures {
+            let blocked = position
+                .surrounding()
+                .into_iter()
+                .filter(|p| board[*p].is_occupied())
+                .count();
+            self.blocked
+                .entry(*position)
+                .or_default()
+                .push_back((turn, blocked));
+        }
+
+        // Forget about anything that is now outside of the window.
+        let oldest = (turn + 1).saturating_sub(self.window);
+        for turns in self.deposits.values_mut() {
+            while turns.front().is_some_and(|t| *t < oldest) {
+                turns.pop_front();
+            }
+        }
+        for turns in self.blocked.values_mut() {
+            while turns.front().is_some_and(|(t, _)| *t < oldest) {
+                turns.pop_front();
+            }
+        }
+    }
+
+    /// Return the number of deposits at the structure at the given Position within the window.
+    pub fn deposits(&self, position: Position) -> usize {
+        self.deposits.get(&position).map_or(0, |turns| turns.len())
+    }
+
+    /// Return the average fraction of Cells adjacent to the structure at the given Position that
+    /// were occupied by a Ship within the window.
+    pub fn blocked_ratio(&self, position: Position) -> f64 {
+        match self.blocked.get(&position) {
+            Some(turns) if !turns.is_empty() => {
+                let blocked: usize = turns.iter().map(|(_, blocked)| blocked).sum();
+                blocked as f64 / (4 * turns.len()) as f64
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_congestion_window() {
+        let mut board = Board::from_ascii(
+            "
+            . . . . .
+            . . a . .
+            . . Y . .
+            . . . . .
+            . . . . .
+            ",
+        ).unwrap();
+        let structure = Position::new(2, 2);
+
+        let mut congestion = Congestion::new();
+        congestion.window = 2;
+
+        congestion.record(0, &board, &[structure], &[structure]);
+        congestion.record(1, &board, &[structure], &[structure]);
+        assert_eq!(congestion.deposits(structure), 2);
+        assert_eq!(congestion.blocked_ratio(structure), 0.25);
+
+        board[Position::new(2, 1)].ships.clear();
+        congestion.record(2, &board, &[structure], &[]);
+        assert_eq!(congestion.deposits(structure), 1);
+        assert_eq!(congestion.blocked_ratio(structure), 0.125);
+    }
+}
@@ -1,4 +1,6 @@
-#[derive(Debug, Deserialize)]
+use std::sync::OnceLock;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Constants {
     #[serde(rename = "CAPTURE_ENABLED")]
     pub capture_enabled: bool,
@@ -90,7 +92,7 @@ pub struct Constants {
     pub game_seed: usize,
 }
 
-static mut CONSTANTS: Option<Constants> = None;
+static CONSTANTS: OnceLock<Constants> = OnceLock::new();
 
 /// Set the global constants.
 ///
@@ -99,15 +101,23 @@ static mut CONSTANTS: Option<Constants> = None;
 /// If this function is called a second time, this function will panic. The constants can only be
 /// set once!
 pub fn set(constants: Constants) {
-    unsafe {
-        if CONSTANTS.is_some() {
-            panic!("constants cannot be set a second time")
-        } else {
-            CONSTANTS = Some(constants);
-        }
+    if CONSTANTS.set(constants).is_err() {
+        panic!("constants cannot be set a second time")
     }
 }
 
+/// Return whether the global constants have been set.
+pub fn is_set() -> bool {
+    try_get().is_some()
+}
+
+/// Retrieve a reference to the global constants, if they have been set.
+///
+/// Use this in code that can run without talking to the engine, like tests and tools.
+pub fn try_get() -> Option<&'static Constants> {
+    CONSTANTS.get()
+}
+
 /// Retrieve a reference to the global constants.
 ///
 /// # Panics
@@ -115,12 +125,7 @@ pub fn set(constants: Constants) {
 /// If constants are accessed before being set (i.e. the Game has not started yet), then this
 /// function will panic.
 pub fn get() -> &'static Constants {
-    unsafe {
-        match CONSTANTS {
-            Some(ref constants) => constants,
-            None => panic!("constants were accessed before being set"),
-        }
-    }
+    try_get().expect("constants were accessed before being set")
 }
 
 #[cfg(test)]
@@ -162,7 +167,7 @@ mod tests {
                         "game_seed": 1539764156
                     }"#;
         let constants: Constants = serde_json::from_str(data).unwrap();
-        assert_eq!(constants.capture_enabled, false);
+        assert!(!constants.capture_enabled);
         assert_eq!(constants.capture_radius, 3);
         assert_eq!(constants.inspired_bonus_multiplier, 2.0);
     }
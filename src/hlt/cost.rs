@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use super::{constants, Command, Direction, Game, Position, ShipId};
+
+impl Command {
+    /// Return the halite this command will consume.
+    ///
+    /// Moving costs a fraction of the halite in the Cell the Ship leaves, out of its cargo.
+    /// Spawning costs a new Ship's worth of halite. Converting costs a Dropoff's worth of halite,
+    /// less the Ship's cargo and the halite in its Cell, which are credited towards it. Returns 0
+    /// for commands for Ships that don't exist.
+    pub fn cost(&self, game: &Game) -> usize {
+        let constants = constants::get();
+        match *self {
+            Command::Spawn => constants.new_entity_halite_cost,
+            Command::Collect(_) | Command::Move(_, Direction::Still) => 0,
+            Command::Move(ship_id, _) => match game.origin(ship_id) {
+                Some(position) => {
+                    let ratio = if game.inspiration.is_inspired(position) {
+                        constants.inspired_move_cost_ratio
+                    } else {
+                        constants.move_cost_ratio
+                    };
+                    game.board[position].halite / ratio
+                }
+                None => 0,
+            },
+            Command::ConvertToDropoff(ship_id) => match game.get_ship(ship_id) {
+                Some(ship) => {
+                    let credit = ship.halite + game.board[ship.position].halite;
+                    constants.dropoff_cost.saturating_sub(credit)
+                }
+                None => 0,
+            },
+        }
+    }
+}
+
+/// Which actions are legal for one of our Ships this turn, for example to mask a policy's outputs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LegalActions {
+    /// Whether each Direction is legal, in the order of `Direction::all_with_still`.
+    pub directions: [bool; 5],
+    /// Whether converting into a Dropoff is legal.
+    pub convert: bool,
+}
+
+impl LegalActions {
+    /// Return whether moving in the given Direction is legal.
+    pub fn can_move(&self, direction: Direction) -> bool {
+        let index = Direction::all_with_still()
+            .iter()
+            .position(|d| *d == direction)
+            .unwrap();
+        self.directions[index]
+    }
+
+    /// Return the legal actions as a mask of the Directions followed by conversion.
+    pub fn mask(&self) -> [bool; 6] {
+        let mut mask = [false; 6];
+        mask[..5].copy_from_slice(&self.directions);
+        mask[5] = self.convert;
+        mask
+    }
+}
+
+impl Game {
+    /// Return which actions are legal for the given Ship, or None if it isn't one of ours.
+    ///
+    /// Actions are judged from where the Ship starts the turn, ignoring any command it has already
+    /// been given. Staying still is always legal. Moving is legal if the Ship can pay to leave its
+    /// Cell. Converting is legal if there is no structure in the Cell and we can afford it, after
+    /// the Spawns and conversions already queued for other Ships.
+    pub fn legal_actions(&self, ship_id: ShipId) -> Option<LegalActions> {
+        if !self.me().ship_ids.contains(&ship_id) {
+            return None;
+        }
+        let constants = constants::get();
+        let ship = self.get_ship(ship_id)?;
+        let position = self.origin(ship_id)?;
+
+        let can_leave = ship.halite >= Command::Move(ship_id, Direction::North).cost(self);
+        let mut directions = [false; 5];
+        for (legal, direction) in directions.iter_mut().zip(Direction::all_with_still()) {
+            *legal = can_leave || direction == Direction::Still;
+        }
+
+        let committed: usize = self
+            .commands
+            .iter()
+            .filter(|command| match **command {
+                Command::Spawn => true,
+                Command::ConvertToDropoff(id) => id != ship_id,
+                _ => false,
+            }).map(|command| command.cost(self))
+            .sum();
+        let credit = ship.halite + self.board[position].halite;
+        let convert = !self.board[position].has_structure()
+            && self.me().halite.saturating_sub(committed) + credit >= constants.dropoff_cost;
+
+        Some(LegalActions {
+            directions,
+            convert,
+        })
+    }
+
+    /// Return where the given Ship starts this turn, undoing a queued move.
+    pub(crate) fn origin(&self, ship_id: ShipId) -> Option<Position> {
+        let ship = self.get_ship(ship_id)?;
+        let position = match self.command(ship_id) {
+            Some(Command::Move(_, direction)) => ship.position + direction.opposite(),
+            _ => ship.position,
+        };
+        Some(position.normalized(self.board.width, self.board.height))
+    }
+
+    /// Return whether we can afford all the given commands.
+    ///
+    /// Spawns and conversions are paid for from our halite, and moves from each Ship's cargo.
+    pub fn is_affordable(&self, commands: &[Command]) -> bool {
+        let mut spent = 0;
+        let mut burned = HashMap::new();
+        for command in commands {
+            let cost = command.cost(self);
+            match *command {
+                Command::Spawn | Command::ConvertToDropoff(_) => spent += cost,
+                Command::Move(ship_id, _) | Command::Collect(ship_id) => {
+                    *burned.entry(ship_id).or_insert(0) += cost;
+                }
+            }
+        }
+        spent <= self.me().halite
+            && burned
+                .into_iter()
+                .all(|(id, cost)| self.get_ship(id).is_none_or(|s| cost <= s.halite))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixtures, Position, ShipId};
+    use super::*;
+
+    #[test]
+    fn test_command_cost() {
+        let mut game = fixtures::game(". a500 b100 . Y");
+        let (a, b) = (ShipId::new(0), ShipId::new(1));
+        game.ships.get_mut(&b).unwrap().halite = 60;
+        let constants = constants::get();
+
+        let move_a = Command::Move(a, Direction::West);
+        assert_eq!(move_a.cost(&game), 500 / constants.move_cost_ratio);
+        game.move_ship(a, Direction::West);
+        assert_eq!(game.ships[&a].position, Position::new(0, 0));
+        assert_eq!(move_a.cost(&game), 500 / constants.move_cost_ratio);
+
+        assert_eq!(Command::Collect(a).cost(&game), 0);
+        assert_eq!(Command::Move(a, Direction::Still).cost(&game), 0);
+        assert_eq!(Command::Spawn.cost(&game), constants.new_entity_halite_cost);
+        assert_eq!(
+            Command::ConvertToDropoff(b).cost(&game),
+            constants.dropoff_cost - 160
+        );
+
+        let me = game.my_id;
+        game.players.get_mut(&me).unwrap().halite = constants.new_entity_halite_cost;
+        assert!(game.is_affordable(&[Command::Spawn, Command::Move(b, Direction::East)]));
+        assert!(!game.is_affordable(&[Command::Spawn, Command::ConvertToDropoff(b)]));
+        game.ships.get_mut(&b).unwrap().halite = 0;
+        assert!(!game.is_affordable(&[Command::Move(b, Direction::East)]));
+    }
+
+    #[test]
+    fn test_legal_actions() {
+        let mut game = fixtures::game(". a500 b100 Y");
+        let (a, b) = (ShipId::new(0), ShipId::new(1));
+        let constants = constants::get();
+        game.ships.get_mut(&a).unwrap().halite = 500 / constants.move_cost_ratio;
+
+        let actions = game.legal_actions(a).unwrap();
+        assert_eq!(actions.mask(), [true, true, true, true, true, false]);
+        game.move_ship(a, Direction::East);
+        assert_eq!(game.legal_actions(a), Some(actions));
+
+        game.ships.get_mut(&a).unwrap().halite -= 1;
+        let actions = game.legal_actions(a).unwrap();
+        assert!(!actions.can_move(Direction::West));
+        assert!(actions.can_move(Direction::Still));
+
+        let me = game.my_id;
+        game.players.get_mut(&me).unwrap().halite = constants.dropoff_cost - 100;
+        assert!(game.legal_actions(b).unwrap().convert);
+        game.convert_to_dropoff(a);
+        assert!(!game.legal_actions(b).unwrap().convert);
+        assert!(game.legal_actions(ShipId::new(2)).is_none());
+    }
+}
@@ -0,0 +1,170 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use super::{Board, Deadline, Direction, Grid, Position};
+
+/// The resolution of the turn estimates, in fractions of a turn.
+const RESOLUTION: f64 = 10.0;
+
+/// How many Cells to expand between checks of the Deadline.
+const DEADLINE_INTERVAL: usize = 64;
+
+/// A map of the cost of getting from each Cell to the closest of some target Positions.
+///
+/// The cost of a move is one turn plus the expected number of turns spent waiting for the next
+/// Cell to be free. The halite burnt along the cheapest route is tracked alongside.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostMap {
+    /// The expected turns from each Position, in tenths of a turn.
+    turns: Grid<usize>,
+    /// The halite burnt along the cheapest route from each Position.
+    burn: Grid<usize>,
+}
+
+impl CostMap {
+    /// Create a new CostMap where everything is a target.
+    pub fn new(width: isize, height: isize) -> Self {
+        CostMap {
+            turns: Grid::new(width, height, 0),
+            burn: Grid::new(width, height, 0),
+        }
+    }
+
+    /// Compute the CostMap to the given targets.
+    ///
+    /// `targets` are the target Positions with the expected turns spent waiting to get onto each,
+    /// `wait` returns the expected turns spent waiting to get onto a Position, and
+    /// `move_cost_ratio` is the fraction of a Cell's halite burnt moving off it.
+    pub fn compute<F>(
+        board: &Board,
+        targets: &[(Position, f64)],
+        wait: F,
+        move_cost_ratio: usize,
+    ) -> Self
+    where
+        F: Fn(Position) -> f64,
+    {
+        let cells = (board.width * board.height) as usize;
+        CostMap::compute_until(board, targets, wait, move_cost_ratio, cells, Deadline::never())
+    }
+
+    /// Compute the CostMap to the given targets, stopping early after expanding `max_cells` Cells
+    /// or if the Deadline passes.
+    ///
+    /// If it stops early, Cells that haven't been reached yet are estimated from their distance to
+    /// the closest target, ignoring waiting and burn.
+    pub fn compute_until<F>(
+        board: &Board,
+        targets: &[(Position, f64)],
+        wait: F,
+        move_cost_ratio: usize,
+        max_cells: usize,
+        deadline: Deadline,
+    ) -> Self
+    where
+        F: Fn(Position) -> f64,
+    {
+        let mut turns = Grid::new(board.width, board.height, usize::MAX);
+        let mut burn = Grid::new(board.width, board.height, 0);
+        let mut heap = BinaryHeap::new();
+
+        for (position, waiting) in targets {
+            let p = position.normalized(board.width, board.height);
+            let cost = (waiting * RESOLUTION).round() as usize;
+            if cost < turns[p] {
+                turns[p] = cost;
+                heap.push(Reverse((cost, p.x, p.y)));
+            }
+        }
+
+        // Dijkstra's algorithm outwards from the targets.
+        let mut expanded = 0;
+        while let Some(Reverse((cost, x, y))) = heap.pop() {
+            let here = Position::new(x, y);
+            if cost > turns[here] {
+                continue;
+            }
+            if expanded == max_cells {
+                break;
+            }
+            expanded += 1;
+            if expanded % DEADLINE_INTERVAL == 0 && deadline.is_expired() {
+                warn!("Ran out of time computing a CostMap");
+                break;
+            }
+            let step = ((1.0 + wait(here)) * RESOLUTION).round() as usize;
+
+            for direction in Direction::all() {
+                let next = (here + direction).normalized(board.width, board.height);
+                let next_cost = cost + step;
+                if next_cost < turns[next] {
+                    turns[next] = next_cost;
+                    burn[next] = burn[here] + board[next].halite / move_cost_ratio;
+                    heap.push(Reverse((next_cost, next.x, next.y)));
+                }
+            }
+        }
+
+        for row in &board.cells {
+            for cell in row {
+                if turns[cell.position] == usize::MAX {
+                    turns[cell.position] = targets
+                        .iter()
+                        .map(|(p, waiting)| {
+                            let distance = board.distance(cell.position, *p) as f64;
+                            ((distance + waiting) * RESOLUTION).round() as usize
+                        }).min()
+                        .unwrap_or(usize::MAX);
+                }
+            }
+        }
+
+        CostMap { turns, burn }
+    }
+
+    /// Return the expected number of turns to get from the given Position to the closest target.
+    pub fn turns(&self, position: Position) -> f64 {
+        self.turns[position] as f64 / RESOLUTION
+    }
+
+    /// Return the halite burnt getting from the given Position to the closest target.
+    pub fn burn(&self, position: Position) -> usize {
+        self.burn[position]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_cost_map_compute() {
+        let mut board = Board::new(10, 10);
+        board[Position::new(2, 0)].halite = 100;
+
+        let map = CostMap::compute(&board, &[(Position::new(0, 0), 0.0)], |_| 0.0, 10);
+        assert_eq!(map.turns(Position::new(0, 0)), 0.0);
+        assert_eq!(map.turns(Position::new(3, 0)), 3.0);
+        assert_eq!(map.turns(Position::new(9, 9)), 2.0);
+        assert_eq!(map.burn(Position::new(2, 0)), 10);
+
+        // Waiting makes it more expensive.
+        let map = CostMap::compute(&board, &[(Position::new(0, 0), 1.0)], |_| 0.5, 10);
+        assert_eq!(map.turns(Position::new(0, 0)), 1.0);
+        assert_eq!(map.turns(Position::new(2, 0)), 4.0);
+
+        // Running out of time falls back to the distance.
+        let targets = [(Position::new(0, 0), 0.0)];
+        let deadline = Deadline::after(Duration::from_secs(0));
+        let map = CostMap::compute_until(&board, &targets, |_| 0.5, 10, 100, deadline);
+        assert_eq!(map.turns(Position::new(0, 0)), 0.0);
+        assert_eq!(map.turns(Position::new(5, 5)), 10.0);
+
+        // So does running out of effort.
+        let map = CostMap::compute_until(&board, &targets, |_| 0.5, 10, 1, Deadline::never());
+        assert_eq!(map.turns(Position::new(1, 0)), 1.5);
+        assert_eq!(map.turns(Position::new(5, 5)), 10.0);
+    }
+}
@@ -0,0 +1,106 @@
+use std::ops::Index;
+
+use super::kernel::Kernel;
+use super::{Board, Grid, Position, Ship};
+
+/// The danger of a Cell occupied by an enemy Ship.
+const OCCUPIED_DANGER: f64 = 1.0;
+
+/// The danger of a Cell adjacent to an enemy Ship.
+const ADJACENT_DANGER: f64 = 0.5;
+
+/// The distance from our structures within which danger is reduced, because any halite dropped
+/// in a collision can easily be picked up by us.
+const HOME_RADIUS: usize = 2;
+
+/// How much danger is reduced near our structures.
+const HOME_FACTOR: f64 = 0.5;
+
+/// A grid of how dangerous each Cell is for our Ships.
+///
+/// Cells occupied by or adjacent to enemy Ships are dangerous. Enemy Ships carrying little halite
+/// have less to lose in a collision, so they are considered more dangerous.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DangerMap {
+    /// The danger for each Position on the map.
+    pub values: Grid<f64>,
+}
+
+impl DangerMap {
+    /// Create a new DangerMap with no danger.
+    pub fn new(width: isize, height: isize) -> Self {
+        DangerMap {
+            values: Grid::new(width, height, 0.0),
+        }
+    }
+
+    /// Compute the DangerMap for the given enemy Ships.
+    ///
+    /// `structures` are the Positions of our structures and `max_halite` is the carrying capacity
+    /// of a Ship.
+    pub fn compute(
+        board: &Board,
+        enemies: &[Ship],
+        structures: &[Position],
+        max_halite: usize,
+    ) -> Self {
+        let mut aggression = Grid::new(board.width, board.height, 0.0);
+        for enemy in enemies {
+            let cargo = enemy.halite as f64 / max_halite as f64;
+            aggression[enemy.position] += 1.0 - 0.5 * cargo.min(1.0);
+        }
+
+        // Spread the aggression of each enemy Ship over the Cells it threatens.
+        let kernel = Kernel::new(vec![
+            vec![0.0, ADJACENT_DANGER, 0.0],
+            vec![ADJACENT_DANGER, OCCUPIED_DANGER, ADJACENT_DANGER],
+            vec![0.0, ADJACENT_DANGER, 0.0],
+        ])
+        .unwrap();
+        let threat = kernel.apply(&aggression);
+
+        let values = Grid::like(board, |position| {
+            let danger = threat[position];
+            let near_home = danger > 0.0
+                && structures
+                    .iter()
+                    .any(|s| board.distance(position, *s) <= HOME_RADIUS);
+            if near_home {
+                danger * HOME_FACTOR
+            } else {
+                danger
+            }
+        });
+        DangerMap { values }
+    }
+}
+
+/// Allow indexing the DangerMap with Positions.
+impl Index<Position> for DangerMap {
+    type Output = f64;
+
+    fn index(&self, index: Position) -> &Self::Output {
+        &self.values[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_danger_map_compute() {
+        let board = Board::new(10, 10);
+        let empty = Ship::new(0.into(), 1.into(), Position::new(5, 5), 0);
+        let full = Ship::new(1.into(), 1.into(), Position::new(0, 0), 1000);
+
+        let map = DangerMap::compute(&board, &[empty, full], &[Position::new(9, 9)], 1000);
+        assert_eq!(map[Position::new(5, 5)], 1.0);
+        assert_eq!(map[Position::new(5, 4)], 0.5);
+        assert_eq!(map[Position::new(5, 3)], 0.0);
+
+        // A loaded Ship near our structure is much less dangerous.
+        assert_eq!(map[Position::new(0, 0)], 0.25);
+        assert_eq!(map[Position::new(10, 1)], 0.25);
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::VecDeque;
+
+use super::{Board, Position};
+
+/// The default number of turns that depletion is measured over.
+const DEFAULT_WINDOW: usize = 20;
+
+/// The default side of the square regions the Board is divided into.
+const DEFAULT_REGION_SIZE: isize = 8;
+
+/// How fast halite is disappearing from each region of the Board, measured over a sliding window
+/// of turns.
+///
+/// The Board is divided into square regions, and the total halite in each region is recorded every
+/// turn, so that the fleet can move on before a region is mined out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Depletion {
+    /// The number of turns that depletion is measured over.
+    pub window: usize,
+    /// The side of the square regions.
+    pub region_size: isize,
+    /// The width and height of the Board, once a turn has been recorded.
+    size: Option<(isize, isize)>,
+    /// The total halite in each region on each turn within the window.
+    history: VecDeque<(usize, Vec<Vec<usize>>)>,
+}
+
+impl Default for Depletion {
+    fn default() -> Self {
+        Depletion::new()
+    }
+}
+
+impl Depletion {
+    /// Create a new empty Depletion tracker.
+    pub fn new() -> Self {
+        Depletion {
+            window: DEFAULT_WINDOW,
+            region_size: DEFAULT_REGION_SIZE,
+            size: None,
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Return the region containing the given Position, as a column and row.
+    ///
+    /// The Position is wrapped onto the Board first, so Positions off the edge are in the region on
+    /// the other side.
+    pub fn region(&self, position: Position) -> (usize, usize) {
+        let position = match self.size {
+            Some((width, height)) => position.normalized(width, height),
+            None => position,
+        };
+        (
+            (position.x / self.region_size) as usize,
+            (position.y / self.region_size) as usize,
+        )
+    }
+
+    /// Record the halite in each region on a turn.
+    pub fn record(&mut self, turn: usize, board: &Board) {
+        self.size = Some((board.width, board.height));
+        let region_size = self.region_size as usize;
+        let columns = (board.width as usize).div_ceil(region_size);
+        let rows = (board.height as usize).div_ceil(region_size);
+        let mut totals = vec![vec![0; columns]; rows];
+        for row in &board.cells {
+            for cell in row {
+                let (x, y) = self.region(cell.position);
+                totals[y][x] += cell.halite;
+            }
+        }
+        self.history.push_back((turn, totals));
+
+        // Forget about anything that is now outside of the window.
+        let oldest = (turn + 1).saturating_sub(self.window);
+        while self.history.front().is_some_and(|(t, _)| *t < oldest) {
+            self.history.pop_front();
+        }
+    }
+
+    /// Return the halite remaining in the region containing the given Position.
+    pub fn halite(&self, position: Position) -> usize {
+        let (x, y) = self.region(position);
+        self.history.back().map_or(0, |(_, totals)| totals[y][x])
+    }
+
+    /// Return the average halite lost per turn within the window by the region containing the
+    /// given Position.
+    pub fn rate(&self, position: Position) -> f64 {
+        let (x, y) = self.region(position);
+        match (self.history.front(), self.history.back()) {
+            (Some((first, before)), Some((last, after))) if last > first => {
+                let lost = before[y][x] as f64 - after[y][x] as f64;
+                lost.max(0.0) / (last - first) as f64
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Return the number of turns until the region containing the given Position is mined out at
+    /// its current rate, or `None` if it isn't being mined.
+    pub fn turns_remaining(&self, position: Position) -> Option<f64> {
+        let rate = self.rate(position);
+        if rate > 0.0 {
+            Some(self.halite(position) as f64 / rate)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_depletion_window() {
+        let mut board = Board::new(16, 8);
+        board[Position::new(2, 2)].halite = 1000;
+        board[Position::new(10, 2)].halite = 1000;
+
+        let mut depletion = Depletion::new();
+        depletion.window = 3;
+        for turn in 0..5 {
+            depletion.record(turn, &board);
+            board[Position::new(2, 2)].halite -= 100;
+        }
+
+        let (mined, untouched) = (Position::new(7, 7), Position::new(8, 0));
+        assert_eq!(depletion.halite(mined), 600);
+        assert_eq!(depletion.rate(mined), 100.0);
+        assert_eq!(depletion.turns_remaining(mined), Some(6.0));
+        assert_eq!(depletion.rate(untouched), 0.0);
+        assert_eq!(depletion.turns_remaining(untouched), None);
+
+        // Positions off the edge wrap around onto the Board.
+        assert_eq!(depletion.region(Position::new(-1, -1)), (1, 0));
+        assert_eq!(depletion.halite(Position::new(16, 8)), 600);
+        assert_eq!(depletion.rate(Position::new(-1, 0)), 0.0);
+        assert_eq!(depletion.halite(Position::new(-14, -6)), 600);
+    }
+}
@@ -0,0 +1,255 @@
+use std::cmp;
+use std::collections::HashMap;
+
+use super::snapshot::Snapshot;
+use super::{constants, Command, Game, Position, Result, ShipId};
+
+/// A difference between what we predicted would happen on a turn and what the engine says did.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Divergence {
+    /// One of our Ships is somewhere else than predicted, or is gone.
+    ShipPosition {
+        ship_id: ShipId,
+        predicted: Position,
+        actual: Option<Position>,
+    },
+    /// One of our Ships is carrying a different amount of halite than predicted.
+    ShipHalite {
+        ship_id: ShipId,
+        predicted: usize,
+        actual: usize,
+    },
+    /// A Cell where one of our Ships collected has a different amount of halite than predicted.
+    CellHalite {
+        position: Position,
+        predicted: usize,
+        actual: usize,
+    },
+    /// We have a different amount of halite than predicted.
+    PlayerHalite { predicted: usize, actual: usize },
+}
+
+/// Where we expect our Ships and halite to be after a turn.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Prediction {
+    /// The Position and cargo of each of our Ships that still exists.
+    pub ships: HashMap<ShipId, (Position, usize)>,
+    /// The halite in the Cells our Ships collected from.
+    pub cells: HashMap<Position, usize>,
+    /// Our halite.
+    pub halite: usize,
+}
+
+/// A record of the state at the start of a turn and the commands we gave, which can be replayed
+/// to check our model of the engine against the next frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TurnRecord {
+    /// The state at the start of the turn.
+    pub snapshot: Snapshot,
+    /// The commands we gave.
+    pub commands: Vec<Command>,
+}
+
+impl TurnRecord {
+    /// Start recording a turn, before any commands have been given.
+    pub fn new(game: &Game) -> Self {
+        TurnRecord {
+            snapshot: game.snapshot(),
+            commands: Vec::new(),
+        }
+    }
+
+    /// Record the commands given this turn, just before the turn ends.
+    pub fn record_commands(&mut self, game: &Game) {
+        self.commands = game.commands.to_vec();
+    }
+
+    /// Restore the state at the start of the turn, apply the commands to it, and predict the
+    /// outcome.
+    ///
+    /// Opponents are ignored, so collisions with them show up as divergences.
+    pub fn replay(&self) -> Result<Prediction> {
+        let mut game = Game::from_snapshot(self.snapshot.clone())?;
+        let constants = constants::get();
+        let me = game.me().clone();
+
+        // Work out the costs on the Board before anything moves.
+        let costs: Vec<_> = self.commands.iter().map(|c| c.cost(&game)).collect();
+        let mut prediction = Prediction {
+            halite: me.halite,
+            ..Prediction::default()
+        };
+        for ship_id in &me.ship_ids {
+            let ship = &game.ships[ship_id];
+            prediction
+                .ships
+                .insert(*ship_id, (ship.position, ship.halite));
+        }
+
+        let mut collecting: Vec<_> = me.ship_ids.clone();
+        for (command, cost) in self.commands.iter().zip(costs) {
+            match command.normalized() {
+                Command::Spawn => {
+                    prediction.halite = prediction.halite.saturating_sub(cost);
+                }
+                Command::Collect(_) => {}
+                Command::Move(ship_id, direction) => {
+                    collecting.retain(|id| *id != ship_id);
+                    if let Some((position, cargo)) = prediction.ships.get_mut(&ship_id) {
+                        // Ships without enough halite to move stay still, without collecting.
+                        if *cargo >= cost {
+                            *position = (*position + direction)
+                                .normalized(game.board.width, game.board.height);
+                            *cargo -= cost;
+                        }
+                    }
+                }
+                Command::ConvertToDropoff(ship_id) => {
+                    collecting.retain(|id| *id != ship_id);
+                    if prediction.ships.remove(&ship_id).is_some() {
+                        prediction.halite = prediction.halite.saturating_sub(cost);
+                        let position = game.ships[&ship_id].position;
+                        game.plan_dropoff(position);
+                    }
+                }
+            }
+        }
+
+        for ship_id in collecting {
+            let (position, cargo) = prediction.ships[&ship_id];
+            let cell = game.board[position].halite;
+            if game.board[position].has_structure() {
+                continue;
+            }
+            let inspired = constants.inspiration_enabled && game.inspiration.is_inspired(position);
+            let ratio = if inspired {
+                constants.inspired_extract_ratio
+            } else {
+                constants.extract_ratio
+            };
+            let space = constants.max_halite.saturating_sub(cargo);
+            let mined = cmp::min(cell.div_ceil(ratio), space);
+            let bonus = if inspired {
+                (mined as f64 * constants.inspired_bonus_multiplier) as usize
+            } else {
+                0
+            };
+            prediction.cells.insert(position, cell - mined);
+            prediction.ships.get_mut(&ship_id).unwrap().1 =
+                cmp::min(cargo + mined + bonus, constants.max_halite);
+        }
+
+        // Ships on our structures deposit their cargo.
+        let deposits = game.deposit_positions();
+        for (position, cargo) in prediction.ships.values_mut() {
+            if deposits.contains(position) {
+                prediction.halite += *cargo;
+                *cargo = 0;
+            }
+        }
+
+        Ok(prediction)
+    }
+
+    /// Replay the turn and compare the prediction with the Game on the next turn.
+    pub fn diff(&self, next: &Game) -> Result<Vec<Divergence>> {
+        let prediction = self.replay()?;
+        let mut divergences = Vec::new();
+
+        let mut ship_ids: Vec<_> = prediction.ships.keys().cloned().collect();
+        ship_ids.sort();
+        for ship_id in ship_ids {
+            let (position, cargo) = prediction.ships[&ship_id];
+            let actual = next.get_ship(ship_id);
+            if actual.map(|s| s.position) != Some(position) {
+                divergences.push(Divergence::ShipPosition {
+                    ship_id,
+                    predicted: position,
+                    actual: actual.map(|s| s.position),
+                });
+            } else if actual.unwrap().halite != cargo {
+                divergences.push(Divergence::ShipHalite {
+                    ship_id,
+                    predicted: cargo,
+                    actual: actual.unwrap().halite,
+                });
+            }
+        }
+
+        let mut cells: Vec<_> = prediction.cells.into_iter().collect();
+        cells.sort_by_key(|(p, _)| (p.y, p.x));
+        for (position, halite) in cells {
+            let actual = next.board[position].halite;
+            if actual != halite {
+                divergences.push(Divergence::CellHalite {
+                    position,
+                    predicted: halite,
+                    actual,
+                });
+            }
+        }
+
+        if next.me().halite != prediction.halite {
+            divergences.push(Divergence::PlayerHalite {
+                predicted: prediction.halite,
+                actual: next.me().halite,
+            });
+        }
+
+        Ok(divergences)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixtures, Direction};
+    use super::*;
+
+    #[test]
+    fn test_turn_record_diff() {
+        let mut game = fixtures::game(". a100 b100 c Y");
+        let (a, b, c) = (ShipId::new(0), ShipId::new(1), ShipId::new(2));
+        game.ships.get_mut(&b).unwrap().halite = 5;
+        game.ships.get_mut(&c).unwrap().halite = 200;
+        let mut next = game.clone();
+
+        let mut record = TurnRecord::new(&game);
+        game.collect_halite(a);
+        game.move_ship(b, Direction::West);
+        game.move_ship(c, Direction::East);
+        record.record_commands(&game);
+
+        let prediction = record.replay().unwrap();
+        assert_eq!(prediction.ships[&a], (Position::new(1, 0), 25));
+        assert_eq!(prediction.ships[&b], (Position::new(2, 0), 5));
+        assert_eq!(prediction.ships[&c], (Position::new(4, 0), 0));
+        assert_eq!(prediction.cells[&Position::new(1, 0)], 75);
+        assert_eq!(prediction.halite, 200);
+
+        // Ship B is stuck, because it doesn't have enough halite to move.
+        next.ships.get_mut(&a).unwrap().halite = 25;
+        next.board[Position::new(1, 0)].halite = 75;
+        next.ships.get_mut(&c).unwrap().position = Position::new(4, 0);
+        next.ships.get_mut(&c).unwrap().halite = 0;
+        let me = next.my_id;
+        next.players.get_mut(&me).unwrap().halite = 200;
+        assert!(record.diff(&next).unwrap().is_empty());
+
+        next.ships.get_mut(&a).unwrap().halite = 30;
+        next.players.get_mut(&me).unwrap().halite = 0;
+        assert_eq!(
+            record.diff(&next).unwrap(),
+            vec![
+                Divergence::ShipHalite {
+                    ship_id: a,
+                    predicted: 25,
+                    actual: 30,
+                },
+                Divergence::PlayerHalite {
+                    predicted: 200,
+                    actual: 0,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,337 @@
+use std::cmp;
+use std::collections::HashMap;
+
+use super::constants::{self, Constants};
+use super::{Deadline, Game, Position, ShipId};
+
+/// The number of turns a Ship is assumed to spend mining before it has to return.
+///
+/// This is used when the surrounding halite is too sparse to estimate it.
+const MAX_MINING_TURNS: f64 = 50.0;
+
+/// How many extra turns of distance each Ship queued at a deposit point is considered to cost.
+const CONGESTION_WEIGHT: usize = 2;
+
+/// The distance from a deposit point within which our Ships are considered to be queueing.
+const QUEUE_RADIUS: usize = 2;
+
+/// The radius around a Ship used to estimate the halite it will mine next.
+const LOCAL_RADIUS: usize = 2;
+
+/// Return the cargo at which a Ship should return to maximize the halite delivered per turn.
+///
+/// A trip is modelled as mining the given `cells` richest first, moving on to the next Cell once
+/// it would yield more than twice the current one, and then travelling `distance` to the deposit
+/// point and back, losing `route_burn` halite on the way back.
+fn best_cargo(
+    distance: usize,
+    mut cells: Vec<usize>,
+    route_burn: usize,
+    capacity: usize,
+    extract_ratio: usize,
+    move_cost_ratio: usize,
+) -> usize {
+    cells.sort_by_key(|halite| !halite);
+    cells.push(0);
+
+    let travel_turns = 2 * distance;
+    let mut best = (0.0, capacity);
+    let mut cargo = 0;
+    let mut turns = 0;
+    let mut index = 0;
+
+    while cargo < capacity {
+        let current = cells[index];
+        let next = cells[index + 1];
+        turns += 1;
+        if index + 2 < cells.len() && 2 * current < next {
+            // Move on to the next Cell.
+            cargo -= cmp::min(cargo, current / move_cost_ratio);
+            index += 1;
+        } else {
+            let mined = cmp::min(current.div_ceil(extract_ratio), capacity - cargo);
+            if mined == 0 {
+                break;
+            }
+            cargo += mined;
+            cells[index] -= mined;
+        }
+
+        let rate = cargo.saturating_sub(route_burn) as f64 / (travel_turns + turns) as f64;
+        if rate > best.0 {
+            best = (rate, cargo);
+        }
+    }
+
+    best.1
+}
+
+impl Game {
+    /// Return the expected halite per turn that a single Ship brings back, given the distance to
+    /// its deposit point and the average halite of the Cells it mines.
+    fn trip_rate(constants: &Constants, distance: usize, average_halite: f64) -> f64 {
+        let capacity = constants.max_halite as f64;
+
+        // Each turn of mining collects 1/EXTRACT_RATIO of the Cell.
+        let per_turn = average_halite / constants.extract_ratio as f64;
+        let mining_turns = if per_turn > 0.0 {
+            (capacity / per_turn).min(MAX_MINING_TURNS)
+        } else {
+            MAX_MINING_TURNS
+        };
+
+        let cargo = (per_turn * mining_turns).min(capacity);
+        cargo / (2.0 * distance as f64 + mining_turns)
+    }
+
+    /// Return the cargo at which the given Ship should return to its closest deposit point.
+    ///
+    /// Ships far away from a deposit point should fill up, but Ships close to one deliver more
+    /// halite per turn by returning early when the local halite is sparse.
+    pub fn return_threshold(&self, ship_id: ShipId) -> usize {
+        let constants = constants::get();
+        let position = self.ships[&ship_id].position;
+        let target = self.nearest_deposit(position);
+
+        // The halite burnt moving along a direct route to the deposit point.
+        let mut route_burn = 0;
+        let mut current = position;
+        while let Some(direction) = self.board.directions_toward(current, target).first() {
+            route_burn += self.board[current].halite / constants.move_cost_ratio;
+            current = current + *direction;
+        }
+
+        let cells = self
+            .board
+            .positions_within(position, LOCAL_RADIUS)
+            .into_iter()
+            .map(|p| self.board[p].halite)
+            .collect();
+
+        best_cargo(
+            self.board.distance(position, target),
+            cells,
+            route_burn,
+            constants.max_halite,
+            constants.extract_ratio,
+            constants.move_cost_ratio,
+        )
+    }
+
+    /// Return whether the given Ship should return to its closest deposit point.
+    pub fn should_return(&self, ship_id: ShipId) -> bool {
+        self.ships[&ship_id].halite >= self.return_threshold(ship_id)
+    }
+
+    /// Estimate the number of turns it would take a Dropoff at the given Position to pay for
+    /// itself.
+    ///
+    /// Ships are assumed to mine within `radius` of the Position. Every one of our Ships that would
+    /// be closer to the new Dropoff than to its current deposit point contributes the difference in
+    /// halite per turn from the shorter round trips.
+    ///
+    /// Returns `None` if the Dropoff would never pay for itself.
+    pub fn dropoff_payback_turns(&self, position: Position, radius: usize) -> Option<usize> {
+        let constants = constants::get();
+
+        // The halite in the converted Cell is credited towards the cost.
+        let cost = constants
+            .dropoff_cost
+            .saturating_sub(self.board[position].halite) as f64;
+
+        let region_halite = self.board.halite_within(position, radius) as f64;
+        if region_halite < cost {
+            return None;
+        }
+        let region_size = self.board.positions_within(position, radius).len() as f64;
+        let average_halite = region_halite / region_size;
+
+        // The distance from the given Position to the closest existing deposit point.
+        let position = position.normalized(self.board.width, self.board.height);
+        let deposits: Vec<_> = self
+            .deposit_positions()
+            .into_iter()
+            .filter(|p| *p != position)
+            .collect();
+        let existing_distance = |from: Position| {
+            deposits
+                .iter()
+                .map(|p| self.board.distance(from, *p))
+                .min()
+                .unwrap_or(0)
+        };
+
+        let existing = existing_distance(position);
+        let saved = Game::trip_rate(constants, radius / 2, average_halite)
+            - Game::trip_rate(constants, existing + radius / 2, average_halite);
+
+        // Ships that would be closer to the new Dropoff would use it.
+        let users = self
+            .me()
+            .ship_ids
+            .iter()
+            .map(|id| self.ships[id].position)
+            .filter(|p| self.board.distance(*p, position) < existing_distance(*p))
+            .count();
+
+        let gain = saved * users as f64;
+        if gain <= 0.0 {
+            return None;
+        }
+        Some((cost / gain).ceil() as usize)
+    }
+
+    /// Return the number of our Ships queueing near the given deposit Position.
+    pub fn deposit_queue(&self, position: Position) -> usize {
+        self.me()
+            .ship_ids
+            .iter()
+            .filter(|id| self.board.distance(self.ships[id].position, position) <= QUEUE_RADIUS)
+            .count()
+    }
+
+    /// Assign each of the given returning Ships to a deposit point.
+    ///
+    /// Each deposit point is scored by the distance to it plus a penalty for every Ship already
+    /// queueing at it or assigned to it, so that returning Ships spread out over the available
+    /// deposit points instead of all jamming the Shipyard. The assignment is improved until the
+    /// Deadline for the current turn.
+    pub fn assign_deposits(&self, ship_ids: &[ShipId]) -> HashMap<ShipId, Position> {
+        timed!{ "assignment" => { self.assign_deposits_until(ship_ids, self.turn_deadline()) } }
+    }
+
+    /// Assign each of the given returning Ships to a deposit point, improving the assignment until
+    /// the Deadline passes.
+    ///
+    /// After the greedy assignment of `assign_deposits`, pairs of Ships swap deposit points while
+    /// that shortens their total distance. This keeps the number of Ships at each deposit point
+    /// the same. If the Deadline passes the best assignment so far is returned.
+    pub fn assign_deposits_until(
+        &self,
+        ship_ids: &[ShipId],
+        deadline: Deadline,
+    ) -> HashMap<ShipId, Position> {
+        let deposits = self.deposit_positions();
+        let mut queues: Vec<_> = deposits.iter().map(|p| self.deposit_queue(*p)).collect();
+
+        // Assign the closest Ships first, since they will arrive first.
+        let mut ship_ids = ship_ids.to_vec();
+        ship_ids.sort_by_key(|id| {
+            let position = self.ships[id].position;
+            self.board
+                .distance(position, self.nearest_deposit(position))
+        });
+
+        let mut assignments = HashMap::with_capacity(ship_ids.len());
+        for &ship_id in &ship_ids {
+            let position = self.ships[&ship_id].position;
+            let (index, _) = deposits
+                .iter()
+                .enumerate()
+                .min_by_key(|(i, p)| {
+                    self.board.distance(position, **p) + CONGESTION_WEIGHT * queues[*i]
+                })
+                .unwrap();
+            queues[index] += 1;
+            assignments.insert(ship_id, deposits[index]);
+        }
+
+        let distance = |id: &ShipId, p: Position| self.board.distance(self.ships[id].position, p);
+        let mut improved = true;
+        while improved && !deadline.is_expired() {
+            improved = false;
+            for (i, a) in ship_ids.iter().enumerate() {
+                for b in &ship_ids[i + 1..] {
+                    let (pa, pb) = (assignments[a], assignments[b]);
+                    if distance(a, pb) + distance(b, pa) < distance(a, pa) + distance(b, pb) {
+                        assignments.insert(*a, pb);
+                        assignments.insert(*b, pa);
+                        improved = true;
+                    }
+                }
+            }
+        }
+        assignments
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::super::{fixtures, Ship};
+    use super::*;
+
+    #[test]
+    fn test_best_cargo() {
+        let rich = vec![2000, 1500, 1200, 1000, 900];
+        let sparse = vec![100, 80, 60, 40, 20];
+
+        // Plenty of halite means a Ship far from home should fill up.
+        assert_eq!(best_cargo(20, rich.clone(), 0, 1000, 4, 10), 1000);
+
+        // Sparse halite close to home means returning early.
+        let close = best_cargo(1, sparse.clone(), 0, 1000, 4, 10);
+        let far = best_cargo(20, sparse.clone(), 0, 1000, 4, 10);
+        assert!(close < far);
+
+        // Burning more on the way back means staying out longer.
+        let burning = best_cargo(1, sparse, 50, 1000, 4, 10);
+        assert!(close <= burning);
+    }
+
+    #[test]
+    fn test_return_threshold() {
+        let mut game = fixtures::empty_game(32, 32, Position::new(0, 0));
+        let (me, near, far) = (game.my_id, ShipId::new(0), ShipId::new(1));
+        fixtures::add_ship(&mut game, Ship::new(near, me, Position::new(1, 0), 0));
+        fixtures::add_ship(&mut game, Ship::new(far, me, Position::new(16, 16), 0));
+        for ship_id in &[near, far] {
+            let position = game.ships[ship_id].position;
+            for p in game.board.positions_within(position, LOCAL_RADIUS) {
+                game.board[p].halite = 100;
+            }
+        }
+
+        // With the same sparse halite around them, a Ship next to home returns earlier.
+        let (close, distant) = (game.return_threshold(near), game.return_threshold(far));
+        assert!(close < distant);
+
+        // A Ship returns once its cargo reaches the threshold.
+        game.ships.get_mut(&near).unwrap().halite = close - 1;
+        assert!(!game.should_return(near));
+        game.ships.get_mut(&near).unwrap().halite = close;
+        assert!(game.should_return(near));
+        game.ships.get_mut(&far).unwrap().halite = close;
+        assert!(!game.should_return(far));
+    }
+
+    #[test]
+    fn test_assign_deposits() {
+        let mut game = fixtures::game(
+            "
+            .  a  .  .  .  .  .  .  .  .  .  .
+            Y  .  b  c  .  .  d  .  .  .  .  .
+            .  .  .  .  .  e  .  .  .  .  .  .
+            ",
+        );
+        game.plan_dropoff(Position::new(8, 1));
+        let ship_ids = game.me().ship_ids.clone();
+
+        let assignments = game.assign_deposits(&ship_ids);
+        assert_eq!(assignments.len(), ship_ids.len());
+        assert_eq!(assignments[&ShipId::new(0)], Position::new(0, 1));
+        assert_eq!(assignments[&ShipId::new(3)], Position::new(8, 1));
+        let distance = |id, p| game.board.distance(game.ships[&id].position, p);
+        for a in &ship_ids {
+            for b in &ship_ids {
+                let (pa, pb) = (assignments[a], assignments[b]);
+                assert!(distance(*a, pb) + distance(*b, pa) >= distance(*a, pa) + distance(*b, pb));
+            }
+        }
+
+        // Running out of time still assigns every Ship.
+        let deadline = Deadline::after(Duration::from_secs(0));
+        assert_eq!(game.assign_deposits_until(&ship_ids, deadline).len(), ship_ids.len());
+    }
+}
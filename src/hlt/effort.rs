@@ -0,0 +1,97 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// The default number of recent turns that are considered.
+const DEFAULT_WINDOW: usize = 5;
+
+/// The smallest fraction of the full effort that is ever asked for.
+const MIN_SCALE: f64 = 0.1;
+
+/// How much the effort shrinks after a slow turn.
+const SHRINK: f64 = 0.7;
+
+/// How much the effort grows after a fast turn.
+const GROW: f64 = 1.1;
+
+/// A controller that scales how much work subsystems do, based on how long recent turns took.
+///
+/// When recent turns ran long, search depths and sample counts shrink, and when there is plenty of
+/// time they grow back, so that turns stay under the time limit on large maps.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Effort {
+    /// The most a turn may take. Effort shrinks when turns get close to it.
+    pub target: Duration,
+    /// The number of recent turns that are considered.
+    pub window: usize,
+    /// The fraction of the full effort to use.
+    scale: f64,
+    /// How long the recent turns took.
+    recent: VecDeque<Duration>,
+}
+
+impl Effort {
+    /// Create a new Effort controller at full effort, that keeps turns well within the target.
+    pub fn new(target: Duration) -> Self {
+        Effort {
+            target,
+            window: DEFAULT_WINDOW,
+            scale: 1.0,
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Record how long a turn took, and adjust the effort.
+    pub fn record(&mut self, duration: Duration) {
+        self.recent.push_back(duration);
+        while self.recent.len() > self.window {
+            self.recent.pop_front();
+        }
+
+        let slowest = self.recent.iter().max().cloned().unwrap_or_default();
+        if slowest > self.target.mul_f64(0.8) {
+            self.scale = (self.scale * SHRINK).max(MIN_SCALE);
+        } else if slowest < self.target / 2 {
+            self.scale = (self.scale * GROW).min(1.0);
+        }
+    }
+
+    /// Return the fraction of the full effort to use.
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Scale the given full amount of work, like a search depth or sample count, never going
+    /// below one.
+    pub fn budget(&self, full: usize) -> usize {
+        ((full as f64 * self.scale).round() as usize).max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effort_scaling() {
+        let mut effort = Effort::new(Duration::from_millis(1500));
+        assert_eq!(effort.budget(100), 100);
+
+        effort.record(Duration::from_millis(1900));
+        assert_eq!(effort.budget(100), 70);
+        for _ in 0..20 {
+            effort.record(Duration::from_millis(1900));
+        }
+        assert_eq!(effort.budget(100), 10);
+        assert_eq!(effort.budget(5), 1);
+
+        // The slow turns have to leave the window before the effort grows back.
+        for _ in 1..effort.window {
+            effort.record(Duration::from_millis(100));
+        }
+        assert_eq!(effort.budget(100), 10);
+        for _ in 0..50 {
+            effort.record(Duration::from_millis(100));
+        }
+        assert_eq!(effort.scale(), 1.0);
+    }
+}
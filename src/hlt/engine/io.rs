@@ -0,0 +1,131 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::super::{Error, Result};
+
+/// A source of input from, and a sink for output to, the Halite engine.
+pub trait EngineIo {
+    /// Read a single line, including the trailing newline.
+    fn read_line(&mut self) -> Result<String>;
+
+    /// Write some output.
+    fn write(&mut self, output: &str);
+
+    /// Flush any buffered output.
+    fn flush(&mut self) -> Result<()>;
+}
+
+/// Talks to the Halite engine over stdin and stdout.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct StdIo;
+
+impl StdIo {
+    /// Create a new StdIo.
+    pub fn new() -> Self {
+        StdIo
+    }
+}
+
+impl EngineIo for StdIo {
+    fn read_line(&mut self) -> Result<String> {
+        let mut buffer = String::new();
+        let stdin = io::stdin();
+        stdin.lock().read_line(&mut buffer).map_err(Error::Io)?;
+        Ok(buffer)
+    }
+
+    fn write(&mut self, output: &str) {
+        print!("{}", output);
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        io::stdout().flush().map_err(Error::Io)?;
+        Ok(())
+    }
+}
+
+/// Reads hand-written input from a string and collects the output, for testing.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StringIo {
+    /// The lines that have not been read yet.
+    pub input: VecDeque<String>,
+    /// Everything that has been written.
+    pub output: String,
+}
+
+impl StringIo {
+    /// Create a new StringIo that reads the given input.
+    pub fn new(input: &str) -> Self {
+        StringIo {
+            input: input.lines().map(|line| format!("{}\n", line)).collect(),
+            output: String::new(),
+        }
+    }
+
+    /// Add more lines to the input.
+    pub fn push(&mut self, input: &str) {
+        self.input
+            .extend(input.lines().map(|line| format!("{}\n", line)));
+    }
+}
+
+impl EngineIo for StringIo {
+    /// Read the next line, or an empty string once the input runs out like stdin would.
+    fn read_line(&mut self) -> Result<String> {
+        Ok(self.input.pop_front().unwrap_or_default())
+    }
+
+    fn write(&mut self, output: &str) {
+        self.output.push_str(output);
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// An EngineIo that can be shared between threads, so that everything goes through one instance.
+///
+/// Clones share the same EngineIo. Each call locks it, so a Watchdog can send fallback commands
+/// through the same EngineIo as the Game, for example to get them into a transcript.
+#[derive(Debug, Default)]
+pub struct SharedIo<W> {
+    inner: Arc<Mutex<W>>,
+}
+
+impl<W> SharedIo<W> {
+    /// Share the given EngineIo.
+    pub fn new(io: W) -> Self {
+        SharedIo {
+            inner: Arc::new(Mutex::new(io)),
+        }
+    }
+
+    /// Lock the shared EngineIo, to use the rest of its methods.
+    pub fn lock(&self) -> MutexGuard<'_, W> {
+        self.inner.lock().unwrap()
+    }
+}
+
+impl<W> Clone for SharedIo<W> {
+    fn clone(&self) -> Self {
+        SharedIo {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<W: EngineIo> EngineIo for SharedIo<W> {
+    fn read_line(&mut self) -> Result<String> {
+        self.lock().read_line()
+    }
+
+    fn write(&mut self, output: &str) {
+        self.lock().write(output)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.lock().flush()
+    }
+}
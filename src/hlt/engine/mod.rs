@@ -1,93 +1,215 @@
+mod io;
 mod recv;
 mod send;
 
-use std::error::Error;
+use std::any;
 use std::fmt;
-use std::io;
 use std::str;
 
-use super::Result;
+use super::{Error, Result};
 
+pub use self::io::{EngineIo, SharedIo, StdIo, StringIo};
 use self::recv::FromEngine;
 use self::send::ToEngine;
 
-/// An Error that can occur when parsing.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub struct EngineParseError;
-
-/// Implement display so that it works with the failure crate.
-impl fmt::Display for EngineParseError {
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        fmt.write_str(self.description())
-    }
-}
-
-/// Add a description so that it looks nice when pretty printed.
-impl Error for EngineParseError {
-    fn description(&self) -> &str {
-        "unable to parse data from engine"
-    }
-}
-
 /// Struct to handle input and output to the Halite game engine.
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Engine {
+///
+/// The parsing is done here, the actual reading and writing is done by an EngineIo.
+pub struct Engine<'a> {
+    io: &'a mut dyn EngineIo,
     tokens: Vec<String>,
+    /// Whether any input has been read yet.
+    started: bool,
 }
 
-impl Engine {
-    /// Create a new Engine object.
-    pub fn new() -> Self {
-        Engine { tokens: Vec::new() }
+impl<'a> Engine<'a> {
+    /// Create a new Engine object that uses the given EngineIo.
+    pub fn new(io: &'a mut dyn EngineIo) -> Self {
+        Engine {
+            io,
+            tokens: Vec::new(),
+            started: false,
+        }
     }
 
-    /// Read a single line from stdin.
-    pub fn next_line(&self) -> Result<String> {
-        let mut buffer = String::new();
-        io::stdin().read_line(&mut buffer)?;
+    /// Read a single line.
+    ///
+    /// If the engine has closed its output before anything was read the Game is over, otherwise
+    /// the input ended in the middle of a frame.
+    pub fn next_line(&mut self) -> Result<String> {
+        let buffer = self.io.read_line()?;
+        if buffer.is_empty() {
+            let err = if self.started {
+                Error::ProtocolEof
+            } else {
+                Error::GameOver
+            };
+            return Err(err.into());
+        }
+        self.started = true;
         Ok(buffer)
     }
 
-    /// Read an arbitrary thing from stdin, as long as it implements FromStr.
-    pub fn next<T: str::FromStr>(&mut self) -> Result<T> {
-        while self.tokens.len() == 0 {
+    /// Skip the input up to the line holding the given turn, which is left to be read as normal.
+    ///
+    /// This throws away the rest of a malformed frame, so that the next one can be parsed. A line
+    /// only counts as the start of a frame if the line after it is the header of one of the given
+    /// number of Players, so that a count in the rest of the frame that happens to be the same as
+    /// the turn isn't mistaken for it.
+    pub fn skip_to_turn(&mut self, turn: usize, players: usize) -> Result<()> {
+        self.tokens.clear();
+        let mut next = None;
+        loop {
+            let line = match next.take() {
+                Some(line) => line,
+                None => self.next_line()?,
+            };
+            // Running out of input while skipping still means that the Game is over.
+            self.started = false;
+            if tokens_of(&line) == [turn] {
+                let header = self.next_line()?;
+                self.started = false;
+                let fields = tokens_of(&header);
+                if fields.len() == 4 && fields[0] < players {
+                    self.tokens.push(turn.to_string());
+                    self.tokens
+                        .extend(header.split_whitespace().map(|s| s.to_string()));
+                    self.started = true;
+                    return Ok(());
+                }
+                next = Some(header);
+            }
+            debug!("Skipping input from engine: {:?}", line.trim_end());
+        }
+    }
+
+    /// Read an arbitrary thing, as long as it implements FromStr.
+    pub fn next_token<T: str::FromStr>(&mut self) -> Result<T> {
+        while self.tokens.is_empty() {
             let buffer = self.next_line()?;
             let tokens = buffer.split_whitespace().map(|s| s.to_string());
             self.tokens.extend(tokens);
         }
-        let token = &self.tokens.remove(0);
-        Ok(T::from_str(token).map_err(|_| EngineParseError)?)
+        let token = self.tokens.remove(0);
+        T::from_str(&token).map_err(move |_| {
+            Error::Parse {
+                token,
+                expected: any::type_name::<T>(),
+            }.into()
+        })
     }
 
-    /// Print an arbitrary thing to stdout, as long as it implements Display.
-    pub fn print<T: fmt::Display>(&self, obj: T) {
-        print!("{}", obj);
+    /// Print an arbitrary thing, as long as it implements Display.
+    pub fn print<T: fmt::Display>(&mut self, obj: T) {
+        self.io.write(&obj.to_string());
     }
 
-    /// Read an arbitrary thing from stdin, as long as it implements FromEngine.
+    /// Read an arbitrary thing, as long as it implements FromEngine.
     pub fn recv<T: FromEngine>(&mut self) -> Result<T> {
         T::new_from_engine(self)
     }
 
-    /// Update an arbitrary thing from stdin, as long as it implements FromEngine.
+    /// Update an arbitrary thing, as long as it implements FromEngine.
     pub fn update<T: FromEngine>(&mut self, obj: &mut T) -> Result<()> {
         obj.update_from_engine(self)
     }
 
-    /// Write something to stdout, as long as it implements ToEngine.
-    pub fn send<T: ToEngine>(&self, obj: T) {
+    /// Write something, as long as it implements ToEngine.
+    pub fn send<T: ToEngine>(&mut self, obj: T) {
         obj.send_to_engine(self)
     }
 
-    /// Flush the stdout.
-    pub fn flush(&self) {
+    /// End the line and flush the output.
+    pub fn flush(&mut self) -> Result<()> {
         self.send("\n");
+        self.io.flush()
     }
 }
 
-impl Drop for Engine {
-    /// If the Engine still has tokens we want to panic, because something went wrong.
+/// Return the numbers on a line, or nothing if any of its tokens isn't a number.
+fn tokens_of(line: &str) -> Vec<usize> {
+    line.split_whitespace()
+        .map(|token| token.parse())
+        .collect::<::std::result::Result<_, _>>()
+        .unwrap_or_default()
+}
+
+impl<'a> Drop for Engine<'a> {
+    /// If the Engine still has tokens something went wrong, but don't take the bot down for it.
     fn drop(&mut self) {
-        assert!(self.tokens.is_empty());
+        if !self.tokens.is_empty() {
+            warn!("Discarding unparsed tokens from engine: {:?}", self.tokens);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Command, Direction, ShipId};
+    use super::*;
+
+    #[test]
+    fn test_engine_next_token() {
+        let mut io = StringIo::new("1 2\n\n  3 abc\n");
+        let mut engine = Engine::new(&mut io);
+        assert_eq!(engine.next_token::<usize>().unwrap(), 1);
+        assert_eq!(engine.next_token::<usize>().unwrap(), 2);
+        assert_eq!(engine.next_token::<usize>().unwrap(), 3);
+        match Error::find(&engine.next_token::<usize>().unwrap_err()) {
+            Some(Error::Parse { token, expected }) => {
+                assert_eq!(token, "abc");
+                assert_eq!(*expected, "usize");
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        match Error::find(&engine.next_token::<usize>().unwrap_err()) {
+            Some(Error::ProtocolEof) => {}
+            other => panic!("expected the end of input, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_engine_game_over() {
+        let mut io = StringIo::new("");
+        let err = Engine::new(&mut io).next_line().unwrap_err();
+        assert!(Error::is_game_over(&err));
+
+        let mut io = StringIo::new("1\n");
+        let mut engine = Engine::new(&mut io);
+        assert_eq!(engine.next_token::<usize>().unwrap(), 1);
+        assert!(!Error::is_game_over(&engine.next_token::<usize>().unwrap_err()));
+    }
+
+    #[test]
+    fn test_engine_skip_to_turn() {
+        let mut io = StringIo::new("0 1 0 4000\n1\n2 2 0\n3\n0 0 0 4000\n");
+        let mut engine = Engine::new(&mut io);
+        engine.skip_to_turn(3, 1).unwrap();
+        assert_eq!(engine.next_token::<usize>().unwrap(), 3);
+        assert_eq!(engine.next_token::<usize>().unwrap(), 0);
+
+        // A count of changed Cells that is the same as the turn isn't the start of a frame.
+        let mut io = StringIo::new("0 0 0 4000\n3\n2 2 0\n1 1 0\n3\n3\n0 0 0 4000\n");
+        let mut engine = Engine::new(&mut io);
+        engine.skip_to_turn(3, 1).unwrap();
+        assert_eq!(engine.next_token::<usize>().unwrap(), 3);
+        assert_eq!(engine.next_token::<usize>().unwrap(), 0);
+
+        let mut io = StringIo::new("0 1 0 4000\n");
+        let err = Engine::new(&mut io).skip_to_turn(3, 1).unwrap_err();
+        assert!(Error::is_game_over(&err));
+    }
+
+    #[test]
+    fn test_engine_send() {
+        let mut io = StringIo::new("");
+        {
+            let mut engine = Engine::new(&mut io);
+            engine.send(&Command::Spawn);
+            engine.send(&Command::Move(ShipId(3), Direction::North));
+            engine.send(&Command::Collect(ShipId(4)));
+            engine.flush().unwrap();
+        }
+        assert_eq!(io.output, "g m 3 n m 4 o \n");
     }
 }
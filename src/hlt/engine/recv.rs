@@ -1,15 +1,58 @@
 use std::collections::HashMap;
 use std::marker;
 use std::str;
+use std::time::Instant;
 
 use serde_json;
 
 use super::super::board::{Board, Position, Structure};
-use super::super::constants::Constants;
+use super::super::constants::{self, Constants};
+use super::super::timing;
 use super::super::{
-    Dropoff, DropoffId, Game, Player, PlayerId, Result, Ship, ShipId, Shipyard, ShipyardId,
+    Dropoff, DropoffId, Error, Game, Player, PlayerId, Result, Ship, ShipId, Shipyard, ShipyardId,
 };
-use super::{Engine, EngineParseError};
+use super::Engine;
+
+/// The largest Board dimension that is accepted from the Engine.
+const MAX_DIMENSION: isize = 256;
+
+/// The largest amount of halite that is accepted from the Engine.
+///
+/// This is far more than there could be in a real Game, but small enough that adding up all the
+/// halite on the Board can't overflow.
+const MAX_HALITE: usize = 1 << 32;
+
+/// Read an amount of halite from the Engine.
+fn recv_halite(engine: &mut Engine) -> Result<usize> {
+    let halite = engine.recv()?;
+    if halite > MAX_HALITE {
+        return Err(Error::InvalidFrame(format!("halite {} is too large", halite)).into());
+    }
+    Ok(halite)
+}
+
+/// Read a Position from the Engine, which must be on a Board of the given size.
+fn recv_position(engine: &mut Engine, width: isize, height: isize) -> Result<Position> {
+    let position: Position = engine.recv()?;
+    if position.x < 0 || position.x >= width || position.y < 0 || position.y >= height {
+        let msg = format!("{:?} is outside the {}x{} Board", position, width, height);
+        return Err(Error::InvalidFrame(msg).into());
+    }
+    Ok(position)
+}
+
+/// Read a count of things on a Board of the given size, of which there can't be more than Cells.
+fn recv_count(engine: &mut Engine, what: &str, width: isize, height: isize) -> Result<usize> {
+    let count = engine.recv()?;
+    if count > (width * height) as usize {
+        let msg = format!(
+            "{} {} don't fit on the {}x{} Board",
+            count, what, width, height
+        );
+        return Err(Error::InvalidFrame(msg).into());
+    }
+    Ok(count)
+}
 
 /// A trait for creating and updating types from the Halite engine.
 pub trait FromEngine
@@ -32,50 +75,50 @@ where
 {
     /// Anything that implements FromStr can be read easily from the Engine.
     fn new_from_engine(engine: &mut Engine) -> Result<Self> {
-        engine.next()
+        engine.next_token()
     }
 }
 
 impl FromEngine for DropoffId {
     /// Read a usize and then convert into a DropoffId.
     fn new_from_engine(engine: &mut Engine) -> Result<Self> {
-        Ok(engine.next::<usize>()?.into())
+        Ok(engine.next_token::<usize>()?.into())
     }
 }
 
 impl FromEngine for PlayerId {
     /// Read a usize and then convert into a PlayerId.
     fn new_from_engine(engine: &mut Engine) -> Result<Self> {
-        Ok(engine.next::<usize>()?.into())
+        Ok(engine.next_token::<usize>()?.into())
     }
 }
 
 impl FromEngine for ShipId {
     /// Read a usize and then convert into a ShipId.
     fn new_from_engine(engine: &mut Engine) -> Result<Self> {
-        Ok(engine.next::<usize>()?.into())
+        Ok(engine.next_token::<usize>()?.into())
     }
 }
 
 impl FromEngine for ShipyardId {
     /// Read a usize and then convert into a ShipyardId.
     fn new_from_engine(engine: &mut Engine) -> Result<Self> {
-        Ok(engine.next::<usize>()?.into())
+        Ok(engine.next_token::<usize>()?.into())
     }
 }
 
 impl FromEngine for Constants {
     /// Read Constants given as a single line of JSON.
     fn new_from_engine(engine: &mut Engine) -> Result<Self> {
-        Ok(serde_json::from_str(&engine.next_line()?)?)
+        Ok(serde_json::from_str(&engine.next_line()?).map_err(Error::Json)?)
     }
 }
 
 impl FromEngine for Position {
     /// Read an X Y point from the Engine.
     fn new_from_engine(engine: &mut Engine) -> Result<Self> {
-        let x = engine.next()?;
-        let y = engine.next()?;
+        let x = engine.next_token()?;
+        let y = engine.next_token()?;
         Ok(Position { x, y })
     }
 }
@@ -98,14 +141,18 @@ impl FromEngine for Player {
 impl FromEngine for Board {
     /// Read the entire game Board from the Engine.
     fn new_from_engine(engine: &mut Engine) -> Result<Self> {
-        let width = engine.next()?;
-        let height = engine.next()?;
+        let width = engine.next_token()?;
+        let height = engine.next_token()?;
+        if !(1..=MAX_DIMENSION).contains(&width) || !(1..=MAX_DIMENSION).contains(&height) {
+            let msg = format!("board size {}x{} is out of range", width, height);
+            return Err(Error::InvalidFrame(msg).into());
+        }
 
         let mut board = Board::new(width, height);
 
         for y in 0..height as usize {
             for x in 0..width as usize {
-                board.cells[y][x].halite = engine.next()?;
+                board.cells[y][x].halite = recv_halite(engine)?;
             }
         }
 
@@ -117,14 +164,15 @@ impl FromEngine for Board {
         // Clear all the ship locations.
         for y in 0..self.height as usize {
             for x in 0..self.width as usize {
-                self.cells[y][x].ship = None;
+                self.cells[y][x].ships.clear();
             }
         }
 
         // Read in the new halite values for each Cell.
-        for _ in 0..engine.recv()? {
-            let position = engine.recv()?;
-            let halite = engine.recv()?;
+        let (width, height) = (self.width, self.height);
+        for _ in 0..recv_count(engine, "Cell updates", width, height)? {
+            let position = recv_position(engine, width, height)?;
+            let halite = recv_halite(engine)?;
             self[position].halite = halite;
         }
 
@@ -137,6 +185,10 @@ impl FromEngine for Game {
     fn new_from_engine(engine: &mut Engine) -> Result<Self> {
         // Read the player count.
         let player_count = engine.recv()?;
+        if player_count > constants::get().max_players {
+            let msg = format!("{} players is more than the maximum", player_count);
+            return Err(Error::InvalidFrame(msg).into());
+        }
 
         // Read our player identifier.
         let my_id = engine.recv()?;
@@ -146,18 +198,44 @@ impl FromEngine for Game {
 
         for _ in 0..player_count {
             let player: Player = engine.recv()?;
-            players.insert(player.id, player);
+            let id = player.id;
+            if usize::from(id) >= player_count {
+                let msg = format!("player {} is out of range for {} players", id, player_count);
+                return Err(Error::InvalidFrame(msg).into());
+            }
+            if players.insert(id, player).is_some() {
+                return Err(Error::InvalidFrame(format!("duplicate player {}", id)).into());
+            }
+        }
+        if !players.contains_key(&my_id) {
+            return Err(Error::InvalidFrame(format!("our player {} is missing", my_id)).into());
         }
 
-        // Read in the Board.
-        let board = engine.recv()?;
+        // Read in the Board, and add the Shipyards to it.
+        let mut board: Board = engine.recv()?;
+        for player in players.values_mut() {
+            let shipyard = &mut player.shipyard;
+            if !board.contains(shipyard.position) {
+                let msg = format!("the Shipyard of player {} is outside the Board", player.id);
+                return Err(Error::InvalidFrame(msg).into());
+            }
+            board[shipyard.position].structure = Some(Structure::Shipyard(shipyard.id));
+        }
 
         Ok(Game::new(my_id, players, board))
     }
 
     /// Update the Game frame from the Engine.
     fn update_from_engine(&mut self, engine: &mut Engine) -> Result<()> {
-        self.turn = engine.recv()?;
+        let turn = engine.recv()?;
+        if turn <= self.turn {
+            let msg = format!("turn {} does not come after turn {}", turn, self.turn);
+            return Err(Error::InvalidFrame(msg).into());
+        }
+        self.turn = turn;
+        // Reading the turn waits for the engine, so only time parsing from here.
+        let started = Instant::now();
+        timing::start_turn(self.turn);
 
         // Clone the old Ships and Dropoffs from the previous frame.
         // This is so we can keep state in Ships and Dropoffs if we so wish.
@@ -168,22 +246,38 @@ impl FromEngine for Game {
         self.ships.clear();
         self.dropoffs.clear();
         self.commands.clear();
+        self.intents.clear();
+        self.streamed = 0;
+
+        let (width, height) = (self.board.width, self.board.height);
+        let mut seen = Vec::with_capacity(self.players.len());
 
         for _ in 0..self.players.len() {
             // Read the player ID and get the corresponding Player.
             let player_id = engine.recv()?;
-            let mut player = self.players.get_mut(&player_id).ok_or(EngineParseError)?;
+            if seen.contains(&player_id) {
+                let msg = format!("player {} appears twice", player_id);
+                return Err(Error::InvalidFrame(msg).into());
+            }
+            seen.push(player_id);
+            let player = self
+                .players
+                .get_mut(&player_id)
+                .ok_or_else(|| Error::InvalidFrame(format!("unknown player {}", player_id)))?;
 
-            let ship_count = engine.recv()?;
-            let dropoff_count = engine.recv()?;
-            player.halite = engine.recv()?;
+            let ship_count = recv_count(engine, "Ships", width, height)?;
+            let dropoff_count = recv_count(engine, "Dropoffs", width, height)?;
+            player.halite = recv_halite(engine)?;
 
             // Update the Ships.
             player.ship_ids.clear();
             for _ in 0..ship_count {
                 let id = engine.recv()?;
-                let position = engine.recv()?;
-                let halite = engine.recv()?;
+                let position = recv_position(engine, width, height)?;
+                let halite = recv_halite(engine)?;
+                if self.ships.contains_key(&id) {
+                    return Err(Error::InvalidFrame(format!("duplicate ship {}", id)).into());
+                }
 
                 let ship = if let Some(ship) = old_ships.get_mut(&id) {
                     ship.position = position;
@@ -201,7 +295,10 @@ impl FromEngine for Game {
             player.dropoff_ids.clear();
             for _ in 0..dropoff_count {
                 let id = engine.recv()?;
-                let position = engine.recv()?;
+                let position = recv_position(engine, width, height)?;
+                if self.dropoffs.contains_key(&id) {
+                    return Err(Error::InvalidFrame(format!("duplicate dropoff {}", id)).into());
+                }
 
                 let dropoff = if let Some(dropoff) = old_dropoffs.get_mut(&id) {
                     dropoff.position = position;
@@ -217,13 +314,22 @@ impl FromEngine for Game {
 
         engine.update(&mut self.board)?;
 
+        // Planned Dropoffs that have been built are now real Dropoffs.
+        let my_id = self.my_id;
+        let dropoffs = &self.dropoffs;
+        self.planned_dropoffs.retain(|position| {
+            !dropoffs
+                .values()
+                .any(|d| d.player_id == my_id && d.position == *position)
+        });
+
         for player in self.players.values() {
             let shipyard = &player.shipyard;
             self.board[shipyard.position].structure = Some(Structure::Shipyard(shipyard.id));
 
             for ship_id in &player.ship_ids {
                 let ship = &self.ships[ship_id];
-                self.board[ship.position].ship = Some(*ship_id);
+                self.board[ship.position].add_ship(*ship_id);
             }
 
             for dropoff_id in &player.dropoff_ids {
@@ -232,6 +338,7 @@ impl FromEngine for Game {
             }
         }
 
+        timing::record("parse", started.elapsed());
         Ok(())
     }
 }
@@ -1,13 +1,15 @@
 use std::fmt;
+use std::result;
+use std::str::FromStr;
 
 use super::Engine;
 
-use super::super::{Command, Direction};
+use super::super::{Command, Direction, Error, Result};
 
 /// A trait for sending types to the Halite engine.
 pub trait ToEngine {
     /// Send this object to the engine.
-    fn send_to_engine(&self, engine: &Engine);
+    fn send_to_engine(&self, engine: &mut Engine);
 }
 
 impl<T> ToEngine for T
@@ -15,30 +17,125 @@ where
     T: fmt::Display,
 {
     /// Automatically implement for types that implement Display.
-    fn send_to_engine(&self, engine: &Engine) {
+    fn send_to_engine(&self, engine: &mut Engine) {
         engine.print(format!("{}", self))
     }
 }
 
-impl<'a> ToEngine for &'a Command {
+impl ToEngine for &Command {
     /// Send a Command to the engine.
-    fn send_to_engine(&self, engine: &Engine) {
+    fn send_to_engine(&self, engine: &mut Engine) {
         engine.print(
             match self {
-                Command::Spawn => format!("g"),
+                Command::Spawn => "g".to_string(),
                 Command::ConvertToDropoff(ship_id) => format!("c {}", ship_id),
                 Command::Collect(ship_id) => format!("m {} o", ship_id),
-                Command::Move(ship_id, direction) => format!(
-                    "m {} {}",
-                    ship_id,
-                    match direction {
-                        Direction::North => 'n',
-                        Direction::East => 'e',
-                        Direction::South => 's',
-                        Direction::West => 'w',
-                    }
-                ),
+                Command::Move(ship_id, direction) => {
+                    format!("m {} {}", ship_id, direction.as_char())
+                }
             } + " ",
         )
     }
 }
+
+impl FromStr for Command {
+    type Err = Error;
+
+    /// Parse a single Command in the engine's syntax, for example `m 4 n`.
+    fn from_str(s: &str) -> result::Result<Self, Error> {
+        let tokens: Vec<_> = s.split_whitespace().collect();
+        let invalid = || Error::InvalidCommand(s.to_string());
+        let ship_id = |token: &str| -> result::Result<_, Error> {
+            token
+                .parse::<usize>()
+                .map(Into::into)
+                .map_err(|_| invalid())
+        };
+        match tokens.as_slice() {
+            ["g"] => Ok(Command::Spawn),
+            ["c", id] => Ok(Command::ConvertToDropoff(ship_id(id)?)),
+            ["m", id, "o"] => Ok(Command::Collect(ship_id(id)?)),
+            ["m", id, direction] => {
+                let direction = Direction::all_with_still()
+                    .into_iter()
+                    .find(|d| d.as_char().to_string() == *direction)
+                    .ok_or_else(invalid)?;
+                Ok(Command::Move(ship_id(id)?, direction))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl Command {
+    /// Parse a line of Commands as sent to the engine, for example `g m 3 n c 4`.
+    pub fn parse_line(line: &str) -> Result<Vec<Command>> {
+        let tokens: Vec<_> = line.split_whitespace().collect();
+        let mut commands = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            let length = match tokens[i] {
+                "g" => 1,
+                "c" => 2,
+                _ => 3,
+            };
+            let end = (i + length).min(tokens.len());
+            commands.push(tokens[i..end].join(" ").parse()?);
+            i = end;
+        }
+        Ok(commands)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::{ShipId, StringIo};
+    use super::*;
+
+    #[test]
+    fn test_command_from_str() {
+        assert_eq!("g".parse::<Command>().unwrap(), Command::Spawn);
+        assert_eq!(
+            "c 3".parse::<Command>().unwrap(),
+            Command::ConvertToDropoff(ShipId::new(3))
+        );
+        assert_eq!(
+            "m 4 n".parse::<Command>().unwrap(),
+            Command::Move(ShipId::new(4), Direction::North)
+        );
+        match "m 4 x".parse::<Command>() {
+            Err(Error::InvalidCommand(s)) => assert_eq!(s, "m 4 x"),
+            other => panic!("expected an invalid command, got {:?}", other),
+        }
+        assert!("m x o".parse::<Command>().is_err());
+        assert!("c 3 4".parse::<Command>().is_err());
+        assert!("".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_command_round_trip() {
+        let commands = vec![
+            Command::Spawn,
+            Command::ConvertToDropoff(ShipId::new(1)),
+            Command::Collect(ShipId::new(2)),
+            Command::Move(ShipId::new(3), Direction::West),
+            Command::Move(ShipId::new(4), Direction::South),
+        ];
+        let mut io = StringIo::new("");
+        {
+            let mut engine = Engine::new(&mut io);
+            for command in &commands {
+                engine.send(command);
+            }
+            engine.flush().unwrap();
+        }
+        assert_eq!(Command::parse_line(&io.output).unwrap(), commands);
+
+        let still = Command::Move(ShipId::new(5), Direction::Still);
+        let mut io = StringIo::new("");
+        Engine::new(&mut io).send(&still);
+        assert_eq!(io.output, "m 5 o ");
+        assert_eq!(io.output.parse::<Command>().unwrap(), still.normalized());
+        assert!(Command::parse_line("m 3").is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use super::{Board, Dropoff, PlayerId, Position, Ship, Shipyard};
+
+/// Something owned by a Player that has a Position on the Board.
+///
+/// This is implemented by Ships, Dropoffs and Shipyards, so that code that only cares about where
+/// things are and who owns them can be written once for all of them.
+pub trait Entity {
+    /// Return the Player that owns this.
+    fn owner(&self) -> PlayerId;
+
+    /// Return the Position of this.
+    fn position(&self) -> Position;
+
+    /// Return whether this is owned by the given Player.
+    fn is_owned_by(&self, player_id: PlayerId) -> bool {
+        self.owner() == player_id
+    }
+}
+
+impl Entity for Ship {
+    fn owner(&self) -> PlayerId {
+        self.player_id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+}
+
+impl Entity for Dropoff {
+    fn owner(&self) -> PlayerId {
+        self.player_id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+}
+
+impl Entity for Shipyard {
+    fn owner(&self) -> PlayerId {
+        self.player_id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+}
+
+impl Board {
+    /// Return the closest of the given Entities to a Position, the first one if there is a tie.
+    pub fn nearest_entity<'a, E, I>(&self, position: Position, entities: I) -> Option<&'a E>
+    where
+        E: Entity + 'a,
+        I: IntoIterator<Item = &'a E>,
+    {
+        let mut nearest = None;
+        for entity in entities {
+            let distance = self.distance(position, entity.position());
+            match nearest {
+                Some((best, _)) if best <= distance => {}
+                _ => nearest = Some((distance, entity)),
+            }
+        }
+        nearest.map(|(_, entity)| entity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixtures, ShipId};
+    use super::*;
+
+    #[test]
+    fn test_nearest_entity() {
+        let game = fixtures::game(". a . Y . b .");
+        let a = game.ships[&ShipId(0)];
+        let b = game.ships[&ShipId(1)];
+        let ships = [a, b];
+        let nearest = |x| game.board.nearest_entity(Position::new(x, 0), &ships);
+        assert_eq!(nearest(0), Some(&a));
+        assert_eq!(nearest(5), Some(&b));
+        assert_eq!(nearest(3), Some(&a));
+        assert_eq!(game.board.nearest_entity::<Ship, _>(a.position, &[]), None);
+
+        let shipyard = game.me().shipyard;
+        assert!(shipyard.is_owned_by(PlayerId(0)));
+        assert!(!shipyard.is_owned_by(PlayerId(1)));
+        assert!(ships.iter().all(|ship| ship.is_owned_by(shipyard.owner())));
+    }
+}
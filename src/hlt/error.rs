@@ -0,0 +1,150 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+use failure;
+use serde_json;
+
+use super::{Command, ShipId};
+
+/// The kinds of Error that can occur when talking to the Halite engine, or commanding Ships.
+///
+/// Functions still return the failure crate's Error, so to handle a particular kind of Error,
+/// use `Error::find` on it and match on the result.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading from or writing to the engine failed.
+    Io(io::Error),
+    /// A token from the engine could not be parsed as the expected type.
+    Parse {
+        /// The token that was read.
+        token: String,
+        /// The name of the type that was expected.
+        expected: &'static str,
+    },
+    /// The engine stopped sending input in the middle of the protocol.
+    ProtocolEof,
+    /// The engine stopped sending input before the next frame, so the Game is over.
+    GameOver,
+    /// The engine sent a line of JSON that could not be parsed.
+    Json(serde_json::Error),
+    /// The engine sent data that parsed, but doesn't make sense, for example a duplicate Ship.
+    InvalidFrame(String),
+    /// A Command in the engine's syntax could not be parsed.
+    InvalidCommand(String),
+    /// The frame for the given turn was malformed and has been skipped, see `Game::resync`.
+    SkippedFrame(usize),
+    /// A Ship that doesn't exist was looked up.
+    UnknownShip {
+        /// The identifier of the Ship.
+        ship_id: ShipId,
+        /// The turn it was looked up on.
+        turn: usize,
+        /// What the Ship was looked up for, for example "move".
+        context: &'static str,
+    },
+    /// A command was not queued, because the engine would reject it or it would break the Game.
+    IllegalCommand {
+        /// The command.
+        command: Command,
+        /// The turn it was given on.
+        turn: usize,
+        /// Why it is illegal.
+        reason: String,
+    },
+}
+
+impl Error {
+    /// Find the first Error of this type in the causal chain of a failure Error.
+    pub fn find(err: &failure::Error) -> Option<&Error> {
+        err.iter_chain().filter_map(|e| e.downcast_ref()).next()
+    }
+
+    /// Return whether a failure Error means that the Game is over.
+    pub fn is_game_over(err: &failure::Error) -> bool {
+        matches!(Error::find(err), Some(Error::GameOver))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(fmt, "unable to talk to engine: {}", e),
+            Error::Parse { token, expected } => write!(
+                fmt,
+                "unable to parse {:?} from engine as {}",
+                token, expected
+            ),
+            Error::ProtocolEof => fmt.write_str("unexpected end of input from engine"),
+            Error::GameOver => fmt.write_str("the engine has ended the game"),
+            Error::Json(e) => write!(fmt, "unable to parse JSON from engine: {}", e),
+            Error::InvalidFrame(msg) => write!(fmt, "invalid data from engine: {}", msg),
+            Error::InvalidCommand(s) => write!(fmt, "invalid command {:?}", s),
+            Error::SkippedFrame(turn) => {
+                write!(fmt, "skipped the malformed frame for turn {}", turn)
+            }
+            Error::UnknownShip {
+                ship_id,
+                turn,
+                context,
+            } => write!(
+                fmt,
+                "unable to {} ship {} on turn {}: it does not exist",
+                context, ship_id, turn
+            ),
+            Error::IllegalCommand {
+                command,
+                turn,
+                reason,
+            } => write!(
+                fmt,
+                "illegal command {:?} on turn {}: {}",
+                command, turn, reason
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_find() {
+        let err: failure::Error = Error::ProtocolEof.into();
+        match Error::find(&err) {
+            Some(Error::ProtocolEof) => {}
+            other => panic!("expected ProtocolEof, got {:?}", other),
+        }
+
+        let err = failure::Error::from(Error::InvalidFrame("oops".to_string())).context("turn 3");
+        let err: failure::Error = err.into();
+        assert_eq!(
+            Error::find(&err).unwrap().to_string(),
+            "invalid data from engine: oops"
+        );
+        assert!(Error::find(&format_err!("something else")).is_none());
+    }
+}
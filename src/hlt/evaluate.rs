@@ -0,0 +1,93 @@
+use super::{Game, Params, PlayerId};
+
+/// The number of terms in an evaluation.
+const TERMS: usize = 4;
+
+/// Scores a position for a Player as a weighted sum of simple terms.
+///
+/// The weights come from the `eval_*` Params, so that evaluation can be tuned with a params file
+/// instead of changing the code that searches over positions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Evaluator {
+    /// The weights of the banked halite, carried halite, Ships and Dropoffs, in that order.
+    pub weights: [f64; TERMS],
+}
+
+impl Default for Evaluator {
+    fn default() -> Self {
+        Evaluator::new(&Params::default())
+    }
+}
+
+impl Evaluator {
+    /// Create a new Evaluator with the weights from the given Params.
+    pub fn new(params: &Params) -> Self {
+        Evaluator {
+            weights: [
+                params.eval_banked,
+                params.eval_carried,
+                params.eval_ships,
+                params.eval_dropoffs,
+            ],
+        }
+    }
+
+    /// Return the unweighted terms for the given Player, in the same order as the weights.
+    ///
+    /// A Player that isn't in the Game has all terms zero.
+    pub fn terms(game: &Game, player_id: PlayerId) -> [f64; TERMS] {
+        match game.players.get(&player_id) {
+            Some(player) => {
+                let carried: usize = player.ships(game).map(|ship| ship.halite).sum();
+                [
+                    player.halite as f64,
+                    carried as f64,
+                    player.ship_ids.len() as f64,
+                    player.dropoff_ids.len() as f64,
+                ]
+            }
+            None => [0.0; TERMS],
+        }
+    }
+
+    /// Score the Game for the given Player, where higher is better.
+    pub fn score(&self, game: &Game, player_id: PlayerId) -> f64 {
+        let terms = Evaluator::terms(game, player_id);
+        self.weights
+            .iter()
+            .zip(terms.iter())
+            .map(|(weight, term)| weight * term)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures;
+    use super::*;
+
+    #[test]
+    fn test_evaluator_score() {
+        let mut game = fixtures::game(". a Y b .");
+        game.players.get_mut(&PlayerId(0)).unwrap().halite = 2000;
+        for ship in game.ships.values_mut() {
+            ship.halite = 100;
+        }
+        assert_eq!(
+            Evaluator::terms(&game, PlayerId(0)),
+            [2000.0, 200.0, 2.0, 0.0]
+        );
+
+        let evaluator = Evaluator {
+            weights: [1.0, 0.5, 300.0, 1000.0],
+        };
+        assert_eq!(evaluator.score(&game, PlayerId(0)), 2700.0);
+        assert_eq!(evaluator.score(&game, PlayerId(1)), 0.0);
+
+        let params = Params {
+            eval_ships: 0.0,
+            ..Params::default()
+        };
+        assert_eq!(Evaluator::new(&params).weights[2], 0.0);
+    }
+}
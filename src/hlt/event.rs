@@ -0,0 +1,28 @@
+use super::{DropoffId, PlayerId, Position, ShipId};
+
+/// Something notable that happened since the previous turn.
+///
+/// Events are collected in `Game::events` during `Game::update`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Event {
+    /// An opponent built a new Dropoff.
+    EnemyDropoffBuilt {
+        player_id: PlayerId,
+        dropoff_id: DropoffId,
+        position: Position,
+    },
+    /// Enemy Ships started camping on or next to our Shipyard.
+    ShipyardBlockaded,
+    /// Enemy Ships are no longer camping on or next to our Shipyard.
+    ShipyardBlockadeLifted,
+    /// An enemy Ship started camping on or next to our Shipyard.
+    ShipyardCamper(ShipId),
+    /// Ships collided at the given Position, dropping their halite.
+    Collision(Position),
+    /// A Ship was destroyed, by colliding with another Ship or being captured.
+    ShipDestroyed {
+        player_id: PlayerId,
+        ship_id: ShipId,
+        position: Position,
+    },
+}
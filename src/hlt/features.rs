@@ -0,0 +1,224 @@
+use super::constants;
+use super::{Game, Grid, Position};
+
+/// The names of the planes, in the order they are stored.
+pub const PLANES: [&str; 6] = [
+    "halite",
+    "my_ships",
+    "enemy_ships",
+    "my_structures",
+    "enemy_structures",
+    "cargo",
+];
+
+/// The largest Board in the Halite III engine, and the default size of the planes.
+const DEFAULT_SIZE: usize = 64;
+
+/// How the values in each plane are scaled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Normalization {
+    /// Amounts of halite are left as they are, and the other planes are 0 or 1.
+    Raw,
+    /// Amounts of halite are divided by the maximum a Ship can carry.
+    MaxHalite,
+    /// Every plane is shifted and scaled to a mean of 0 and a standard deviation of 1.
+    ///
+    /// A plane with every value the same becomes all zeros.
+    Standardize,
+}
+
+/// Options for extracting Features from a Game.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeatureOptions {
+    /// The number of cells along each side of every plane.
+    ///
+    /// The Board wraps around, so smaller Boards are tiled to fill the planes and larger Boards
+    /// are cropped.
+    pub size: usize,
+    /// Whether to put our Shipyard in the middle of the planes, rather than the Board's origin
+    /// in the corner.
+    pub centered: bool,
+    /// How to scale the values.
+    pub normalization: Normalization,
+}
+
+impl Default for FeatureOptions {
+    fn default() -> Self {
+        FeatureOptions {
+            size: DEFAULT_SIZE,
+            centered: true,
+            normalization: Normalization::MaxHalite,
+        }
+    }
+}
+
+/// The state of a Game as fixed-shape numeric planes, for use as the input to a model.
+///
+/// The values are stored plane by plane, then row by row, in the order of `PLANES`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Features {
+    /// The number of cells along each side of every plane.
+    pub size: usize,
+    /// The Position on the Board of the top left cell of the planes.
+    pub origin: Position,
+    /// The values.
+    pub values: Vec<f32>,
+}
+
+impl Features {
+    /// Extract the Features of the current turn of the Game, from our point of view.
+    pub fn extract(game: &Game, options: &FeatureOptions) -> Self {
+        let size = options.size;
+        let origin = if options.centered {
+            let half = (size / 2) as isize;
+            let shipyard = game.me().shipyard.position;
+            Position::new(shipyard.x - half, shipyard.y - half)
+        } else {
+            Position::new(0, 0)
+        };
+
+        // Mark everything on Grids the size of the Board, then sample them to fill the planes.
+        let board = &game.board;
+        let mut grids = vec![Grid::new(board.width, board.height, 0.0); PLANES.len()];
+        grids[0] = Grid::like(board, |p| board[p].halite as f32);
+        for ship in game.ships.values() {
+            let plane = if ship.player_id == game.my_id { 1 } else { 2 };
+            grids[plane][ship.position] = 1.0;
+            grids[5][ship.position] = ship.halite as f32;
+        }
+        for player in game.players.values() {
+            let plane = if player.id == game.my_id { 3 } else { 4 };
+            grids[plane][player.shipyard.position] = 1.0;
+        }
+        for dropoff in game.dropoffs.values() {
+            let plane = if dropoff.player_id == game.my_id {
+                3
+            } else {
+                4
+            };
+            grids[plane][dropoff.position] = 1.0;
+        }
+
+        let mut values = Vec::with_capacity(PLANES.len() * size * size);
+        for grid in &grids {
+            for y in 0..size as isize {
+                for x in 0..size as isize {
+                    values.push(grid[Position::new(origin.x + x, origin.y + y)]);
+                }
+            }
+        }
+
+        let mut features = Features {
+            size,
+            origin,
+            values,
+        };
+        features.normalize(options.normalization);
+        features
+    }
+
+    /// Scale the values in place.
+    fn normalize(&mut self, normalization: Normalization) {
+        let area = self.size * self.size;
+        match normalization {
+            Normalization::Raw => {}
+            Normalization::MaxHalite => {
+                let max_halite = constants::try_get().map_or(1000, |c| c.max_halite) as f32;
+                for &plane in &[0, 5] {
+                    for value in &mut self.values[plane * area..(plane + 1) * area] {
+                        *value /= max_halite;
+                    }
+                }
+            }
+            Normalization::Standardize => {
+                for plane in self.values.chunks_mut(area.max(1)) {
+                    let n = plane.len() as f32;
+                    let mean = plane.iter().sum::<f32>() / n;
+                    let variance = plane.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / n;
+                    let std = variance.sqrt();
+                    for value in plane.iter_mut() {
+                        *value = if std > 0.0 {
+                            (*value - mean) / std
+                        } else {
+                            0.0
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    /// Return the values of the plane with the given name, row by row.
+    pub fn plane(&self, name: &str) -> Option<&[f32]> {
+        let area = self.size * self.size;
+        PLANES
+            .iter()
+            .position(|&plane| plane == name)
+            .map(|i| &self.values[i * area..(i + 1) * area])
+    }
+
+    /// Return the value of the given plane at the given cell of the planes.
+    pub fn get(&self, name: &str, x: usize, y: usize) -> Option<f32> {
+        if x < self.size && y < self.size {
+            self.plane(name).map(|plane| plane[y * self.size + x])
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures;
+    use super::*;
+
+    #[test]
+    fn test_features_extract() {
+        let mut game = fixtures::game("a Y 7 .");
+        game.ships.values_mut().next().unwrap().halite = 500;
+        let options = FeatureOptions {
+            size: 6,
+            centered: false,
+            normalization: Normalization::Raw,
+        };
+        let features = Features::extract(&game, &options);
+        assert_eq!(features.values.len(), 6 * 6 * 6);
+        assert_eq!(
+            &features.plane("my_ships").unwrap()[..6],
+            &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0]
+        );
+        assert_eq!(features.get("cargo", 4, 5), Some(500.0));
+        assert_eq!(features.get("my_structures", 1, 3), Some(1.0));
+        assert_eq!(features.get("enemy_ships", 0, 0), Some(0.0));
+        assert_eq!(features.get("halite", 6, 0), None);
+
+        let options = FeatureOptions {
+            size: 4,
+            centered: true,
+            normalization: Normalization::MaxHalite,
+        };
+        let features = Features::extract(&game, &options);
+        assert_eq!(features.origin, Position::new(-1, -2));
+        assert_eq!(features.get("my_structures", 2, 2), Some(1.0));
+        assert_eq!(features.get("cargo", 1, 2), Some(0.5));
+    }
+
+    #[test]
+    fn test_features_standardize() {
+        let game = fixtures::game("a Y 7 .");
+        let options = FeatureOptions {
+            size: 4,
+            centered: false,
+            normalization: Normalization::Standardize,
+        };
+        let features = Features::extract(&game, &options);
+        let ships = features.plane("my_ships").unwrap();
+        let mean = ships.iter().sum::<f32>() / ships.len() as f32;
+        assert!(mean.abs() < 1e-6);
+        assert!(features
+            .plane("enemy_ships")
+            .unwrap()
+            .iter()
+            .all(|&v| v == 0.0));
+    }
+}
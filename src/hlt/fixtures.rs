@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::sync::Once;
+
+use serde_json;
+
+use super::board::Structure;
+use super::constants::{self, Constants};
+use super::{
+    Board, EngineIo, Game, Player, PlayerId, Position, Ship, Shipyard, ShipyardId, StringIo,
+};
+
+/// Frames recorded from the Halite engine, by name.
+///
+/// Each fixture is an init frame followed by a number of update frames. They all use the same
+/// constants, because the constants can only be set once.
+pub const FIXTURES: &[(&str, &str)] = &[
+    (
+        "2p-32x32",
+        include_str!("../../tests/fixtures/2p-32x32.txt"),
+    ),
+    (
+        "2p-48x48",
+        include_str!("../../tests/fixtures/2p-48x48.txt"),
+    ),
+    (
+        "4p-40x40",
+        include_str!("../../tests/fixtures/4p-40x40.txt"),
+    ),
+];
+
+/// An uncompressed replay of a short Game on a 4x4 Board.
+pub const REPLAY: &str = r#"{
+    "ENGINE_VERSION": "1.1.6",
+    "REPLAY_FILE_VERSION": 3,
+    "GAME_CONSTANTS": {
+        "CAPTURE_ENABLED": false, "CAPTURE_RADIUS": 3, "DEFAULT_MAP_HEIGHT": 4,
+        "DEFAULT_MAP_WIDTH": 4, "DROPOFF_COST": 4000, "DROPOFF_PENALTY_RATIO": 4,
+        "EXTRACT_RATIO": 4, "FACTOR_EXP_1": 2.0, "FACTOR_EXP_2": 2.0, "INITIAL_ENERGY": 5000,
+        "INSPIRATION_ENABLED": true, "INSPIRATION_RADIUS": 4, "INSPIRATION_SHIP_COUNT": 2,
+        "INSPIRED_BONUS_MULTIPLIER": 2.0, "INSPIRED_EXTRACT_RATIO": 4,
+        "INSPIRED_MOVE_COST_RATIO": 10, "MAX_CELL_PRODUCTION": 1000, "MAX_ENERGY": 1000,
+        "MAX_PLAYERS": 16, "MAX_TURNS": 400, "MAX_TURN_THRESHOLD": 64,
+        "MIN_CELL_PRODUCTION": 900, "MIN_TURNS": 400, "MIN_TURN_THRESHOLD": 32,
+        "MOVE_COST_RATIO": 10, "NEW_ENTITY_ENERGY_COST": 1000, "PERSISTENCE": 0.7,
+        "SHIPS_ABOVE_FOR_CAPTURE": 3, "STRICT_ERRORS": false, "game_seed": 42
+    },
+    "number_of_players": 2,
+    "players": [
+        {"player_id": 0, "name": "MyBot", "energy": 5000, "entities": [],
+         "factory_location": {"x": 1, "y": 1}},
+        {"player_id": 1, "name": "Other", "energy": 5000, "entities": [],
+         "factory_location": {"x": 3, "y": 3}}
+    ],
+    "production_map": {
+        "width": 4, "height": 4, "map_generator": "basic",
+        "grid": [
+            [{"energy": 10}, {"energy": 20}, {"energy": 30}, {"energy": 40}],
+            [{"energy": 10}, {"energy": 0}, {"energy": 30}, {"energy": 40}],
+            [{"energy": 10}, {"energy": 20}, {"energy": 30}, {"energy": 40}],
+            [{"energy": 10}, {"energy": 20}, {"energy": 30}, {"energy": 0}]
+        ]
+    },
+    "full_frames": [
+        {"cells": [], "deposited": {}, "energy": {}, "entities": {}, "events": [],
+         "moves": {}},
+        {"cells": [], "energy": {"0": 4000, "1": 4000},
+         "entities": {"0": {"0": {"x": 1, "y": 1, "energy": 0, "is_inspired": false}},
+                      "1": {"1": {"x": 3, "y": 3, "energy": 0, "is_inspired": false}}},
+         "events": [{"type": "spawn", "id": 0, "owner_id": 0, "energy": 0,
+                     "location": {"x": 1, "y": 1}}],
+         "moves": {"0": [{"type": "g"}], "1": [{"type": "g"}]}},
+        {"cells": [{"x": 2, "y": 1, "production": 0}], "energy": {"0": 10},
+         "entities": {"0": {}, "1": {"1": {"x": 3, "y": 2, "energy": 30}}},
+         "events": [{"type": "construct", "id": 2, "owner_id": 0,
+                     "location": {"x": 2, "y": 1}}],
+         "moves": {}}
+    ]
+}"#;
+
+static CONSTANTS: Once = Once::new();
+
+/// Return the fixture with the given name.
+pub fn get(name: &str) -> &'static str {
+    FIXTURES
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, input)| *input)
+        .expect("fixture does not exist")
+}
+
+/// Start a Game from the given engine input.
+///
+/// The constants are only set the first time, so that many tests can start Games. The returned
+/// StringIo holds the remaining input.
+pub fn start(input: &str) -> (Game, StringIo) {
+    let mut io = StringIo::new(input);
+    let line = io.read_line().unwrap();
+    let constants: Constants = serde_json::from_str(&line).unwrap();
+    CONSTANTS.call_once(|| constants::set(constants));
+    let game = Game::init_with(&mut io).unwrap();
+    (game, io)
+}
+
+/// Set the constants from the first fixture, unless they have already been set.
+fn set_constants() {
+    let line = FIXTURES[0].1.lines().next().unwrap();
+    let constants: Constants = serde_json::from_str(line).unwrap();
+    CONSTANTS.call_once(|| constants::set(constants));
+}
+
+/// Create a single Player Game from a Board in the format of `Board::from_ascii`.
+///
+/// The Board must have a Shipyard, and every Ship belongs to Player 0 and is empty. This uses the
+/// constants from the first fixture.
+pub fn game(ascii: &str) -> Game {
+    set_constants();
+
+    let board = Board::from_ascii(ascii).unwrap();
+    let player_id = PlayerId::new(0);
+    let mut shipyard = None;
+    let mut ships = Vec::new();
+    for row in &board.cells {
+        for cell in row {
+            if let Some(Structure::Shipyard(_)) = cell.structure {
+                shipyard = Some(Shipyard {
+                    id: ShipyardId::new(0),
+                    player_id,
+                    position: cell.position,
+                });
+            }
+            for ship_id in &cell.ships {
+                ships.push(Ship::new(*ship_id, player_id, cell.position, 0));
+            }
+        }
+    }
+
+    let mut player = Player::new(player_id, shipyard.expect("Board must have a Shipyard"));
+    player.ship_ids = ships.iter().map(|ship| ship.id).collect();
+    let mut players = HashMap::new();
+    players.insert(player_id, player);
+
+    let mut game = Game::new(player_id, players, board);
+    game.ships = ships.into_iter().map(|ship| (ship.id, ship)).collect();
+    game
+}
+
+/// Play the given engine input, which may be malformed, as far as it can be parsed.
+///
+/// This uses the constants from the first fixture.
+pub fn play(input: &str) -> Option<Game> {
+    set_constants();
+
+    let mut io = StringIo::new(input);
+    let mut game = Game::init_with(&mut io).ok()?;
+    while !io.input.is_empty() {
+        game.update_with(&mut io).ok()?;
+    }
+    Some(game)
+}
+
+/// Create a Game on an empty Board, where we are Player 0 with a Shipyard at the given Position.
+pub fn empty_game(width: isize, height: isize, shipyard: Position) -> Game {
+    set_constants();
+
+    let mut board = Board::new(width, height);
+    let player_id = PlayerId::new(0);
+    let id = ShipyardId::new(0);
+    board[shipyard].structure = Some(Structure::Shipyard(id));
+    let shipyard = Shipyard {
+        id,
+        player_id,
+        position: shipyard,
+    };
+    let mut players = HashMap::new();
+    players.insert(player_id, Player::new(player_id, shipyard));
+    Game::new(player_id, players, board)
+}
+
+/// Add a Ship to the Game and the Board, and to its Player if it has one.
+pub fn add_ship(game: &mut Game, ship: Ship) {
+    game.board[ship.position].add_ship(ship.id);
+    if let Some(player) = game.players.get_mut(&ship.player_id) {
+        player.ship_ids.push(ship.id);
+    }
+    game.ships.insert(ship.id, ship);
+}
+
+/// Assert that the Game is consistent with itself.
+pub fn check_invariants(game: &Game) {
+    let (width, height) = (game.board.width, game.board.height);
+    let max_halite = constants::get().max_halite;
+
+    for row in &game.board.cells {
+        for cell in row {
+            assert!(cell.halite <= max_halite);
+            for ship_id in &cell.ships {
+                assert_eq!(game.ships[ship_id].position, cell.position);
+            }
+        }
+    }
+
+    for player in game.players.values() {
+        let shipyard = player.shipyard.position;
+        assert!(game.board[shipyard].has_structure());
+
+        for ship_id in &player.ship_ids {
+            let ship = &game.ships[ship_id];
+            assert_eq!(ship.player_id, player.id);
+            assert!(ship.position.x >= 0 && ship.position.x < width);
+            assert!(ship.position.y >= 0 && ship.position.y < height);
+            assert!(ship.halite <= max_halite);
+            assert!(game.board[ship.position].has_ship(*ship_id));
+        }
+
+        for dropoff_id in &player.dropoff_ids {
+            let dropoff = &game.dropoffs[dropoff_id];
+            assert_eq!(dropoff.player_id, player.id);
+            assert!(game.board[dropoff.position].has_structure());
+        }
+    }
+
+    let ship_count: usize = game.players.values().map(|p| p.ship_ids.len()).sum();
+    assert_eq!(game.ships.len(), ship_count);
+    let dropoff_count: usize = game.players.values().map(|p| p.dropoff_ids.len()).sum();
+    assert_eq!(game.dropoffs.len(), dropoff_count);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{DropoffId, PlayerId, Position};
+    use super::*;
+
+    #[test]
+    fn test_fixtures() {
+        for (name, input) in FIXTURES {
+            let (mut game, mut io) = start(input);
+            let size: isize = name[3..5].parse().unwrap();
+            let players: usize = name[..1].parse().unwrap();
+            assert_eq!((game.board.width, game.board.height), (size, size));
+            assert_eq!(game.players.len(), players);
+            check_invariants(&game);
+
+            let mut turn = 0;
+            while !io.input.is_empty() {
+                game.update_with(&mut io).unwrap();
+                turn += 1;
+                assert_eq!(game.turn, turn, "{} turn {}", name, turn);
+                check_invariants(&game);
+            }
+            assert!(turn > 0);
+        }
+    }
+
+    #[test]
+    fn test_malformed_frames() {
+        // Skip the constants, they are set separately.
+        let input: Vec<_> = get("2p-32x32").lines().skip(1).collect();
+        let input = input.join("\n");
+        let tokens: Vec<_> = input.split_whitespace().collect();
+        assert!(play(&input).is_some());
+
+        // Truncated input.
+        for end in (0..input.len()).step_by(97) {
+            play(&input[..end]);
+        }
+
+        // Corrupted tokens.
+        for i in (0..tokens.len()).step_by(53) {
+            for replacement in &["-1", "0", "99999999999", "4294967297", "x", "1 1"] {
+                let mut corrupted = tokens.clone();
+                corrupted[i] = replacement;
+                play(&corrupted.join(" "));
+            }
+        }
+    }
+
+    #[test]
+    fn test_fixture_dropoff() {
+        let (mut game, mut io) = start(get("2p-48x48"));
+        while !io.input.is_empty() {
+            game.update_with(&mut io).unwrap();
+        }
+        assert_eq!(game.turn, 40);
+        assert_eq!(game.me().dropoff_ids, vec![DropoffId(0)]);
+        let dropoff = game.dropoffs[&DropoffId(0)];
+        assert_eq!(dropoff.player_id, PlayerId(0));
+        assert_eq!(dropoff.position, Position::new(12, 23));
+    }
+}
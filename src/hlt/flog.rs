@@ -0,0 +1,131 @@
+use std::fs::File;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde_json;
+
+use super::{Position, Result};
+
+/// An annotation of a Cell on a turn, which the Halite visualizer shows when the Cell is
+/// selected.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FlogEntry {
+    /// The turn.
+    pub t: usize,
+    /// The column of the Cell.
+    pub x: isize,
+    /// The row of the Cell.
+    pub y: isize,
+    /// A message shown for the Cell.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub msg: Option<String>,
+    /// A color the Cell is highlighted with, like `"#ff0000"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+/// Collects annotations in memory and writes them as a `.flog` file for the Halite visualizer.
+///
+/// The file is only written by `write`, so call it at the end of the Game. If the bot panics
+/// first, `install_panic_hook` makes sure the annotations so far are still written. Clones share
+/// the same annotations.
+#[derive(Clone, Debug)]
+pub struct FlogWriter {
+    /// Where the `.flog` file is written.
+    path: PathBuf,
+    /// The annotations so far.
+    entries: Arc<Mutex<Vec<FlogEntry>>>,
+}
+
+impl FlogWriter {
+    /// Create a new FlogWriter that writes to the file at the given path.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        FlogWriter {
+            path: path.as_ref().to_path_buf(),
+            entries: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Add an annotation entry.
+    pub fn push(&self, entry: FlogEntry) {
+        self.entries.lock().unwrap().push(entry);
+    }
+
+    /// Add a message for the Cell at the given Position on the given turn.
+    pub fn log(&self, turn: usize, position: Position, msg: &str) {
+        self.push(FlogEntry {
+            t: turn,
+            x: position.x,
+            y: position.y,
+            msg: Some(msg.to_string()),
+            color: None,
+        });
+    }
+
+    /// Highlight the Cell at the given Position on the given turn with the given color.
+    pub fn color(&self, turn: usize, position: Position, color: &str) {
+        self.push(FlogEntry {
+            t: turn,
+            x: position.x,
+            y: position.y,
+            msg: None,
+            color: Some(color.to_string()),
+        });
+    }
+
+    /// Return the annotations so far.
+    pub fn entries(&self) -> Vec<FlogEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Return the annotations so far as a `.flog` JSON array.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&*self.entries.lock().unwrap()).unwrap()
+    }
+
+    /// Write the annotations so far to the `.flog` file, replacing it if it exists.
+    pub fn write(&self) -> Result<()> {
+        write_flog(&self.path, &self.entries.lock().unwrap())
+    }
+
+    /// Write the annotations when the bot panics, before running the previous panic hook.
+    pub fn install_panic_hook(&self) {
+        let writer = self.clone();
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            // The panic may have happened while the entries were locked, so don't wait for them.
+            if let Ok(entries) = writer.entries.try_lock() {
+                if let Err(err) = write_flog(&writer.path, &entries) {
+                    error!("Unable to write {}: {}", writer.path.display(), err);
+                }
+            }
+            previous(info);
+        }));
+    }
+}
+
+/// Write annotations to a `.flog` file, replacing it if it exists.
+fn write_flog(path: &Path, entries: &[FlogEntry]) -> Result<()> {
+    let file = File::create(path)?;
+    serde_json::to_writer(file, entries)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flog_writer() {
+        let flog = FlogWriter::new("test.flog");
+        flog.log(3, Position::new(1, 2), "mining");
+        flog.clone().color(4, Position::new(0, 5), "#ff0000");
+
+        assert_eq!(flog.entries().len(), 2);
+        assert_eq!(
+            flog.to_json(),
+            r##"[{"t":3,"x":1,"y":2,"msg":"mining"},{"t":4,"x":0,"y":5,"color":"#ff0000"}]"##
+        );
+    }
+}
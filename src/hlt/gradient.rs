@@ -0,0 +1,75 @@
+use super::kernel::Kernel;
+use super::{Board, Direction, Grid, Position};
+
+/// The radius of the neighbourhood that halite is averaged over before taking the gradient.
+const SMOOTHING_RADIUS: usize = 2;
+
+/// A grid of which Direction the smoothed halite increases most steeply in, for each Cell.
+///
+/// Exploring Ships can follow it to climb towards rich areas without a full target assignment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GradientMap {
+    /// The average halite around each Position.
+    pub smoothed: Grid<f64>,
+    /// The Direction of steepest ascent from each Position, or `None` at a local maximum.
+    pub directions: Grid<Option<Direction>>,
+}
+
+impl GradientMap {
+    /// Create a new flat GradientMap.
+    pub fn new(width: isize, height: isize) -> Self {
+        GradientMap {
+            smoothed: Grid::new(width, height, 0.0),
+            directions: Grid::new(width, height, None),
+        }
+    }
+
+    /// Compute the GradientMap for the halite on the Board.
+    pub fn compute(board: &Board) -> Self {
+        let mut map = GradientMap::new(board.width, board.height);
+        map.smoothed = board.convolve(&Kernel::diamond(SMOOTHING_RADIUS).normalized());
+
+        for row in &board.cells {
+            for cell in row {
+                let mut best = (map.smoothed(cell.position), None);
+                for direction in Direction::all() {
+                    let value = map.smoothed(cell.position + direction);
+                    if value > best.0 {
+                        best = (value, Some(direction));
+                    }
+                }
+                map.directions[cell.position] = best.1;
+            }
+        }
+
+        map
+    }
+
+    /// Return the average halite around the given Position.
+    pub fn smoothed(&self, position: Position) -> f64 {
+        self.smoothed[position]
+    }
+
+    /// Return the Direction to move from the given Position to climb towards more halite.
+    pub fn direction(&self, position: Position) -> Option<Direction> {
+        self.directions[position]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gradient_map_compute() {
+        let mut board = Board::new(16, 16);
+        board[Position::new(8, 8)].halite = 1000;
+
+        let map = GradientMap::compute(&board);
+        assert_eq!(map.direction(Position::new(8, 8)), None);
+        assert_eq!(map.direction(Position::new(8, 5)), Some(Direction::South));
+        assert_eq!(map.direction(Position::new(11, 8)), Some(Direction::West));
+        assert_eq!(map.direction(Position::new(0, 0)), None);
+        assert!(map.smoothed(Position::new(8, 7)) > 0.0);
+    }
+}
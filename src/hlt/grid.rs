@@ -0,0 +1,139 @@
+use std::ops::{Index, IndexMut};
+
+use super::{Board, Position};
+
+/// A layer of values aligned to the Board, one for each Position.
+///
+/// Indexing with a Position wraps around at the edges, just like indexing the Board.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Grid<T> {
+    /// The width of the Grid.
+    pub width: isize,
+    /// The height of the Grid.
+    pub height: isize,
+    /// The values, row by row.
+    values: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// Create a new Grid with every value set to the given one.
+    pub fn new(width: isize, height: isize, value: T) -> Self {
+        Grid {
+            width,
+            height,
+            values: vec![value; (width * height) as usize],
+        }
+    }
+
+    /// Set every value in the Grid to the given one.
+    pub fn fill(&mut self, value: T) {
+        for v in &mut self.values {
+            *v = value.clone();
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Create a new Grid by calling a function for each Position.
+    pub fn from_fn<F>(width: isize, height: isize, mut f: F) -> Self
+    where
+        F: FnMut(Position) -> T,
+    {
+        let mut values = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                values.push(f(Position::new(x, y)));
+            }
+        }
+        Grid {
+            width,
+            height,
+            values,
+        }
+    }
+
+    /// Create a new Grid the same size as the Board by calling a function for each Position.
+    pub fn like<F>(board: &Board, f: F) -> Self
+    where
+        F: FnMut(Position) -> T,
+    {
+        Grid::from_fn(board.width, board.height, f)
+    }
+
+    /// Return the index of the value for the given Position.
+    fn index_of(&self, position: Position) -> usize {
+        let normalized = position.normalized(self.width, self.height);
+        (normalized.y * self.width + normalized.x) as usize
+    }
+
+    /// Return an iterator over the Positions and values, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        let width = self.width;
+        self.values
+            .iter()
+            .enumerate()
+            .map(move |(i, v)| (Position::new(i as isize % width, i as isize / width), v))
+    }
+
+    /// Return an iterator over the values, row by row.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.values.iter()
+    }
+
+    /// Return the rows of the Grid.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.values.chunks(self.width.max(1) as usize)
+    }
+
+    /// Return the rows of the Grid mutably.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        self.values.chunks_mut(self.width.max(1) as usize)
+    }
+}
+
+/// Allow indexing the Grid with Positions.
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: Position) -> &Self::Output {
+        &self.values[self.index_of(index)]
+    }
+}
+
+/// Allow mutably indexing the Grid with Positions.
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, index: Position) -> &mut Self::Output {
+        let index = self.index_of(index);
+        &mut self.values[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_index() {
+        let mut grid = Grid::new(3, 2, 0);
+        grid[Position::new(-1, 3)] = 5;
+        assert_eq!(grid[Position::new(2, 1)], 5);
+        assert_eq!(grid.values().sum::<i32>(), 5);
+
+        let rows: Vec<_> = grid.rows().collect();
+        assert_eq!(rows, vec![&[0, 0, 0][..], &[0, 0, 5][..]]);
+
+        grid.fill(1);
+        assert!(grid.values().all(|v| *v == 1));
+    }
+
+    #[test]
+    fn test_grid_from_fn() {
+        let board = Board::new(4, 3);
+        let grid = Grid::like(&board, |p| p.x + 10 * p.y);
+        for (position, value) in grid.iter() {
+            assert_eq!(*value, position.x + 10 * position.y);
+            assert_eq!(board[position].position, position);
+        }
+        assert_eq!(grid.iter().count(), 12);
+    }
+}
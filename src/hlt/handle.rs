@@ -0,0 +1,151 @@
+use super::{Command, Direction, Game, Position, Ship, ShipId};
+
+/// A handle for commanding one of our Ships.
+///
+/// Created by `Game::ship`. Each command is recorded in `Game::intents`, replacing any earlier one
+/// for the Ship, and is only issued when `Game::schedule` runs, which `Game::run_strategy` does
+/// once the strategy has finished. Commands consume the handle since a Ship only gets one command
+/// per turn.
+pub struct ShipHandle<'a> {
+    game: &'a mut Game,
+    ship_id: ShipId,
+}
+
+impl Game {
+    /// Return a handle for commanding the given Ship.
+    ///
+    /// Panics if the Ship does not exist.
+    pub fn ship(&mut self, ship_id: ShipId) -> ShipHandle<'_> {
+        assert!(
+            self.ships.contains_key(&ship_id),
+            "ship {} does not exist",
+            ship_id
+        );
+        ShipHandle {
+            game: self,
+            ship_id,
+        }
+    }
+
+    /// Issue the commands given through ShipHandles, in the order they were given.
+    ///
+    /// Each one replaces any command already given to the Ship this turn, and keeps the Board up
+    /// to date just like calling the corresponding method on the Game.
+    pub fn schedule(&mut self) {
+        let intents = self.intents.to_vec();
+        self.intents.clear();
+        for command in intents {
+            match command {
+                Command::Spawn => self.spawn_ship(),
+                Command::ConvertToDropoff(ship_id) => self.convert_to_dropoff(ship_id),
+                Command::Collect(ship_id) => self.collect_halite(ship_id),
+                Command::Move(ship_id, direction) => self.move_ship(ship_id, direction),
+            }
+        }
+    }
+}
+
+impl<'a> ShipHandle<'a> {
+    /// Return the Ship.
+    pub fn get(&self) -> &Ship {
+        &self.game.ships[&self.ship_id]
+    }
+
+    /// Return the Ship's identifier.
+    pub fn id(&self) -> ShipId {
+        self.ship_id
+    }
+
+    /// Return the Ship's Position.
+    pub fn position(&self) -> Position {
+        self.get().position
+    }
+
+    /// Return the halite the Ship is carrying.
+    pub fn halite(&self) -> usize {
+        self.get().halite
+    }
+
+    /// Return whether the Ship has already been given a command this turn, directly or through a
+    /// handle.
+    pub fn has_command(&self) -> bool {
+        self.game.has_command(self.ship_id) || self.game.intents.get(self.ship_id).is_some()
+    }
+
+    /// Move the Ship in the given Direction.
+    pub fn move_in(self, direction: Direction) {
+        self.game.intents.push(Command::Move(self.ship_id, direction));
+    }
+
+    /// Move the Ship one step toward the target Position, or collect if it can't get closer.
+    ///
+    /// Returns the Direction the Ship moved in, if any.
+    pub fn move_toward(self, target: Position) -> Option<Direction> {
+        let direction = self.game.navigate_to(self.ship_id, target);
+        self.go(direction)
+    }
+
+    /// Move the Ship one step toward our closest deposit point, or collect if it can't get closer.
+    ///
+    /// Returns the Direction the Ship moved in, if any.
+    pub fn return_home(self) -> Option<Direction> {
+        let direction = self.game.navigate_to_deposit(self.ship_id);
+        self.go(direction)
+    }
+
+    /// Make the Ship collect halite where it is.
+    pub fn collect(self) {
+        self.game.intents.push(Command::Collect(self.ship_id));
+    }
+
+    /// Convert the Ship into a Dropoff where it is.
+    pub fn convert(self) {
+        self.game.intents.push(Command::ConvertToDropoff(self.ship_id));
+    }
+
+    /// Move the Ship in the Direction, if any, or else collect.
+    fn go(self, direction: Option<Direction>) -> Option<Direction> {
+        match direction {
+            Some(direction) => self.move_in(direction),
+            None => self.collect(),
+        }
+        direction
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures;
+    use super::*;
+
+    #[test]
+    fn test_ship_handle() {
+        let mut game = fixtures::game(
+            "
+            .  .  .  .  .
+            a  .  .  b  c
+            .  .  Y  .  .
+            ",
+        );
+        let (a, b, c) = (ShipId::new(0), ShipId::new(1), ShipId::new(2));
+
+        assert_eq!(game.ship(a).position(), Position::new(0, 1));
+        assert_eq!(
+            game.ship(a).move_toward(Position::new(0, 0)),
+            Some(Direction::North)
+        );
+        assert!(game.ship(a).has_command());
+        assert!(game.ship(b).return_home().is_some());
+        game.ship(c).collect();
+
+        // Nothing is issued until the scheduler runs.
+        assert!(game.commands.is_empty());
+        assert_eq!(game.ships[&a].position, Position::new(0, 1));
+        game.schedule();
+        assert!(game.intents.is_empty());
+        assert_eq!(game.ships[&a].position, Position::new(0, 0));
+        assert!(game.board[Position::new(0, 0)].has_ship(a));
+        assert_eq!(game.commands[0], Command::Move(a, Direction::North));
+        assert_eq!(game.commands[2], Command::Collect(c));
+    }
+}
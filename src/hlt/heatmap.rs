@@ -0,0 +1,103 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use super::{Game, Grid, Result};
+
+/// The number of pixels along each side of a cell, by default.
+const DEFAULT_SCALE: usize = 8;
+
+/// Render a Grid as a binary grayscale PGM image.
+///
+/// The values are scaled so that the smallest is black and the largest is white, and each cell
+/// becomes a square of `scale` by `scale` pixels. Values that aren't finite are drawn black.
+pub fn to_pgm(grid: &Grid<f64>, scale: usize) -> Vec<u8> {
+    let scale = scale.max(1);
+    let (min, max) = grid.values().filter(|value| value.is_finite()).fold(
+        (f64::INFINITY, f64::NEG_INFINITY),
+        |(min, max), &value| (min.min(value), max.max(value)),
+    );
+    let shade = |value: f64| -> u8 {
+        if !value.is_finite() || max <= min {
+            0
+        } else {
+            ((value - min) / (max - min) * 255.0).round() as u8
+        }
+    };
+
+    let width = grid.width as usize * scale;
+    let height = grid.height as usize * scale;
+    let mut image = format!("P5\n{} {}\n255\n", width, height).into_bytes();
+    image.reserve(width * height);
+    for row in grid.rows() {
+        let pixels: Vec<u8> = row
+            .iter()
+            .flat_map(|&value| ::std::iter::repeat_n(shade(value), scale))
+            .collect();
+        for _ in 0..scale {
+            image.extend_from_slice(&pixels);
+        }
+    }
+    image
+}
+
+/// Writes overlays of the Game as heatmap images, one file for each overlay every turn.
+///
+/// The files are named "<name>-<turn>.pgm" and can be opened with most image viewers, or
+/// stitched into an animation. Record the heatmaps after ending the turn.
+pub struct Heatmaps {
+    /// The directory to write the images to.
+    directory: PathBuf,
+    /// The number of pixels along each side of a cell.
+    pub scale: usize,
+}
+
+impl Heatmaps {
+    /// Create Heatmaps that write to the given directory, creating it if necessary.
+    pub fn create<P: AsRef<Path>>(directory: P) -> Result<Self> {
+        fs::create_dir_all(&directory)?;
+        Ok(Heatmaps {
+            directory: directory.as_ref().to_path_buf(),
+            scale: DEFAULT_SCALE,
+        })
+    }
+
+    /// Write a single Grid for the given turn, returning the path of the image.
+    pub fn export(&self, name: &str, turn: usize, grid: &Grid<f64>) -> Result<PathBuf> {
+        let path = self.directory.join(format!("{}-{:03}.pgm", name, turn));
+        let mut writer = BufWriter::new(File::create(&path)?);
+        writer.write_all(&to_pgm(grid, self.scale))?;
+        writer.flush()?;
+        Ok(path)
+    }
+
+    /// Write the halite, danger and value overlays for the current turn of the Game.
+    pub fn record(&self, game: &Game) -> Result<()> {
+        let board = &game.board;
+        let halite = Grid::like(board, |p| board[p].halite as f64);
+        let danger = Grid::like(board, |p| game.danger[p]);
+        let value = Grid::like(board, |p| game.cell_value(p));
+        self.export("halite", game.turn, &halite)?;
+        self.export("danger", game.turn, &danger)?;
+        self.export("value", game.turn, &value)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_pgm() {
+        let grid = Grid::from_fn(2, 1, |p| p.x as f64 * 10.0);
+        assert_eq!(to_pgm(&grid, 1), b"P5\n2 1\n255\n\x00\xff".to_vec());
+        assert_eq!(
+            to_pgm(&grid, 2),
+            b"P5\n4 2\n255\n\x00\x00\xff\xff\x00\x00\xff\xff".to_vec()
+        );
+
+        let flat = Grid::new(2, 2, 7.0);
+        assert_eq!(&to_pgm(&flat, 1)[11..], &[0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,120 @@
+use std::ops::Index;
+
+use super::kernel::Kernel;
+use super::{Board, Grid, Position, Ship};
+
+/// A grid of where our Ships would be inspired, and the bonus halite that would bring.
+///
+/// A Ship is inspired when enough enemy Ships are within the inspiration radius. How likely that is
+/// to persist into the next turn depends on how far inside the radius the enemy Ships are.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InspirationMap {
+    /// Whether a Ship at each Position would currently be inspired.
+    pub inspired: Grid<bool>,
+    /// The likelihood that a Ship at each Position will be inspired next turn.
+    pub likelihood: Grid<f64>,
+    /// The expected bonus halite from being inspired, for each Position.
+    pub bonus: Grid<f64>,
+}
+
+impl InspirationMap {
+    /// Create a new InspirationMap where nothing is inspired.
+    pub fn new(width: isize, height: isize) -> Self {
+        InspirationMap {
+            inspired: Grid::new(width, height, false),
+            likelihood: Grid::new(width, height, 0.0),
+            bonus: Grid::new(width, height, 0.0),
+        }
+    }
+
+    /// Compute the InspirationMap for the given enemy Ships.
+    ///
+    /// `radius`, `ship_count`, `multiplier`, `extract_ratio`, and `inspired_extract_ratio` are the
+    /// inspiration and extraction constants of the Game.
+    pub fn compute(
+        board: &Board,
+        enemies: &[Ship],
+        radius: usize,
+        ship_count: usize,
+        multiplier: f64,
+        extract_ratio: usize,
+        inspired_extract_ratio: usize,
+    ) -> Self {
+        let mut map = InspirationMap::new(board.width, board.height);
+
+        let mut ships = Grid::new(board.width, board.height, 0.0);
+        for enemy in enemies {
+            ships[enemy.position] += 1.0;
+        }
+
+        // The number of enemy Ships well inside, inside, and just outside the radius of each Cell.
+        let within = |radius: Option<usize>| match radius {
+            Some(radius) => Kernel::diamond(radius).apply(&ships),
+            None => Grid::new(board.width, board.height, 0.0),
+        };
+        let deep = within(radius.checked_sub(1));
+        let inside = within(Some(radius));
+        let near = within(Some(radius + 1));
+
+        // How much more mining a Cell is worth when inspired, from the faster extraction and the
+        // bonus on what is extracted.
+        let extra = (1.0 + multiplier) * extract_ratio as f64 / inspired_extract_ratio as f64 - 1.0;
+
+        let ship_count = ship_count as f64;
+        for (position, count) in inside.iter() {
+            map.inspired[position] = *count >= ship_count;
+            map.likelihood[position] = if deep[position] >= ship_count {
+                1.0
+            } else if *count >= ship_count {
+                0.5
+            } else if near[position] >= ship_count {
+                0.25
+            } else {
+                0.0
+            };
+            let halite = board[position].halite as f64;
+            map.bonus[position] = halite * extra * map.likelihood[position];
+        }
+
+        map
+    }
+
+    /// Return whether a Ship at the given Position would currently be inspired.
+    pub fn is_inspired(&self, position: Position) -> bool {
+        self.inspired[position]
+    }
+}
+
+/// Allow indexing the InspirationMap with Positions to get the expected bonus.
+impl Index<Position> for InspirationMap {
+    type Output = f64;
+
+    fn index(&self, index: Position) -> &Self::Output {
+        &self.bonus[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspiration_map_compute() {
+        let mut board = Board::new(20, 20);
+        board[Position::new(5, 5)].halite = 100;
+        let enemies = [
+            Ship::new(0.into(), 1.into(), Position::new(5, 3), 0),
+            Ship::new(1.into(), 1.into(), Position::new(5, 7), 0),
+        ];
+
+        let map = InspirationMap::compute(&board, &enemies, 4, 2, 2.0, 4, 4);
+        assert!(map.is_inspired(Position::new(5, 5)));
+        assert_eq!(map[Position::new(5, 5)], 200.0);
+
+        // Faster extraction when inspired is worth more.
+        let map = InspirationMap::compute(&board, &enemies, 4, 2, 2.0, 4, 2);
+        assert_eq!(map[Position::new(5, 5)], 500.0);
+        assert!(!map.is_inspired(Position::new(5, 12)));
+        assert!(!map.is_inspired(Position::new(15, 5)));
+    }
+}
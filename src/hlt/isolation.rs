@@ -0,0 +1,98 @@
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::panic::{self, AssertUnwindSafe};
+
+use super::Game;
+
+/// Return the message a panic was started with.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg
+    } else {
+        "unknown panic"
+    }
+}
+
+/// Log every panic with a stack trace, before running the previous panic hook.
+///
+/// Panics caught by `Game::run_strategy` are only logged here, since the stack trace is gone by
+/// the time they are caught.
+pub fn install_panic_logger() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        error!("{}\n{}", info, Backtrace::force_capture());
+        previous(info);
+    }));
+}
+
+impl Game {
+    /// Run the strategy for a turn, and return whether it finished without panicking.
+    ///
+    /// Once the strategy has finished, the commands given through ShipHandles are scheduled.
+    ///
+    /// If the strategy panics, every one of our Ships collects halite instead of whatever it was
+    /// commanded to do, except for commands that have already been streamed. A bug in strategy
+    /// code then costs a turn rather than the whole Game.
+    pub fn run_strategy<F>(&mut self, strategy: F) -> bool
+    where
+        F: FnOnce(&mut Game),
+    {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| strategy(self)));
+        match result {
+            Ok(()) => {
+                self.schedule();
+                true
+            }
+            Err(payload) => {
+                error!(
+                    "Strategy panicked on turn {}, so all our Ships will collect: {}",
+                    self.turn,
+                    panic_message(&*payload)
+                );
+                self.intents.clear();
+                self.fall_back();
+                false
+            }
+        }
+    }
+
+    /// Replace the commands for our Ships with Collects, where they can still be revoked.
+    fn fall_back(&mut self) {
+        let ship_ids = self.me().ship_ids.clone();
+        for ship_id in ship_ids {
+            if self.is_revocable(ship_id) {
+                self.collect_halite(ship_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixtures, Command, Direction, Position, ShipId};
+
+    #[test]
+    fn test_run_strategy() {
+        let mut game = fixtures::game(". a Y b .");
+        let (a, b) = (ShipId::new(0), ShipId::new(1));
+        assert!(game.run_strategy(|game| game.move_ship(a, Direction::West)));
+        assert_eq!(
+            game.commands.to_vec(),
+            vec![Command::Move(a, Direction::West)]
+        );
+
+        let finished = game.run_strategy(|game| {
+            game.move_ship(b, Direction::East);
+            panic!("strategy bug");
+        });
+        assert!(!finished);
+        assert_eq!(
+            game.commands.to_vec(),
+            vec![Command::Collect(a), Command::Collect(b)]
+        );
+        assert_eq!(game.ships[&a].position, Position::new(1, 0));
+        assert_eq!(game.ships[&b].position, Position::new(3, 0));
+    }
+}
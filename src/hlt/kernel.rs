@@ -0,0 +1,165 @@
+use super::{Board, Grid, Result};
+
+/// A small square grid of weights that can be convolved over the Board.
+///
+/// The center of the grid is the Cell being computed, and the Board wraps around at the edges.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Kernel {
+    /// The distance from the center to the edges of the Kernel.
+    radius: isize,
+    /// The weights, row by row.
+    weights: Vec<Vec<f64>>,
+}
+
+impl Kernel {
+    /// Create a new Kernel from rows of weights.
+    ///
+    /// The weights must form a square with an odd side, so that it has a center.
+    pub fn new(weights: Vec<Vec<f64>>) -> Result<Self> {
+        let size = weights.len();
+        if size.is_multiple_of(2) || weights.iter().any(|row| row.len() != size) {
+            return Err(format_err!(
+                "kernel must be a square with an odd side, got {} rows",
+                size
+            ));
+        }
+        Ok(Kernel {
+            radius: (size / 2) as isize,
+            weights,
+        })
+    }
+
+    /// Create a Kernel that weights every Cell within the given distance by one.
+    pub fn diamond(radius: usize) -> Self {
+        Kernel::falloff(radius, 1.0)
+    }
+
+    /// Create a Kernel that weights Cells within the given distance by `decay` to the power of
+    /// their distance from the center.
+    pub fn falloff(radius: usize, decay: f64) -> Self {
+        let radius = radius as isize;
+        let weights = (-radius..=radius)
+            .map(|dy| {
+                (-radius..=radius)
+                    .map(|dx| {
+                        let distance = dx.abs() + dy.abs();
+                        if distance <= radius {
+                            decay.powi(distance as i32)
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Kernel { radius, weights }
+    }
+
+    /// Create a Laplacian Kernel, which picks out edges where values change sharply.
+    pub fn laplacian() -> Self {
+        Kernel {
+            radius: 1,
+            weights: vec![
+                vec![0.0, 1.0, 0.0],
+                vec![1.0, -4.0, 1.0],
+                vec![0.0, 1.0, 0.0],
+            ],
+        }
+    }
+
+    /// Return the distance from the center to the edges of this Kernel.
+    pub fn radius(&self) -> isize {
+        self.radius
+    }
+
+    /// Return the weights of this Kernel, row by row.
+    pub fn weights(&self) -> &[Vec<f64>] {
+        &self.weights
+    }
+
+    /// Return this Kernel scaled so that its weights sum to one.
+    pub fn normalized(mut self) -> Self {
+        let total: f64 = self.weights.iter().flat_map(|row| row.iter()).sum();
+        if total != 0.0 {
+            for weight in self.weights.iter_mut().flat_map(|row| row.iter_mut()) {
+                *weight /= total;
+            }
+        }
+        self
+    }
+
+    /// Convolve this Kernel over a Grid of values, wrapping around at the edges.
+    pub fn apply(&self, values: &Grid<f64>) -> Grid<f64> {
+        let (width, height) = (values.width, values.height);
+        let mut result = Grid::new(width, height, 0.0);
+        if width == 0 {
+            return result;
+        }
+        let rows: Vec<_> = values.rows().collect();
+
+        // Skip zero weights, since most Kernels are sparse.
+        for (ky, row) in self.weights.iter().enumerate() {
+            for (kx, weight) in row.iter().enumerate() {
+                if *weight == 0.0 {
+                    continue;
+                }
+                let dx = (kx as isize - self.radius).rem_euclid(width) as usize;
+                let dy = (ky as isize - self.radius).rem_euclid(height) as usize;
+                for (y, out) in result.rows_mut().enumerate() {
+                    let source = rows[(y + dy) % height as usize];
+                    let (head, tail) = source.split_at(dx);
+                    for (out, value) in out.iter_mut().zip(tail.iter().chain(head)) {
+                        *out += weight * value;
+                    }
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl Board {
+    /// Convolve a Kernel over the halite on the Board.
+    pub fn convolve(&self, kernel: &Kernel) -> Grid<f64> {
+        let halite = Grid::like(self, |position| self[position].halite as f64);
+        kernel.apply(&halite)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Position;
+    use super::*;
+
+    #[test]
+    fn test_kernel_new() {
+        assert!(Kernel::new(vec![vec![1.0; 3]; 3]).is_ok());
+        assert!(Kernel::new(vec![vec![1.0; 2]; 2]).is_err());
+        assert!(Kernel::new(vec![vec![1.0; 3], vec![1.0; 2], vec![1.0; 3]]).is_err());
+
+        let kernel = Kernel::diamond(2);
+        assert_eq!(kernel.radius(), 2);
+        assert_eq!(kernel.weights().len(), 5);
+    }
+
+    #[test]
+    fn test_board_convolve() {
+        let mut board = Board::new(7, 5);
+        for (i, position) in board
+            .positions_within(Position::new(0, 0), 3)
+            .iter()
+            .enumerate()
+        {
+            board[*position].halite = 10 * i;
+        }
+
+        let sums = board.convolve(&Kernel::diamond(2));
+        for (position, sum) in sums.iter() {
+            assert_eq!(*sum, board.halite_within(position, 2) as f64);
+        }
+
+        let edges = Board::new(4, 4).convolve(&Kernel::laplacian());
+        assert!(edges.values().all(|v| *v == 0.0));
+    }
+}
@@ -0,0 +1,100 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use super::{Command, Game, Result};
+
+/// The header of the CSV metrics.
+const HEADER: &str = "turn,halite,ships,dropoffs,halite_burned,time_ms";
+
+/// Writes a few metrics for every turn as CSV, for loading into a spreadsheet.
+///
+/// Each row has the turn, our banked halite, our number of Ships and Dropoffs, the halite our
+/// Ships burn moving this turn, and how long the turn took in milliseconds. Record a row after
+/// ending the turn, so that all the commands are included.
+pub struct CsvMetrics<W: Write = BufWriter<File>> {
+    writer: W,
+    /// Whether the header has been written.
+    started: bool,
+}
+
+impl CsvMetrics<BufWriter<File>> {
+    /// Create CsvMetrics that write to the file at the given path.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(CsvMetrics::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> CsvMetrics<W> {
+    /// Create CsvMetrics that write to the given writer.
+    pub fn new(writer: W) -> Self {
+        CsvMetrics {
+            writer,
+            started: false,
+        }
+    }
+
+    /// Write the metrics for the current turn of the Game.
+    pub fn record(&mut self, game: &Game) -> Result<()> {
+        if !self.started {
+            writeln!(self.writer, "{}", HEADER)?;
+            self.started = true;
+        }
+
+        let me = game.me();
+        let burned: usize = game
+            .commands
+            .iter()
+            .filter(|command| matches!(command, Command::Move(..)))
+            .map(|command| command.cost(game))
+            .sum();
+        writeln!(
+            self.writer,
+            "{},{},{},{},{},{:.3}",
+            game.turn,
+            me.halite,
+            me.ship_ids.len(),
+            me.dropoff_ids.len(),
+            burned,
+            game.elapsed().as_secs_f64() * 1000.0
+        )?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixtures, Direction, ShipId};
+    use super::*;
+
+    #[test]
+    fn test_csv_metrics() {
+        let mut game = fixtures::game(
+            "
+            .  a100  .
+            .  Y     .
+            ",
+        );
+        let mut metrics = CsvMetrics::new(Vec::new());
+        metrics.record(&game).unwrap();
+        game.turn += 1;
+        game.move_ship(ShipId::new(0), Direction::East);
+        metrics.record(&game).unwrap();
+
+        let output = String::from_utf8(metrics.into_inner()).unwrap();
+        let rows: Vec<Vec<_>> = output
+            .lines()
+            .map(|line| line.split(',').collect())
+            .collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].len(), 6);
+        assert_eq!(rows[1][..5], ["0", "0", "1", "0", "0"]);
+        assert_eq!(rows[2][..5], ["1", "0", "1", "0", "10"]);
+    }
+}
@@ -1,22 +1,122 @@
+// Declared first so that the `timed!` macro can be used in the other modules.
+#[macro_use]
+pub mod timing;
+
+pub mod analysis;
+pub mod analytics;
+pub mod background;
+pub mod bands;
+pub mod blockade;
 pub mod board;
+pub mod bot;
+pub mod calibration;
+pub mod checks;
+pub mod collision;
+pub mod combat;
+pub mod compare;
+pub mod compat;
+pub mod conflict;
+pub mod congestion;
 pub mod constants;
+pub mod cost;
+pub mod costmap;
+pub mod danger;
+pub mod depletion;
+pub mod divergence;
+pub mod dropoff;
+pub mod effort;
 pub mod engine;
+pub mod entity;
+pub mod error;
+pub mod evaluate;
+pub mod event;
+pub mod features;
+#[cfg(test)]
+mod fixtures;
+pub mod flog;
+pub mod gradient;
+pub mod grid;
+pub mod handle;
+pub mod heatmap;
+pub mod inspiration;
+pub mod isolation;
+pub mod kernel;
+pub mod metrics;
+pub mod navigation;
+pub mod occupancy;
+pub mod opponent;
+pub mod params;
+pub mod policy;
+pub mod preview;
+pub mod queue;
+pub mod replay;
+pub mod rng;
+pub mod runner;
+pub mod scoreboard;
+#[cfg(feature = "debug-server")]
+pub mod server;
+pub mod snapshot;
+#[cfg(any(test, feature = "proptest"))]
+pub mod strategy;
+pub mod summary;
+pub mod territory;
+pub mod timer;
+pub mod trajectory;
+pub mod transcript;
 pub mod util;
+pub mod visits;
+pub mod watchdog;
+pub mod zobrist;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use std::time::Duration;
 
+use self::bands::Bands;
+use self::blockade::Blockade;
 pub use self::board::{Board, Position};
+use self::board::{Cell, Structure};
+use self::calibration::YieldCalibration;
+use self::collision::HalitePile;
+use self::congestion::Congestion;
+use self::constants::Constants;
+use self::costmap::CostMap;
+use self::danger::DangerMap;
+use self::depletion::Depletion;
+pub use self::effort::Effort;
 use self::engine::Engine;
+pub use self::engine::{EngineIo, SharedIo, StdIo, StringIo};
+pub use self::entity::Entity;
+pub use self::error::Error;
+pub use self::evaluate::Evaluator;
+pub use self::event::Event;
+use self::gradient::GradientMap;
+pub use self::grid::Grid;
+use self::inspiration::InspirationMap;
+pub use self::navigation::NavigationOptions;
+use self::occupancy::Occupancy;
+use self::opponent::Opponents;
+pub use self::params::Params;
+pub use self::queue::CommandQueue;
+pub use self::rng::GameRng;
+use self::scoreboard::Scoreboard;
+pub use self::summary::TurnSummary;
+use self::territory::Territory;
+pub use self::timer::{Deadline, TurnBudget, TurnTimer};
+use self::util::pretty_error;
 pub use self::util::Result;
+use self::visits::Visits;
+pub use self::watchdog::Watchdog;
 
 /// A Player identifier.
 #[derive(
-    Clone, Constructor, Copy, Debug, Display, Eq, From, Hash, Into, Ord, PartialEq, PartialOrd,
+    Clone, Constructor, Copy, Debug, Deserialize, Display, Eq, From, Hash, Into, Ord, PartialEq,
+    PartialOrd, Serialize,
 )]
 pub struct PlayerId(usize);
 
 /// A Player in the Game.
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct Player {
     /// This Player's identifier.
     pub id: PlayerId,
@@ -41,16 +141,29 @@ impl Player {
             halite: 0,
         }
     }
+
+    /// Return this Player's Ships in the given Game.
+    pub fn ships<'a>(&'a self, game: &'a Game) -> impl Iterator<Item = &'a Ship> + 'a {
+        self.ship_ids.iter().filter_map(move |id| game.ships.get(id))
+    }
+
+    /// Return this Player's Dropoffs in the given Game, not including the Shipyard.
+    pub fn dropoffs<'a>(&'a self, game: &'a Game) -> impl Iterator<Item = &'a Dropoff> + 'a {
+        self.dropoff_ids
+            .iter()
+            .filter_map(move |id| game.dropoffs.get(id))
+    }
 }
 
 /// A Dropoff identifier.
 #[derive(
-    Clone, Constructor, Copy, Debug, Display, Eq, From, Hash, Into, Ord, PartialEq, PartialOrd,
+    Clone, Constructor, Copy, Debug, Deserialize, Display, Eq, From, Hash, Into, Ord, PartialEq,
+    PartialOrd, Serialize,
 )]
 pub struct DropoffId(usize);
 
 /// A Dropoff in the Game.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct Dropoff {
     /// This Dropoff's identifier.
     pub id: DropoffId,
@@ -73,12 +186,13 @@ impl Dropoff {
 
 /// A Shipyard identifier.
 #[derive(
-    Clone, Constructor, Copy, Debug, Display, Eq, From, Hash, Into, Ord, PartialEq, PartialOrd,
+    Clone, Constructor, Copy, Debug, Deserialize, Display, Eq, From, Hash, Into, Ord, PartialEq,
+    PartialOrd, Serialize,
 )]
 pub struct ShipyardId(usize);
 
 // A Shipyard in the Game.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct Shipyard {
     /// This Shipyard's identifier.
     pub id: ShipyardId,
@@ -90,12 +204,13 @@ pub struct Shipyard {
 
 /// A Ship identifier.
 #[derive(
-    Clone, Constructor, Copy, Debug, Display, Eq, From, Hash, Into, Ord, PartialEq, PartialOrd,
+    Clone, Constructor, Copy, Debug, Deserialize, Display, Eq, From, Hash, Into, Ord, PartialEq,
+    PartialOrd, Serialize,
 )]
 pub struct ShipId(usize);
 
 /// A ship in the Game.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct Ship {
     /// This Ship's identifier.
     pub id: ShipId,
@@ -120,7 +235,32 @@ impl Ship {
 
     /// Return whether the Ship has reached max halite carrying capacity.
     pub fn is_full(&self) -> bool {
-        self.halite >= constants::get().max_halite
+        self.is_full_with(constants::get())
+    }
+
+    /// Return whether the Ship has reached max halite carrying capacity, given the constants.
+    pub fn is_full_with(&self, constants: &Constants) -> bool {
+        self.halite >= constants.max_halite
+    }
+
+    /// Return how much more halite the Ship can carry.
+    pub fn cargo_space(&self) -> usize {
+        constants::get().max_halite.saturating_sub(self.halite)
+    }
+
+    /// Return the halite it costs the Ship to move off the given Cell.
+    ///
+    /// This is a tenth of the Cell's halite with the default constants, rounded down. It doesn't
+    /// account for inspiration, use `Command::cost` for that.
+    pub fn move_cost_from(&self, cell: &Cell) -> usize {
+        cell.halite / constants::get().move_cost_ratio
+    }
+
+    /// Return whether the Ship has enough halite to move off the given Cell.
+    ///
+    /// A Ship that can't afford to move is stuck there, and the engine makes it stay still.
+    pub fn can_move_from(&self, cell: &Cell) -> bool {
+        self.halite >= self.move_cost_from(cell)
     }
 }
 
@@ -131,9 +271,40 @@ pub enum Direction {
     East,
     South,
     West,
+    /// Stay still, which collects halite just like `Command::Collect`.
+    Still,
 }
 
 impl Direction {
+    /// Return the opposite Direction.
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+            Direction::Still => Direction::Still,
+        }
+    }
+
+    /// Return the character the engine uses for this Direction.
+    pub fn as_char(self) -> char {
+        match self {
+            Direction::North => 'n',
+            Direction::East => 'e',
+            Direction::South => 's',
+            Direction::West => 'w',
+            Direction::Still => 'o',
+        }
+    }
+
+    /// Return all the cardinals, and staying still.
+    pub fn all_with_still() -> Vec<Direction> {
+        let mut directions = Direction::all();
+        directions.push(Direction::Still);
+        directions
+    }
+
     /// Return all the cardinals.
     pub fn all() -> Vec<Direction> {
         vec![
@@ -158,6 +329,40 @@ pub enum Command {
     Move(ShipId, Direction),
 }
 
+impl Command {
+    /// Return the canonical form of this command, where moving `Direction::Still` is a Collect.
+    pub fn normalized(self) -> Command {
+        match self {
+            Command::Move(id, Direction::Still) => Command::Collect(id),
+            command => command,
+        }
+    }
+
+    /// Return the Ship this command is for, if any.
+    pub fn ship_id(&self) -> Option<ShipId> {
+        match *self {
+            Command::Spawn => None,
+            Command::ConvertToDropoff(id) | Command::Collect(id) | Command::Move(id, _) => {
+                Some(id)
+            }
+        }
+    }
+}
+
+/// When commands are sent to the Halite engine.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Submission {
+    /// Send all the commands at once in `end_turn`.
+    Batched,
+    /// Send the commands given so far whenever `stream_with` is called, and end the line in
+    /// `end_turn`.
+    ///
+    /// If a late computation overruns the clock, the commands streamed so far still reach the
+    /// engine. But commands that have been streamed can't be revoked, so they are not checked for
+    /// legality and `resolve_conflicts` leaves them as they are.
+    Streaming,
+}
+
 /// The core Game struct.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Game {
@@ -172,25 +377,146 @@ pub struct Game {
     /// All the Dropoffs in this Game.
     pub dropoffs: HashMap<DropoffId, Dropoff>,
     /// Commands that will be sent when `end_turn` is called.
-    pub commands: Vec<Command>,
+    pub commands: CommandQueue,
+    /// Commands given through ShipHandles, which `schedule` turns into `commands`.
+    pub intents: CommandQueue,
+    /// When commands are sent to the Halite engine.
+    pub submission: Submission,
+    /// The number of commands that have already been streamed to the engine this turn.
+    streamed: usize,
+    /// Positions where we intend to build Dropoffs, treated as deposit points until built.
+    pub planned_dropoffs: Vec<Position>,
+    /// Halite dropped by colliding Ships that has not been mined yet.
+    pub halite_piles: Vec<HalitePile>,
+    /// Positions our Ships should route around this turn, for example around a fight.
+    pub avoided_positions: Vec<Position>,
+    /// Congestion metrics for our structures.
+    pub congestion: Congestion,
+    /// How fast halite is being mined out of each region of the Board.
+    pub depletion: Depletion,
+    /// Events that happened since the previous turn.
+    pub events: Vec<Event>,
+    /// How dangerous each Cell is for our Ships.
+    pub danger: DangerMap,
+    /// The probability of each Cell being occupied by an enemy Ship next turn.
+    pub occupancy: Occupancy,
+    /// Where our Ships would be inspired.
+    pub inspiration: InspirationMap,
+    /// Which way the smoothed halite increases from each Cell.
+    pub gradient: GradientMap,
+    /// Which Player controls each part of the Board.
+    pub territory: Territory,
+    /// The cost of getting from each Cell to our closest structure.
+    pub home_costs: CostMap,
+    /// Which Cells are within various distances of our structures.
+    pub home_bands: Bands,
+    /// How often our Ships visited each Cell and how much they mined there.
+    pub visits: Visits,
+    /// How well the halite our Ships mine matches the expected yield.
+    pub yields: YieldCalibration,
+    /// Options that control how our Ships are navigated.
+    pub navigation: NavigationOptions,
+    /// Parameters that control the strategy.
+    pub params: Params,
+    /// Detects enemy Ships camping at our Shipyard.
+    pub blockade: Blockade,
+    /// Statistics about our opponents' behaviour.
+    pub opponents: Opponents,
+    /// Live statistics about every Player.
+    pub scoreboard: Scoreboard,
     /// The current turn.
     pub turn: usize,
+    /// The clock for the current turn, started when the frame has been read.
+    pub timer: TurnTimer,
+    /// The time limits for turns and initialization. Change it with `set_budget`.
+    pub budget: TurnBudget,
+    /// Scales how much work subsystems do, based on how long recent turns took.
+    pub effort: Effort,
+    /// Whether to skip malformed frames and carry on, instead of failing the update.
+    pub resync: bool,
+    /// The turn to skip the input to, after a malformed frame.
+    resync_turn: Option<usize>,
+    /// The source of randomness for strategies.
+    rng: GameRng,
 }
 
 impl Game {
     /// Create a new Game from a PlayerId, Board, and Players.
     pub fn new(my_id: PlayerId, players: HashMap<PlayerId, Player>, board: Board) -> Self {
+        let budget = TurnBudget::new();
         Game {
             my_id,
+            danger: DangerMap::new(board.width, board.height),
+            occupancy: Occupancy::new(board.width, board.height),
+            inspiration: InspirationMap::new(board.width, board.height),
+            gradient: GradientMap::new(board.width, board.height),
+            territory: Territory::new(board.width, board.height),
+            home_costs: CostMap::new(board.width, board.height),
+            home_bands: Bands::new(board.width, board.height),
+            visits: Visits::new(board.width, board.height),
+            yields: YieldCalibration::new(),
             board,
             players,
             ships: HashMap::new(),
             dropoffs: HashMap::new(),
-            commands: Vec::new(),
+            commands: CommandQueue::new(),
+            intents: CommandQueue::new(),
+            submission: Submission::Batched,
+            streamed: 0,
+            planned_dropoffs: Vec::new(),
+            halite_piles: Vec::new(),
+            avoided_positions: Vec::new(),
+            congestion: Congestion::new(),
+            depletion: Depletion::new(),
+            events: Vec::new(),
+            navigation: NavigationOptions::new(),
+            params: Params::default(),
+            blockade: Blockade::new(),
+            opponents: Opponents::new(),
+            scoreboard: Scoreboard::new(),
             turn: 0,
+            timer: TurnTimer::new(),
+            budget,
+            effort: Effort::new(budget.turn()),
+            resync: false,
+            resync_turn: None,
+            rng: GameRng::new(constants::try_get().map_or(0, |c| c.game_seed) as u64),
         }
     }
 
+    /// Return how long it has been since the frame for this turn was read.
+    pub fn elapsed(&self) -> Duration {
+        self.timer.elapsed()
+    }
+
+    /// Return how much of the given budget is left this turn, or zero if it has run out.
+    pub fn remaining(&self, budget: Duration) -> Duration {
+        self.timer.remaining(budget)
+    }
+
+    /// Return a Deadline the given budget after the frame for this turn was read.
+    pub fn deadline(&self, budget: Duration) -> Deadline {
+        Deadline::at(self.timer.started + budget)
+    }
+
+    /// Return the Deadline for the current turn, with the margin of the TurnBudget applied.
+    pub fn turn_deadline(&self) -> Deadline {
+        self.deadline(self.budget.turn())
+    }
+
+    /// Return the Deadline for initialization, with the margin of the TurnBudget applied.
+    ///
+    /// This is only meaningful before `ready` is called.
+    pub fn init_deadline(&self) -> Deadline {
+        self.deadline(self.budget.init())
+    }
+
+    /// Change the time limits, and the target of the Effort controller with them.
+    pub fn set_budget(&mut self, budget: TurnBudget) {
+        self.budget = budget;
+        self.effort.target = budget.turn();
+    }
+
     /// Return a mutable reference to a Player.
     pub fn get_player(&self, player_id: PlayerId) -> Option<&Player> {
         self.players.get(&player_id)
@@ -211,32 +537,345 @@ impl Game {
         &self.players[&self.my_id]
     }
 
+    /// Return every Player except us.
+    pub fn enemies<'a>(&'a self) -> impl Iterator<Item = &'a Player> + 'a {
+        let my_id = self.my_id;
+        self.players.values().filter(move |player| player.id != my_id)
+    }
+
+    /// Return the number of turns left in the Game, including this one.
+    pub fn turns_remaining(&self) -> usize {
+        (constants::get().max_turns + 1).saturating_sub(self.turn)
+    }
+
+    /// Return the source of randomness that every strategy should draw from.
+    ///
+    /// It is seeded from the game seed, so that Games can be reproduced.
+    pub fn rng(&mut self) -> &mut GameRng {
+        &mut self.rng
+    }
+
+    /// Reseed the source of randomness with the given seed.
+    pub fn seed_rng(&mut self, seed: u64) {
+        self.rng = GameRng::new(seed);
+    }
+
     /// Start a new Game.
     pub fn start() -> Result<Self> {
-        let mut engine = Engine::new();
-        constants::set(engine.recv()?);
-        Ok(engine.recv()?)
+        Game::start_with(&mut StdIo::new())
+    }
+
+    /// Start a new Game, reading from the given EngineIo.
+    pub fn start_with(io: &mut dyn EngineIo) -> Result<Self> {
+        constants::set(Engine::new(io).recv()?);
+        Game::init_with(io)
+    }
+
+    /// Read the initial Game state, once the constants have been set.
+    pub fn init_with(io: &mut dyn EngineIo) -> Result<Self> {
+        Engine::new(io).recv()
     }
 
     /// Let the Halite engine know that we are ready to start playing.
-    pub fn ready(&self, name: &str) {
-        let engine = Engine::new();
+    pub fn ready(&self, name: &str) -> Result<()> {
+        self.ready_with(&mut StdIo::new(), name)
+    }
+
+    /// Let the Halite engine know that we are ready to start playing, using the given EngineIo.
+    pub fn ready_with(&self, io: &mut dyn EngineIo, name: &str) -> Result<()> {
+        let mut engine = Engine::new(io);
         engine.send(name);
-        engine.flush();
+        engine.flush()
     }
 
     /// Update the Game information from the Halite engine.
     pub fn update(&mut self) -> Result<()> {
-        Engine::new().update(self)?;
+        self.update_with(&mut StdIo::new())
+    }
+
+    /// Update the Game information, reading from the given EngineIo.
+    ///
+    /// With `resync` set, a malformed frame is logged and skipped, leaving the Game as it was on
+    /// the previous turn, and an `Error::SkippedFrame` is returned. Call `skip_turn` and then
+    /// update again, and the input is skipped up to the start of the next frame.
+    pub fn update_with(&mut self, io: &mut dyn EngineIo) -> Result<()> {
+        // Ships added by `spawn_ship` are only guesses, so don't remember them.
+        let players = &self.players;
+        let previous_ships: HashMap<_, _> = self
+            .ships
+            .iter()
+            .filter(|(id, ship)| players[&ship.player_id].ship_ids.contains(id))
+            .map(|(id, ship)| (*id, *ship))
+            .collect();
+        let previous_dropoffs = self.dropoffs.clone();
+        let previous_board = self.board.clone();
+        let previous_players = self.players.clone();
+        let previous_turn = self.turn;
+
+        let parsed = {
+            let mut engine = Engine::new(io);
+            match self.resync_turn.take() {
+                Some(turn) => engine.skip_to_turn(turn, self.players.len()),
+                None => Ok(()),
+            }.and_then(|_| engine.update(self))
+        }.and_then(|_| self.verify_frame(&previous_dropoffs));
+        if let Err(err) = parsed {
+            let malformed = matches!(
+                Error::find(&err),
+                Some(Error::Parse { .. }) | Some(Error::InvalidFrame(_))
+            );
+            if !self.resync || !malformed {
+                return Err(err);
+            }
+            warn!(
+                "Skipping the frame after turn {}: {}",
+                previous_turn,
+                pretty_error(&err)
+            );
+            self.turn = previous_turn;
+            self.players = previous_players;
+            self.ships = previous_ships;
+            self.dropoffs = previous_dropoffs;
+            self.board = previous_board;
+            self.commands.clear();
+            self.intents.clear();
+            self.streamed = 0;
+            // We skip this frame, so the next frame the engine sends is for the turn after it.
+            self.resync_turn = Some(previous_turn + 2);
+            return Err(Error::SkippedFrame(previous_turn + 1).into());
+        }
+        self.timer.restart();
         info!("=============== TURN {} ================", self.turn);
+        timed!{ "analysis" => {
+            self.analyze(&previous_ships, &previous_dropoffs, &previous_board);
+        }};
+        self.check_invariants();
         Ok(())
     }
 
+    /// Update all the information derived from the Game, given the state on the previous turn.
+    fn analyze(
+        &mut self,
+        previous_ships: &HashMap<ShipId, Ship>,
+        previous_dropoffs: &HashMap<DropoffId, Dropoff>,
+        previous_board: &Board,
+    ) {
+        self.events.clear();
+        self.avoided_positions.clear();
+
+        // Dropoffs that opponents have built since the previous turn.
+        for dropoff in self.dropoffs.values() {
+            if dropoff.player_id != self.my_id && !previous_dropoffs.contains_key(&dropoff.id) {
+                info!(
+                    "Player {} built a Dropoff at {:?}",
+                    dropoff.player_id, dropoff.position
+                );
+                self.events.push(Event::EnemyDropoffBuilt {
+                    player_id: dropoff.player_id,
+                    dropoff_id: dropoff.id,
+                    position: dropoff.position,
+                });
+            }
+        }
+
+        // Ships that no longer exist, and weren't converted into a Dropoff.
+        for ship in self.vanished_ships(previous_ships, previous_dropoffs) {
+            if ship.player_id == self.my_id {
+                info!("Ship {} was destroyed at {:?}", ship.id, ship.position);
+            }
+            self.events.push(Event::ShipDestroyed {
+                player_id: ship.player_id,
+                ship_id: ship.id,
+                position: ship.position,
+            });
+        }
+
+        // Our Ships that were carrying halite and are now empty on one of our structures.
+        let structures = self.structure_positions();
+        let deposits: Vec<_> = self
+            .me()
+            .ship_ids
+            .iter()
+            .filter_map(|id| {
+                let ship = &self.ships[id];
+                match previous_ships.get(id) {
+                    Some(previous) if previous.halite > 0 && ship.halite == 0 => {
+                        Some(ship.position)
+                    }
+                    _ => None,
+                }
+            }).filter(|position| structures.contains(position))
+            .collect();
+        self.congestion
+            .record(self.turn, &self.board, &structures, &deposits);
+        self.depletion.record(self.turn, &self.board);
+
+        // Count where our Ships are spending their time.
+        let ships = &self.ships;
+        let my_ships = self.players[&self.my_id].ship_ids.iter().map(|id| &ships[id]);
+        self.visits.record(my_ships, previous_ships);
+
+        // Compute how dangerous each Cell is.
+        let mut enemies: Vec<_> = self
+            .ships
+            .values()
+            .filter(|ship| ship.player_id != self.my_id)
+            .cloned()
+            .collect();
+        enemies.sort_by_key(|ship| ship.id);
+        self.danger = DangerMap::compute(
+            &self.board,
+            &enemies,
+            &structures,
+            constants::get().max_halite,
+        );
+
+        // Compute where our Ships would be inspired.
+        let constants = constants::get();
+        if constants.inspiration_enabled {
+            self.inspiration = InspirationMap::compute(
+                &self.board,
+                &enemies,
+                constants.inspiration_radius,
+                constants.inspiration_ship_count,
+                constants.inspired_bonus_multiplier,
+                constants.extract_ratio,
+                constants.inspired_extract_ratio,
+            );
+        }
+
+        // Check how well we predicted what our Ships would mine, and predict this turn.
+        self.record_yields();
+
+        // Compute which way the halite is richer.
+        self.gradient = GradientMap::compute(&self.board);
+
+        // Predict where enemy Ships will be next turn.
+        let mut occupancy = mem::replace(&mut self.occupancy, Occupancy::new(0, 0));
+        occupancy.record(self, previous_ships);
+        self.occupancy = occupancy;
+
+        // Compute the cost of getting home, including waiting for enemy Ships and our own Ships to
+        // move out of the way, and for the congestion around our structures.
+        let targets: Vec<_> = structures
+            .iter()
+            .map(|p| (*p, 2.0 * self.congestion.blocked_ratio(*p)))
+            .collect();
+        let is_mine = |position: Position| {
+            self.board[position]
+                .ships
+                .iter()
+                .any(|id| self.ships.get(id).is_some_and(|s| s.player_id == self.my_id))
+        };
+        let home_costs = CostMap::compute_until(
+            &self.board,
+            &targets,
+            |p| self.occupancy[p] + if is_mine(p) { 0.5 } else { 0.0 },
+            constants.move_cost_ratio,
+            self.effort.budget((self.board.width * self.board.height) as usize),
+            self.turn_deadline(),
+        );
+        self.home_costs = home_costs;
+        self.home_bands = Bands::compute(&self.board, &structures);
+
+        // Partition the Board by the closest structures.
+        let mut all_structures: Vec<_> = structures.iter().map(|p| (self.my_id, *p)).collect();
+        all_structures.extend(self.enemy_structures());
+        self.territory = Territory::compute(&self.board, &all_structures);
+
+        // Detect enemy Ships camping at our Shipyard.
+        let mut blockade = mem::take(&mut self.blockade);
+        let was_active = blockade.is_active();
+        let previous_campers = blockade.campers.clone();
+        blockade.record(self);
+        for camper in &blockade.campers {
+            if !previous_campers.contains(camper) {
+                warn!("Enemy Ship {} is camping at our Shipyard", camper);
+                self.events.push(Event::ShipyardCamper(*camper));
+            }
+        }
+        match (was_active, blockade.is_active()) {
+            (false, true) => self.events.push(Event::ShipyardBlockaded),
+            (true, false) => self.events.push(Event::ShipyardBlockadeLifted),
+            _ => {}
+        }
+        self.blockade = blockade;
+
+        // Update the statistics about our opponents.
+        let mut opponents = mem::replace(&mut self.opponents, Opponents::new());
+        opponents.record(self, previous_ships, previous_dropoffs);
+        self.opponents = opponents;
+
+        // Update the live statistics about every Player.
+        let mut scoreboard = mem::replace(&mut self.scoreboard, Scoreboard::new());
+        scoreboard.record(self, previous_ships);
+        self.scoreboard = scoreboard;
+
+        // Find where Ships collided and dropped their halite.
+        let collisions = collision::detect(self, previous_ships, previous_dropoffs, previous_board);
+        for pile in &collisions {
+            info!(
+                "Ships collided at {:?}, dropping {} halite",
+                pile.position, pile.halite
+            );
+            self.events.push(Event::Collision(pile.position));
+        }
+
+        // Forget about piles that have been mined.
+        let board = &self.board;
+        self.halite_piles
+            .retain(|pile| board[pile.position].halite > pile.baseline);
+        self.halite_piles.extend(collisions);
+    }
+
     /// Spawn a Ship at the Shipyard.
     ///
     /// This does not just tell the engine that we want to spawn a new Ship, it also adds a new Ship
-    /// to the Board, so that we can use it when considering collisions.
+    /// to the Board, so that we can use it when considering collisions. If the Ship can't be
+    /// spawned, see `try_spawn_ship`, a warning is logged and nothing changes.
     pub fn spawn_ship(&mut self) {
+        if let Err(err) = self.try_spawn_ship() {
+            warn!("{}", err);
+        }
+    }
+
+    /// Spawn a Ship at the Shipyard, or return an `Error::IllegalCommand`.
+    ///
+    /// A Ship can't be spawned if one is already being spawned, if we can't afford it on top of
+    /// the queued conversions, or if one of our Ships will be on the Shipyard. Enemy Ships on the
+    /// Shipyard don't stop a spawn, since they will be destroyed.
+    pub fn try_spawn_ship(&mut self) -> Result<()> {
+        // The Shipyard position is always where Ships are spawned.
+        let position = self.me().shipyard.position;
+
+        let spent: usize = self
+            .commands
+            .iter()
+            .filter(|command| matches!(command, Command::ConvertToDropoff(_)))
+            .map(|command| command.cost(self))
+            .sum();
+        let blocked = self.board[position].ships.iter().any(|id| {
+            self.ships
+                .get(id)
+                .is_some_and(|ship| ship.player_id == self.my_id)
+        });
+        let reason = if self.is_spawning() {
+            Some("a Ship is already being spawned")
+        } else if self.me().halite < spent + constants::get().new_entity_halite_cost {
+            Some("not enough halite")
+        } else if blocked {
+            Some("one of our Ships will be on the Shipyard")
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            return Err(Error::IllegalCommand {
+                command: Command::Spawn,
+                turn: self.turn,
+                reason: reason.to_string(),
+            }.into());
+        }
+
         // Add a new Ship with the next possible ShipId number. This should not be relied upon,
         // because it could change on the next frame.
         let id = if let Some(ship_id) = self.ships.keys().max() {
@@ -245,20 +884,70 @@ impl Game {
             ShipId::new(0)
         };
 
-        // The Shipyard position is always where Ships are spawned.
-        let position = self.me().shipyard.position;
-
         // Create a new Ship!
         let ship = Ship::new(id, self.my_id, position, 0);
 
         // Add the Ship to the Board.
-        self.board[position].ship = Some(ship.id);
+        self.board[position].add_ship(ship.id);
 
         // Insert the new Ship into our list of Ships.
         self.ships.insert(ship.id, ship);
 
         // Finally add the Spawn command to tell the engine that we want to spawn a new Ship.
-        self.commands.push(Command::Spawn);
+        self.queue(Command::Spawn);
+        Ok(())
+    }
+
+    /// Spawn a Ship at the Shipyard if it is legal and safe, and return whether it was spawned.
+    ///
+    /// On top of the checks in `try_spawn_ship`, which cover our halite and our Ships that are on
+    /// or moving onto the Shipyard, this doesn't spawn while an enemy Ship camps next to the
+    /// Shipyard, where it could crash into the new Ship. A camper on the Shipyard itself doesn't
+    /// stop a spawn, since spawning destroys it.
+    pub fn try_spawn(&mut self) -> bool {
+        let shipyard = self.me().shipyard.position;
+        let threatened = self.blockade.campers.iter().any(|id| {
+            self.ships
+                .get(id)
+                .is_some_and(|ship| ship.position != shipyard)
+        });
+        if threatened {
+            debug!("Not spawning while enemy Ships camp next to our Shipyard");
+            return false;
+        }
+        match self.try_spawn_ship() {
+            Ok(()) => true,
+            Err(err) => {
+                debug!("{}", err);
+                false
+            }
+        }
+    }
+
+    /// Return the value of the halite in the Cell at the given Position.
+    ///
+    /// This is the halite in the Cell plus the expected bonus from being inspired there.
+    pub fn cell_value(&self, position: Position) -> f64 {
+        self.board[position].halite as f64 + self.inspiration[position]
+    }
+
+    /// Return the expected number of turns it will take the given Ship to get to our closest
+    /// structure.
+    ///
+    /// This accounts for waiting on enemy Ships, our own Ships, and congestion around structures.
+    pub fn turns_to_home(&self, ship_id: ShipId) -> usize {
+        self.home_costs
+            .turns(self.ships[&ship_id].position)
+            .ceil() as usize
+    }
+
+    /// Return the closest pile of dropped halite within the given distance of a Position.
+    pub fn nearest_halite_pile(&self, position: Position, max_distance: usize) -> Option<Position> {
+        self.halite_piles
+            .iter()
+            .map(|pile| pile.position)
+            .filter(|p| self.board.distance(position, *p) <= max_distance)
+            .min_by_key(|p| self.board.distance(position, *p))
     }
 
     /// Return the best direction for the given Ship to move to.
@@ -271,54 +960,816 @@ impl Game {
         // Get the surrounding and current Cells.
         let mut cells: Vec<_> = Direction::all()
             .iter()
-            .map(|d| (Some(*d), self.board[ship.position + *d]))
+            .map(|d| (Some(*d), &self.board[ship.position + *d]))
             .collect();
-        cells.push((None, self.board[ship.position]));
+        cells.push((None, &self.board[ship.position]));
 
-        // Order Cells from least halite to most halite.
-        cells.sort_by_key(|(_, c)| !c.halite);
+        // Order Cells from most valuable to least valuable.
+        cells.sort_by(|(_, a), (_, b)| {
+            self.cell_value(b.position)
+                .partial_cmp(&self.cell_value(a.position))
+                .unwrap()
+        });
 
-        // Loop through Cells and return the first unoccupied one.
+        // Loop through Cells and return the first one we can move to.
         for (direction, cell) in cells {
-            if !cell.is_occupied() {
+            if self.is_passable(ship_id, cell.position) {
                 return direction;
             }
         }
         None
     }
 
+    /// Register a Position where we intend to build a Dropoff.
+    ///
+    /// Until the Dropoff is built it is considered a deposit point, so that returning Ships and
+    /// the builder Ship converge there before it exists.
+    pub fn plan_dropoff(&mut self, position: Position) {
+        let position = position.normalized(self.board.width, self.board.height);
+        if !self.planned_dropoffs.contains(&position) {
+            self.planned_dropoffs.push(position);
+        }
+    }
+
+    /// Remove a previously planned Dropoff Position.
+    pub fn cancel_planned_dropoff(&mut self, position: Position) {
+        let position = position.normalized(self.board.width, self.board.height);
+        self.planned_dropoffs.retain(|p| *p != position);
+    }
+
+    /// Return the Positions of our Shipyard and Dropoffs.
+    pub fn structure_positions(&self) -> Vec<Position> {
+        let me = self.me();
+        let mut positions = vec![me.shipyard.position];
+        positions.extend(me.dropoff_ids.iter().map(|id| self.dropoffs[id].position));
+        positions
+    }
+
+    /// Return the closest of our Shipyard and Dropoffs to the given Position.
+    pub fn nearest_structure(&self, position: Position) -> Position {
+        self.structure_positions()
+            .into_iter()
+            .min_by_key(|p| self.board.distance(position, *p))
+            .unwrap()
+    }
+
+    /// Return the Positions of all our opponents' Shipyards and Dropoffs, with their owners.
+    pub fn enemy_structures(&self) -> Vec<(PlayerId, Position)> {
+        let shipyards = self
+            .enemies()
+            .map(|player| (player.id, player.shipyard.position));
+        let dropoffs = self
+            .dropoffs
+            .values()
+            .filter(|dropoff| dropoff.player_id != self.my_id)
+            .map(|dropoff| (dropoff.player_id, dropoff.position));
+        shipyards.chain(dropoffs).collect()
+    }
+
+    /// Return the Shipyard or Dropoff at the given Position, if there is one.
+    pub fn structure_at(&self, position: Position) -> Option<&dyn Entity> {
+        match self.board[position].structure? {
+            Structure::Shipyard(id) => self
+                .players
+                .values()
+                .map(|player| &player.shipyard)
+                .find(|shipyard| shipyard.id == id)
+                .map(|shipyard| shipyard as &dyn Entity),
+            Structure::Dropoff(id) => self.dropoffs.get(&id).map(|dropoff| dropoff as &dyn Entity),
+        }
+    }
+
+    /// Return the Player that owns the Shipyard or Dropoff at the given Position, if there is one.
+    pub fn structure_owner(&self, position: Position) -> Option<PlayerId> {
+        self.structure_at(position).map(|structure| structure.owner())
+    }
+
+    /// Return the Positions where our Ships can deposit halite.
+    ///
+    /// This includes our Shipyard, our Dropoffs, and any planned Dropoffs.
+    pub fn deposit_positions(&self) -> Vec<Position> {
+        let mut positions = self.structure_positions();
+        positions.extend(self.planned_dropoffs.iter().cloned());
+        positions
+    }
+
+    /// Return the closest deposit Position to the given Position.
+    pub fn nearest_deposit(&self, position: Position) -> Position {
+        self.deposit_positions()
+            .into_iter()
+            .min_by_key(|p| self.board.distance(position, *p))
+            .unwrap()
+    }
+
     /// Move a Ship in the given Direction.
+    ///
+    /// Moving `Direction::Still` is the same as collecting halite. This replaces any command
+    /// already given to the Ship this turn.
+    ///
+    /// # Panics
+    ///
+    /// If the Ship doesn't exist, see `try_move_ship`.
     pub fn move_ship(&mut self, ship_id: ShipId, direction: Direction) {
+        if direction == Direction::Still {
+            return self.collect_halite(ship_id);
+        }
+        if !self.is_revocable(ship_id) {
+            warn!("Ship {} was already given a command that has been sent", ship_id);
+            return;
+        }
+        if let Err(err) = self.try_move_ship(ship_id, direction) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Move a Ship in the given Direction, or return an Error if it isn't one of ours or its
+    /// command has already been sent.
+    ///
+    /// Moving onto a Cell with another of our Ships is allowed, and left to `resolve_conflicts`.
+    pub fn try_move_ship(&mut self, ship_id: ShipId, direction: Direction) -> Result<()> {
+        if direction == Direction::Still {
+            return self.try_collect_halite(ship_id);
+        }
+        let owner = self.find_ship(ship_id, "move")?.player_id;
+        if owner != self.my_id {
+            return Err(Error::IllegalCommand {
+                command: Command::Move(ship_id, direction),
+                turn: self.turn,
+                reason: format!("ship {} belongs to player {}", ship_id, owner),
+            }.into());
+        }
+        self.revoke_for(Command::Move(ship_id, direction))?;
+
         // Get the specified Ship.
-        let ship = self
-            .ships
-            .get_mut(&ship_id)
-            .expect(&format!("ship {} does not exist", ship_id));
+        let ship = self.ships.get_mut(&ship_id).unwrap();
 
         // Compute the resultant position.
         let position = ship.position + direction;
 
         // Update the Board and Ship with the new Position.
-        self.board[ship.position].ship = None;
-        self.board[position].ship = Some(ship_id);
-        ship.position = position;
+        self.board[ship.position].remove_ship(ship_id);
+        self.board[position].add_ship(ship_id);
+        ship.position = position.normalized(self.board.width, self.board.height);
 
         // Finally add the Move command to tell the engine that we want to move the Ship.
-        self.commands.push(Command::Move(ship_id, direction));
+        self.queue(Command::Move(ship_id, direction));
+        Ok(())
     }
 
     /// Make a Ship collect halite in its current location.
+    ///
+    /// This replaces any command already given to the Ship this turn.
     pub fn collect_halite(&mut self, ship_id: ShipId) {
-        let command = Command::Collect(ship_id);
+        if let Err(err) = self.revoke_for(Command::Collect(ship_id)) {
+            warn!("{}", err);
+            return;
+        }
+        self.queue(Command::Collect(ship_id));
+    }
+
+    /// Make a Ship collect halite in its current location, or return an `Error::UnknownShip`, or
+    /// an `Error::IllegalCommand` if its command has already been sent.
+    pub fn try_collect_halite(&mut self, ship_id: ShipId) -> Result<()> {
+        self.find_ship(ship_id, "collect with")?;
+        self.revoke_for(Command::Collect(ship_id))?;
+        self.queue(Command::Collect(ship_id));
+        Ok(())
+    }
+
+    /// Convert a Ship into a Dropoff at its current location.
+    ///
+    /// This replaces any command already given to the Ship this turn.
+    pub fn convert_to_dropoff(&mut self, ship_id: ShipId) {
+        if let Err(err) = self.revoke_for(Command::ConvertToDropoff(ship_id)) {
+            warn!("{}", err);
+            return;
+        }
+        self.queue(Command::ConvertToDropoff(ship_id));
+    }
+
+    /// Convert a Ship into a Dropoff at its current location, or return an `Error::UnknownShip`,
+    /// or an `Error::IllegalCommand` if its command has already been sent.
+    pub fn try_convert_to_dropoff(&mut self, ship_id: ShipId) -> Result<()> {
+        self.find_ship(ship_id, "convert")?;
+        self.revoke_for(Command::ConvertToDropoff(ship_id))?;
+        self.queue(Command::ConvertToDropoff(ship_id));
+        Ok(())
+    }
+
+    /// Return the given Ship, or an `Error::UnknownShip` saying what it was needed for.
+    pub fn find_ship(&self, ship_id: ShipId, context: &'static str) -> Result<&Ship> {
+        self.ships.get(&ship_id).ok_or_else(|| {
+            Error::UnknownShip {
+                ship_id,
+                turn: self.turn,
+                context,
+            }.into()
+        })
+    }
+
+    /// Return the command queued for the given Ship this turn, if any.
+    pub fn command(&self, ship_id: ShipId) -> Option<Command> {
+        self.commands.get(ship_id)
+    }
+
+    /// Return whether the command queued for the given Ship, if any, can still be revoked.
+    ///
+    /// Commands can't be revoked once they have been streamed to the engine.
+    pub fn is_revocable(&self, ship_id: ShipId) -> bool {
+        self.commands
+            .index_of(ship_id)
+            .is_none_or(|index| index >= self.streamed)
+    }
+
+    /// Take back the command queued for the given Ship this turn, and return it.
+    ///
+    /// If the Ship was moving it is put back where it was. Returns `None` if there was no command
+    /// or it can't be revoked.
+    pub fn revoke(&mut self, ship_id: ShipId) -> Option<Command> {
+        if !self.is_revocable(ship_id) {
+            return None;
+        }
+        let origin = self.origin(ship_id);
+        let command = self.commands.remove(ship_id);
+        if let (Some(Command::Move(..)), Some(origin)) = (command, origin) {
+            let ship = self.ships.get_mut(&ship_id).unwrap();
+            self.board[ship.position].remove_ship(ship_id);
+            self.board[origin].add_ship(ship_id);
+            ship.position = origin;
+        }
+        command
+    }
+
+    /// Revoke the command for the Ship of the given command so that it can be given that one
+    /// instead, or return an `Error::IllegalCommand` if its command has already been sent.
+    fn revoke_for(&mut self, command: Command) -> Result<()> {
+        if let Some(ship_id) = command.ship_id() {
+            if !self.is_revocable(ship_id) {
+                return Err(Error::IllegalCommand {
+                    command,
+                    turn: self.turn,
+                    reason: format!("ship {} was already given a command that was sent", ship_id),
+                }.into());
+            }
+            self.revoke(ship_id);
+        }
+        Ok(())
+    }
+
+    /// Add a command to be sent to the engine.
+    fn queue(&mut self, command: Command) {
         self.commands.push(command);
     }
 
-    /// End the turn and submit the commands.
-    pub fn end_turn(&self) {
-        let engine = Engine::new();
+    /// Send the commands that haven't been streamed yet to the given EngineIo, without ending the
+    /// turn.
+    ///
+    /// Call this after each step of the strategy when using `Submission::Streaming`, with the same
+    /// EngineIo that the turn will be ended with.
+    pub fn stream_with(&mut self, io: &mut dyn EngineIo) -> Result<()> {
+        {
+            let mut engine = Engine::new(io);
+            for command in &self.commands[self.streamed..] {
+                engine.send(command);
+            }
+        }
+        self.streamed = self.commands.len();
+        io.flush()
+    }
+
+    /// Return whether a Ship is going to be spawned this turn.
+    pub fn is_spawning(&self) -> bool {
+        self.commands.contains(&Command::Spawn)
+    }
+
+    /// Return the Ships that were destroyed since the previous turn.
+    pub fn destroyed_ships(&self) -> Vec<ShipId> {
+        self.events
+            .iter()
+            .filter_map(|event| match *event {
+                Event::ShipDestroyed { ship_id, .. } => Some(ship_id),
+                _ => None,
+            }).collect()
+    }
+
+    /// Return the Ships from the previous turn that no longer exist, ordered by id.
+    ///
+    /// Ships that were converted into a Dropoff since the previous turn are not included.
+    pub fn vanished_ships<'a>(
+        &self,
+        previous_ships: &'a HashMap<ShipId, Ship>,
+        previous_dropoffs: &HashMap<DropoffId, Dropoff>,
+    ) -> Vec<&'a Ship> {
+        let mut vanished: Vec<_> = previous_ships
+            .values()
+            .filter(|ship| !self.ships.contains_key(&ship.id))
+            .filter(|ship| {
+                !self.dropoffs.values().any(|d| {
+                    d.player_id == ship.player_id
+                        && d.position == ship.position
+                        && !previous_dropoffs.contains_key(&d.id)
+                })
+            }).collect();
+        vanished.sort_by_key(|ship| ship.id);
+        vanished
+    }
+
+    /// Return whether a command has already been given to the given Ship this turn.
+    pub fn has_command(&self, ship_id: ShipId) -> bool {
+        self.commands.get(ship_id).is_some()
+    }
+
+    /// Return the commands that the engine would accept.
+    ///
+    /// This drops commands for Ships that are not ours or no longer exist, repeated commands for
+    /// the same Ship, and Spawns and conversions that we can't afford.
+    pub fn legal_commands(&self) -> Vec<Command> {
+        let constants = constants::get();
+        let me = self.me();
+        let mut halite = me.halite;
+        let mut commanded = HashSet::new();
+        let mut spawned = false;
+        let mut legal = Vec::with_capacity(self.commands.len());
+
         for command in &self.commands {
-            engine.send(command);
+            let is_legal = match *command {
+                Command::Spawn => {
+                    let affordable = !spawned && halite >= constants.new_entity_halite_cost;
+                    if affordable {
+                        halite -= constants.new_entity_halite_cost;
+                        spawned = true;
+                    }
+                    affordable
+                }
+                Command::ConvertToDropoff(id) => {
+                    let exists = me.ship_ids.contains(&id) && commanded.insert(id);
+                    let ship = self.ships.get(&id);
+                    let credit = ship.map_or(0, |s| s.halite + self.board[s.position].halite);
+                    let affordable = halite + credit >= constants.dropoff_cost;
+                    let free = ship.is_some_and(|s| !self.board[s.position].has_structure());
+                    if exists && affordable && free {
+                        halite = halite + credit - constants.dropoff_cost;
+                    }
+                    exists && affordable && free
+                }
+                Command::Collect(id) | Command::Move(id, _) => {
+                    me.ship_ids.contains(&id) && commanded.insert(id)
+                }
+            };
+
+            if is_legal {
+                legal.push(*command);
+            } else {
+                warn!("Dropping illegal command {:?}", command);
+            }
         }
-        engine.flush();
+
+        legal
+    }
+
+    /// Return our Ships that haven't been given a command this turn.
+    pub fn uncommanded_ships(&self) -> Vec<ShipId> {
+        self.me()
+            .ship_ids
+            .iter()
+            .filter(|id| !self.has_command(**id))
+            .cloned()
+            .collect()
+    }
+
+    /// Send no commands for a turn whose frame was skipped, see `update_with`.
+    pub fn skip_turn(&self) -> Result<()> {
+        self.skip_turn_with(&mut StdIo::new())
+    }
+
+    /// Send no commands for a turn whose frame was skipped, to the given EngineIo.
+    pub fn skip_turn_with(&self, io: &mut dyn EngineIo) -> Result<()> {
+        Engine::new(io).flush()
+    }
+
+    /// End the turn and submit the commands.
+    ///
+    /// On the final turn, or if the engine treats errors strictly, illegal commands are dropped
+    /// first so that mistakes in strategy code don't get us kicked out of the Game. Ships that
+    /// weren't given a command are logged, since they will stay still.
+    pub fn end_turn(&self) -> Result<TurnSummary> {
+        self.end_turn_with(&mut StdIo::new())
+    }
+
+    /// End the turn and submit the commands to the given EngineIo.
+    pub fn end_turn_with(&self, io: &mut dyn EngineIo) -> Result<TurnSummary> {
+        self.check_invariants();
+        let constants = constants::get();
+        let commands = if self.submission == Submission::Streaming {
+            self.commands[self.streamed..].to_vec()
+        } else if self.turn >= constants.max_turns || constants.strict_errors {
+            self.legal_commands()
+        } else {
+            self.commands.to_vec()
+        };
+
+        timed!{ "serialization" => {
+            let mut engine = Engine::new(io);
+            for command in &commands {
+                engine.send(command);
+            }
+            engine.flush()?;
+        }};
+
+        if self.turn >= constants.max_turns {
+            self.visits.dump();
+            info!(
+                "Expected yields were off by {:.1} halite on average, with a bias of {:.1}, \
+                 over {} mining turns",
+                self.yields.mean_absolute_error(),
+                self.yields.bias(),
+                self.yields.samples()
+            );
+            timing::report();
+        }
+
+        let uncommanded = self.uncommanded_ships();
+        if !uncommanded.is_empty() {
+            warn!("Ships without a command this turn: {:?}", uncommanded);
+        }
+        let commands = if self.submission == Submission::Streaming {
+            self.commands.to_vec()
+        } else {
+            commands
+        };
+        Ok(TurnSummary {
+            turn: self.turn,
+            commands,
+            uncommanded,
+            elapsed: self.elapsed(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONSTANTS: &str = r#"{
+        "CAPTURE_ENABLED":false,
+        "CAPTURE_RADIUS":3,
+        "DEFAULT_MAP_HEIGHT":8,
+        "DEFAULT_MAP_WIDTH":8,
+        "DROPOFF_COST":4000,
+        "DROPOFF_PENALTY_RATIO":4,
+        "EXTRACT_RATIO":4,
+        "FACTOR_EXP_1":2.0,
+        "FACTOR_EXP_2":2.0,
+        "INITIAL_ENERGY":5000,
+        "INSPIRATION_ENABLED":true,
+        "INSPIRATION_RADIUS":4,
+        "INSPIRATION_SHIP_COUNT":2,
+        "INSPIRED_BONUS_MULTIPLIER":2.0,
+        "INSPIRED_EXTRACT_RATIO":4,
+        "INSPIRED_MOVE_COST_RATIO":10,
+        "MAX_CELL_PRODUCTION":1000,
+        "MAX_ENERGY":1000,
+        "MAX_PLAYERS":16,
+        "MAX_TURNS":400,
+        "MAX_TURN_THRESHOLD":64,
+        "MIN_CELL_PRODUCTION":900,
+        "MIN_TURNS":400,
+        "MIN_TURN_THRESHOLD":32,
+        "MOVE_COST_RATIO":10,
+        "NEW_ENTITY_ENERGY_COST":1000,
+        "PERSISTENCE":0.7,
+        "SHIPS_ABOVE_FOR_CAPTURE":3,
+        "STRICT_ERRORS":false,
+        "game_seed":1
+    }"#;
+
+    #[test]
+    fn test_game_start_and_update() {
+        // The engine sends the constants on a single line.
+        let mut input = CONSTANTS.replace("\n", "");
+        input.push_str(
+            "
+2 0
+0 2 2
+1 6 6
+8 8
+10 10 10 10 10 10 10 10
+10 10 10 10 10 10 10 10
+10 10 10 10 10 10 10 10
+10 10 10 10 10 10 10 10
+10 10 10 10 10 10 10 10
+10 10 10 10 10 10 10 10
+10 10 10 10 10 10 10 10
+10 10 10 10 10 10 10 10",
+        );
+        let (mut game, mut io) = fixtures::start(&input);
+        assert_eq!(game.my_id, PlayerId(0));
+        assert_eq!(game.board.width, 8);
+        assert_eq!(game.players[&PlayerId(1)].shipyard.position, Position::new(6, 6));
+
+        game.ready_with(&mut io, "MyBot").unwrap();
+        assert_eq!(io.output, "MyBot\n");
+
+        io.push(
+            "1
+0 1 0 4000
+0 2 3 0
+1 0 0 5000
+1
+2 2 0",
+        );
+        game.update_with(&mut io).unwrap();
+        assert_eq!(game.turn, 1);
+        assert_eq!(game.me().halite, 4000);
+        assert_eq!(game.me().ship_ids, vec![ShipId(0)]);
+        assert_eq!(game.board[Position::new(2, 3)].ship(), Some(ShipId(0)));
+        assert_eq!(game.board[Position::new(2, 2)].halite, 0);
+
+        game.move_ship(ShipId(0), Direction::South);
+        let summary = game.end_turn_with(&mut io).unwrap();
+        assert_eq!(summary.commands, vec![Command::Move(ShipId(0), Direction::South)]);
+        assert!(summary.uncommanded.is_empty());
+        assert_eq!(io.output, "MyBot\nm 0 s \n");
+
+        // Ship 0 was destroyed.
+        io.push(
+            "2
+0 0 0 4000
+1 0 0 5000
+0",
+        );
+        game.update_with(&mut io).unwrap();
+        assert!(game.me().ship_ids.is_empty());
+        assert_eq!(game.destroyed_ships(), vec![ShipId(0)]);
+    }
+
+    #[test]
+    fn test_ship_is_full_with() {
+        fixtures::game("Y");
+        let mut constants = constants::try_get().unwrap().clone();
+        constants.max_halite = 500;
+        let ship = Ship::new(ShipId(0), PlayerId(0), Position::new(0, 0), 500);
+        assert!(ship.is_full_with(&constants));
+        constants.max_halite = 1000;
+        assert!(!ship.is_full_with(&constants));
+    }
+
+    #[test]
+    fn test_ship_movement_economics() {
+        let game = fixtures::game("a Y");
+        let mut ship = game.ships[&ShipId(0)];
+        assert_eq!(ship.cargo_space(), 1000);
+        ship.halite = 40;
+        assert_eq!(ship.cargo_space(), 960);
+
+        let cell = Cell::new(Position::new(0, 0), 409);
+        assert_eq!(ship.move_cost_from(&cell), 40);
+        assert!(ship.can_move_from(&cell));
+        ship.halite = 39;
+        assert!(!ship.can_move_from(&cell));
+        ship.halite = 1100;
+        assert_eq!(ship.cargo_space(), 0);
+    }
+
+    #[test]
+    fn test_game_try_move_ship() {
+        let mut game = fixtures::game(". a Y .");
+        game.turn = 7;
+        game.try_move_ship(ShipId(0), Direction::West).unwrap();
+        assert_eq!(game.ships[&ShipId(0)].position, Position::new(0, 0));
+
+        let err = game.try_move_ship(ShipId(3), Direction::West).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unable to move ship 3 on turn 7: it does not exist"
+        );
+        assert!(game.try_collect_halite(ShipId(3)).is_err());
+        assert!(game.try_convert_to_dropoff(ShipId(3)).is_err());
+        assert_eq!(game.commands.len(), 1);
+    }
+
+    #[test]
+    fn test_game_try_spawn_ship() {
+        let mut game = fixtures::game(". a Y .");
+        assert!(game.try_spawn_ship().is_err());
+        assert!(!game.is_spawning());
+
+        game.players.get_mut(&PlayerId(0)).unwrap().halite = 5000;
+        game.move_ship(ShipId(0), Direction::East);
+        assert!(game.try_spawn_ship().is_err());
+        game.move_ship(ShipId(0), Direction::West);
+        game.try_spawn_ship().unwrap();
+        assert!(game.is_spawning());
+        assert!(game.try_spawn_ship().is_err());
+        assert_eq!(game.ships.len(), 2);
+        assert_eq!(game.commands.len(), 2);
+
+        let enemy = Ship::new(ShipId(5), PlayerId(1), Position::new(3, 0), 0);
+        game.ships.insert(enemy.id, enemy);
+        game.board[enemy.position].add_ship(enemy.id);
+        assert!(game.try_move_ship(enemy.id, Direction::West).is_err());
+        assert_eq!(game.ships[&enemy.id].position, Position::new(3, 0));
+    }
+
+    #[test]
+    fn test_game_structure_at() {
+        let mut game = fixtures::game(". Y . .");
+        let dropoff = Dropoff::new(DropoffId(3), PlayerId(1), Position::new(3, 0));
+        game.dropoffs.insert(dropoff.id, dropoff);
+        game.board[dropoff.position].structure = Some(Structure::Dropoff(dropoff.id));
+
+        let shipyard = game.structure_at(Position::new(1, 0)).unwrap();
+        assert_eq!(shipyard.position(), game.me().shipyard.position);
+        assert_eq!(game.structure_owner(Position::new(1, 0)), Some(PlayerId(0)));
+        assert_eq!(game.structure_owner(Position::new(3, 0)), Some(PlayerId(1)));
+        assert!(game.structure_at(Position::new(0, 0)).is_none());
+        assert_eq!(game.structure_owner(Position::new(2, 0)), None);
+    }
+
+    #[test]
+    fn test_game_nearest_halite_pile() {
+        let mut game = fixtures::game(". a . . . 300 . . . 500 Y");
+        let position = game.ships[&ShipId(0)].position;
+        for x in &[5, 9] {
+            let position = Position::new(*x, 0);
+            game.halite_piles.push(HalitePile {
+                position,
+                halite: game.board[position].halite,
+                baseline: 0,
+                turn: 0,
+            });
+        }
+
+        // The Board wraps around, so the pile past the Shipyard is the closest.
+        assert_eq!(game.nearest_halite_pile(position, 2), None);
+        assert_eq!(
+            game.nearest_halite_pile(position, 4),
+            Some(Position::new(9, 0))
+        );
+
+        game.halite_piles.pop();
+        assert_eq!(game.nearest_halite_pile(position, 3), None);
+        assert_eq!(
+            game.nearest_halite_pile(position, 4),
+            Some(Position::new(5, 0))
+        );
+    }
+
+    #[test]
+    fn test_game_try_spawn() {
+        let mut game = fixtures::game(". . Y . a");
+        assert!(!game.try_spawn());
+
+        game.players.get_mut(&PlayerId(0)).unwrap().halite = 5000;
+        let enemy = Ship::new(ShipId(5), PlayerId(1), Position::new(3, 0), 0);
+        game.ships.insert(enemy.id, enemy);
+        game.board[enemy.position].add_ship(enemy.id);
+        game.blockade.campers = vec![enemy.id];
+        assert!(!game.try_spawn());
+
+        // A camper on the Shipyard is destroyed by spawning.
+        game.board[enemy.position].remove_ship(enemy.id);
+        game.ships.get_mut(&enemy.id).unwrap().position = Position::new(2, 0);
+        game.board[Position::new(2, 0)].add_ship(enemy.id);
+        assert!(game.try_spawn());
+        assert!(game.is_spawning());
+        assert!(!game.try_spawn());
+    }
+
+    #[test]
+    fn test_player_ships_and_enemies() {
+        let mut game = fixtures::game(". a Y b .");
+        let dropoff = Dropoff::new(DropoffId(0), PlayerId(0), Position::new(0, 0));
+        game.dropoffs.insert(dropoff.id, dropoff);
+        game.players
+            .get_mut(&PlayerId(0))
+            .unwrap()
+            .dropoff_ids
+            .extend(&[dropoff.id, DropoffId(9)]);
+        let ids: Vec<_> = game.me().ships(&game).map(|ship| ship.id).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.iter().all(|id| game.ships[id].player_id == PlayerId(0)));
+        assert_eq!(game.me().dropoffs(&game).collect::<Vec<_>>(), vec![&dropoff]);
+        assert_eq!(game.enemies().count(), 0);
+
+        let shipyard = Shipyard {
+            id: ShipyardId(1),
+            player_id: PlayerId(1),
+            position: Position::new(4, 0),
+        };
+        game.players
+            .insert(PlayerId(1), Player::new(PlayerId(1), shipyard));
+        let enemies: Vec<_> = game.enemies().map(|player| player.id).collect();
+        assert_eq!(enemies, vec![PlayerId(1)]);
+        assert_eq!(game.players[&PlayerId(1)].ships(&game).count(), 0);
+    }
+
+    #[test]
+    fn test_game_update_validation() {
+        let invalid = |input: &str| {
+            let mut game = fixtures::game(". a Y .");
+            let err = game.update_with(&mut StringIo::new(input)).unwrap_err();
+            match Error::find(&err) {
+                Some(Error::InvalidFrame(msg)) => msg.clone(),
+                other => panic!("expected an invalid frame, got {:?}", other),
+            }
+        };
+        assert_eq!(
+            invalid("1\n0 1 0 0\n0 4 0 0\n0"),
+            "Position { x: 4, y: 0 } is outside the 4x1 Board"
+        );
+        assert_eq!(
+            invalid("1\n0 5 0 0\n"),
+            "5 Ships don't fit on the 4x1 Board"
+        );
+        assert_eq!(invalid("1\n3 0 0 0\n0"), "unknown player 3");
+        assert_eq!(invalid("0\n"), "turn 0 does not come after turn 0");
+    }
+
+    #[test]
+    fn test_game_resync() {
+        let mut game = fixtures::game(". a Y .");
+        game.resync = true;
+        let mut io = StringIo::new(
+            "1
+0 1 0 4000
+0 x 0 0
+0
+2
+0 1 0 3000
+0 0 0 20
+0",
+        );
+        match Error::find(&game.update_with(&mut io).unwrap_err()) {
+            Some(Error::SkippedFrame(1)) => {}
+            other => panic!("expected a skipped frame, got {:?}", other),
+        }
+        assert_eq!(game.turn, 0);
+        assert_eq!(game.ships[&ShipId(0)].position, Position::new(1, 0));
+        assert_eq!(game.board[Position::new(1, 0)].ship(), Some(ShipId(0)));
+
+        game.skip_turn_with(&mut io).unwrap();
+        assert_eq!(io.output, "\n");
+        game.update_with(&mut io).unwrap();
+        assert_eq!(game.turn, 2);
+        assert_eq!(game.me().halite, 3000);
+        assert_eq!(game.ships[&ShipId(0)].position, Position::new(0, 0));
+    }
+
+    #[test]
+    fn test_game_revoke_command() {
+        let mut game = fixtures::game(". a . Y");
+        let a = ShipId(0);
+
+        game.collect_halite(a);
+        game.move_ship(a, Direction::East);
+        assert_eq!(game.command(a), Some(Command::Move(a, Direction::East)));
+        assert_eq!(game.commands.len(), 1);
+        assert_eq!(game.ships[&a].position, Position::new(2, 0));
+
+        // Dodge the other way instead.
+        game.move_ship(a, Direction::West);
+        assert_eq!(game.ships[&a].position, Position::new(0, 0));
+        assert!(game.board[Position::new(0, 0)].has_ship(a));
+        assert!(!game.board[Position::new(2, 0)].is_occupied());
+
+        assert_eq!(game.revoke(a), Some(Command::Move(a, Direction::West)));
+        assert_eq!(game.ships[&a].position, Position::new(1, 0));
+        assert!(!game.has_command(a));
+
+        // Streamed commands can't be taken back.
+        game.collect_halite(a);
+        game.stream_with(&mut StringIo::new("")).unwrap();
+        assert!(!game.is_revocable(a));
+        game.move_ship(a, Direction::East);
+        assert_eq!(game.revoke(a), None);
+        assert_eq!(game.command(a), Some(Command::Collect(a)));
+        let err = game.try_move_ship(a, Direction::East).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "illegal command Move(ShipId(0), East) on turn 0: ship 0 was already given a command \
+             that was sent"
+        );
+        assert!(game.try_collect_halite(a).is_err());
+        assert!(game.try_convert_to_dropoff(a).is_err());
+        assert_eq!(game.command(a), Some(Command::Collect(a)));
+    }
+
+    #[test]
+    fn test_game_stream_commands() {
+        let mut game = fixtures::game(". a b Y");
+        let mut io = StringIo::new("");
+        game.submission = Submission::Streaming;
+
+        // Nothing is sent until the commands are streamed to the EngineIo.
+        game.move_ship(ShipId(0), Direction::West);
+        assert_eq!(io.output, "");
+        game.stream_with(&mut io).unwrap();
+        assert_eq!(io.output, "m 0 w ");
+
+        let summary = game.end_turn_with(&mut io).unwrap();
+        assert_eq!(io.output, "m 0 w \n");
+        assert_eq!(summary.commands, vec![Command::Move(ShipId(0), Direction::West)]);
+        assert_eq!(summary.uncommanded, vec![ShipId(1)]);
     }
 }
@@ -0,0 +1,144 @@
+use super::{constants, Direction, Game, Position, ShipId};
+
+/// Options that control how our Ships are navigated.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct NavigationOptions {
+    /// Whether loaded Ships avoid dangerous Cells.
+    pub avoid_danger: bool,
+    /// The cargo at which a Ship is considered loaded.
+    pub loaded_cargo: usize,
+    /// The danger above which a Cell is avoided by loaded Ships.
+    pub danger_tolerance: f64,
+    /// The maximum expected halite loss a Ship may risk by moving to a Cell, if any.
+    pub risk_tolerance: Option<f64>,
+    /// Whether our Ships may move onto our own structures even if they are occupied.
+    ///
+    /// Any halite in Ships destroyed on a structure goes to the owner of the structure, so at the
+    /// end of the Game crashing onto our own structures loses nothing.
+    pub allow_stacking: bool,
+}
+
+impl Default for NavigationOptions {
+    fn default() -> Self {
+        NavigationOptions::new()
+    }
+}
+
+impl NavigationOptions {
+    /// Create new NavigationOptions with reasonable defaults.
+    pub fn new() -> Self {
+        NavigationOptions {
+            avoid_danger: true,
+            loaded_cargo: 500,
+            danger_tolerance: 0.4,
+            risk_tolerance: None,
+            allow_stacking: false,
+        }
+    }
+}
+
+impl Game {
+    /// Return the expected halite lost if the given Ship moves to the given Position.
+    ///
+    /// This is the chance of an enemy Ship being there next turn times the value of the Ship and
+    /// its cargo.
+    pub fn expected_loss(&self, ship_id: ShipId, position: Position) -> f64 {
+        let value = self.ships[&ship_id].halite + constants::get().new_entity_halite_cost;
+        self.occupancy[position] * value as f64
+    }
+
+    /// Return whether several of our Ships may end up at the given Position this turn.
+    ///
+    /// Ships that end up in the same Cell destroy each other, even on a structure. This is only
+    /// allowed on our own Shipyard and Dropoffs while `NavigationOptions::allow_stacking` is set by
+    /// the end-game Recall, since the halite of Ships destroyed there is deposited for us and the
+    /// Ships are no longer needed.
+    pub fn can_stack(&self, position: Position) -> bool {
+        let normalized = position.normalized(self.board.width, self.board.height);
+        self.navigation.allow_stacking && self.structure_positions().contains(&normalized)
+    }
+
+    /// Return whether the given Ship can move to the given Position.
+    ///
+    /// The Position must not be occupied by another Ship or avoided this turn, if the Ship is
+    /// loaded it must not be too dangerous, and the expected loss must be within the risk
+    /// tolerance. Our own structures are always passable when stacking is allowed.
+    pub fn is_passable(&self, ship_id: ShipId, position: Position) -> bool {
+        if self.can_stack(position) {
+            return true;
+        }
+
+        if self.board[position].has_other_ship(ship_id) {
+            return false;
+        }
+
+        let normalized = position.normalized(self.board.width, self.board.height);
+        if self.avoided_positions.contains(&normalized) {
+            return false;
+        }
+
+        let options = &self.navigation;
+        let loaded = self.ships[&ship_id].halite >= options.loaded_cargo;
+        if options.avoid_danger && loaded && self.danger[position] > options.danger_tolerance {
+            return false;
+        }
+
+        options
+            .risk_tolerance
+            .is_none_or(|tolerance| self.expected_loss(ship_id, position) <= tolerance)
+    }
+
+    /// Return the best direction for the given Ship to move to get to the target Position.
+    ///
+    /// Naively picks the first passable Direction that brings the Ship closer.
+    pub fn navigate_to(&self, ship_id: ShipId, target: Position) -> Option<Direction> {
+        let ship = self.ships[&ship_id];
+        self.board
+            .directions_toward(ship.position, target)
+            .into_iter()
+            .find(|d| self.is_passable(ship_id, ship.position + *d))
+    }
+
+    /// Return the best direction for the given Ship to move to get to the closest deposit.
+    pub fn navigate_to_deposit(&self, ship_id: ShipId) -> Option<Direction> {
+        let target = self.nearest_deposit(self.ships[&ship_id].position);
+        self.navigate_to(ship_id, target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixtures, Ship};
+    use super::*;
+
+    #[test]
+    fn test_navigate_to() {
+        let mut game = fixtures::empty_game(8, 3, Position::new(2, 2));
+        let (me, a, b) = (game.my_id, ShipId::new(0), ShipId::new(1));
+        fixtures::add_ship(&mut game, Ship::new(a, me, Position::new(1, 1), 0));
+        fixtures::add_ship(&mut game, Ship::new(b, me, Position::new(2, 1), 0));
+        let target = Position::new(3, 2);
+        let below = Position::new(1, 2);
+
+        // Ships go around other Ships.
+        assert_eq!(game.navigate_to(a, target), Some(Direction::South));
+
+        // And around avoided Positions.
+        game.avoided_positions.push(below);
+        assert_eq!(game.navigate_to(a, target), None);
+        game.avoided_positions.clear();
+
+        // Loaded Ships avoid danger.
+        game.danger.values[below] = 1.0;
+        assert_eq!(game.navigate_to(a, target), Some(Direction::South));
+        game.ships.get_mut(&a).unwrap().halite = 600;
+        assert_eq!(game.navigate_to(a, target), None);
+        game.danger.values[below] = 0.0;
+
+        // Ships don't risk more than the tolerance.
+        game.occupancy.values[below] = 0.5;
+        assert_eq!(game.navigate_to(a, target), Some(Direction::South));
+        game.navigation.risk_tolerance = Some(100.0);
+        assert_eq!(game.navigate_to(a, target), None);
+    }
+}
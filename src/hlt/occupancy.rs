@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::ops::Index;
+
+use super::{Direction, Game, Grid, Position, Ship, ShipId};
+
+/// A grid of the probability that each Cell will be occupied by an enemy Ship next turn.
+///
+/// Each enemy Ship is assumed to either stay still, with a probability learned from how often it
+/// has stayed still before, or move in one of the four directions with equal probability. Ships
+/// that can't afford to move always stay still.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Occupancy {
+    /// The probability for each Position on the map.
+    pub values: Grid<f64>,
+    /// For each enemy Ship, the number of turns it stayed still and the number of turns observed.
+    history: HashMap<ShipId, (usize, usize)>,
+}
+
+impl Occupancy {
+    /// Create a new Occupancy map where nothing will be occupied.
+    pub fn new(width: isize, height: isize) -> Self {
+        Occupancy {
+            values: Grid::new(width, height, 0.0),
+            history: HashMap::new(),
+        }
+    }
+
+    /// Return the probability that the given enemy Ship stays still next turn.
+    pub fn stay_probability(&self, ship_id: ShipId) -> f64 {
+        let (stays, turns) = self.history.get(&ship_id).cloned().unwrap_or((0, 0));
+        // Start from a uniform guess over the five possible moves.
+        (stays as f64 + 0.2) / (turns as f64 + 1.0)
+    }
+
+    /// Return where the given enemy Ship could be next turn, with the probability of each.
+    ///
+    /// The first Position is where the Ship is now, followed by the Position in each Direction.
+    pub fn moves(&self, game: &Game, enemy: &Ship) -> Vec<(Position, f64)> {
+        let stay = if !enemy.can_move_from(&game.board[enemy.position]) {
+            1.0
+        } else {
+            self.stay_probability(enemy.id)
+        };
+
+        let mut moves = vec![(enemy.position, stay)];
+        moves.extend(
+            Direction::all()
+                .into_iter()
+                .map(|d| (enemy.position + d, (1.0 - stay) / 4.0)),
+        );
+        moves
+    }
+
+    /// Return the probability that the given enemy Ship will be at the given Position next turn.
+    pub fn ship_probability(&self, game: &Game, enemy: &Ship, position: Position) -> f64 {
+        let (width, height) = (self.values.width, self.values.height);
+        let position = position.normalized(width, height);
+        self.moves(game, enemy)
+            .into_iter()
+            .filter(|(p, _)| p.normalized(width, height) == position)
+            .map(|(_, probability)| probability)
+            .sum()
+    }
+
+    /// Update the history and recompute the map, given the Ships from the previous turn.
+    pub fn record(&mut self, game: &Game, previous_ships: &HashMap<ShipId, Ship>) {
+        let enemies: Vec<_> = game
+            .ships
+            .values()
+            .filter(|ship| ship.player_id != game.my_id)
+            .collect();
+
+        // Forget about Ships that no longer exist.
+        self.history.retain(|id, _| game.ships.contains_key(id));
+
+        for enemy in &enemies {
+            if let Some(previous) = previous_ships.get(&enemy.id) {
+                let entry = self.history.entry(enemy.id).or_insert((0, 0));
+                entry.1 += 1;
+                if previous.position == enemy.position {
+                    entry.0 += 1;
+                }
+            }
+        }
+
+        // The probability that each Cell will not be occupied.
+        let mut free = Grid::new(self.values.width, self.values.height, 1.0);
+        for enemy in &enemies {
+            for (position, probability) in self.moves(game, enemy) {
+                free[position] *= 1.0 - probability;
+            }
+        }
+
+        self.values = Grid::from_fn(free.width, free.height, |position| 1.0 - free[position]);
+    }
+}
+
+/// Allow indexing the Occupancy map with Positions.
+impl Index<Position> for Occupancy {
+    type Output = f64;
+
+    fn index(&self, index: Position) -> &Self::Output {
+        &self.values[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixtures, PlayerId};
+    use super::*;
+
+    #[test]
+    fn test_occupancy_record() {
+        let mut game = fixtures::empty_game(6, 3, Position::new(5, 1));
+        let enemy = Ship::new(ShipId::new(5), PlayerId::new(1), Position::new(2, 1), 0);
+        fixtures::add_ship(&mut game, enemy);
+        let mut occupancy = Occupancy::new(game.board.width, game.board.height);
+
+        // A new enemy Ship is equally likely to make any move.
+        occupancy.record(&game, &HashMap::new());
+        assert!((occupancy[Position::new(2, 1)] - 0.2).abs() < 1e-9);
+        assert!((occupancy[Position::new(3, 1)] - 0.2).abs() < 1e-9);
+        assert_eq!(occupancy[Position::new(4, 1)], 0.0);
+
+        // An enemy Ship that keeps staying still is expected to stay.
+        let previous = game.ships.clone();
+        for _ in 0..9 {
+            occupancy.record(&game, &previous);
+        }
+        assert!(occupancy.stay_probability(enemy.id) > 0.9);
+        assert!(occupancy[Position::new(2, 1)] > 0.9);
+        assert!(occupancy[Position::new(3, 1)] < 0.05);
+
+        // An enemy Ship that can't afford to move stays.
+        game.board[enemy.position].halite = 1000;
+        occupancy.record(&game, &previous);
+        assert_eq!(occupancy[Position::new(2, 1)], 1.0);
+        assert_eq!(occupancy[Position::new(3, 1)], 0.0);
+
+        // Enemy Ships that are gone are forgotten.
+        game.ships.remove(&enemy.id);
+        game.board[enemy.position].remove_ship(enemy.id);
+        occupancy.record(&game, &previous);
+        assert_eq!(occupancy.stay_probability(enemy.id), 0.2);
+        assert_eq!(occupancy[Position::new(2, 1)], 0.0);
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use super::{Dropoff, DropoffId, Game, PlayerId, Ship, ShipId};
+
+/// Statistics gathered about an opponent over the course of the Game.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OpponentStats {
+    /// The number of turns this opponent has been observed.
+    pub turns: usize,
+    /// The number of Ships this opponent has spawned.
+    pub ships_spawned: usize,
+    /// The number of times this opponent's Ships deposited halite.
+    pub deposits: usize,
+    /// The total halite this opponent's Ships deposited.
+    pub deposited_halite: usize,
+    /// The number of times one of this opponent's Ships was destroyed next to one of ours.
+    pub aggression_incidents: usize,
+    /// The turns on which this opponent built a Dropoff.
+    pub dropoff_turns: Vec<usize>,
+}
+
+/// A summary of an opponent's behaviour.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpponentProfile {
+    /// The average number of Ships spawned per turn.
+    pub spawn_rate: f64,
+    /// The average cargo a Ship returns with.
+    pub average_return_cargo: f64,
+    /// The number of times one of this opponent's Ships was destroyed next to one of ours.
+    pub aggression_incidents: usize,
+    /// The turn on which this opponent built its first Dropoff.
+    pub first_dropoff_turn: Option<usize>,
+}
+
+impl OpponentStats {
+    /// Summarize these statistics.
+    pub fn profile(&self) -> OpponentProfile {
+        OpponentProfile {
+            spawn_rate: if self.turns > 0 {
+                self.ships_spawned as f64 / self.turns as f64
+            } else {
+                0.0
+            },
+            average_return_cargo: if self.deposits > 0 {
+                self.deposited_halite as f64 / self.deposits as f64
+            } else {
+                0.0
+            },
+            aggression_incidents: self.aggression_incidents,
+            first_dropoff_turn: self.dropoff_turns.first().cloned(),
+        }
+    }
+}
+
+/// Tracks statistics about all of our opponents.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Opponents {
+    /// The statistics for each opponent.
+    pub stats: HashMap<PlayerId, OpponentStats>,
+}
+
+impl Opponents {
+    /// Create a new empty Opponents tracker.
+    pub fn new() -> Self {
+        Opponents {
+            stats: HashMap::new(),
+        }
+    }
+
+    /// Return the profile of the given opponent.
+    pub fn profile(&self, player_id: PlayerId) -> Option<OpponentProfile> {
+        self.stats.get(&player_id).map(|stats| stats.profile())
+    }
+
+    /// Update the statistics, given the Ships and Dropoffs from the previous turn.
+    pub fn record(
+        &mut self,
+        game: &Game,
+        previous_ships: &HashMap<ShipId, Ship>,
+        previous_dropoffs: &HashMap<DropoffId, Dropoff>,
+    ) {
+        let vanished = game.vanished_ships(previous_ships, previous_dropoffs);
+        // The Positions our Ships were at before they were destroyed.
+        let my_losses: Vec<_> = vanished
+            .iter()
+            .filter(|ship| ship.player_id == game.my_id)
+            .map(|ship| ship.position)
+            .collect();
+
+        for player in game.players.values() {
+            if player.id == game.my_id {
+                continue;
+            }
+            let stats = self.stats.entry(player.id).or_default();
+            stats.turns += 1;
+
+            let mut structures = vec![player.shipyard.position];
+            structures.extend(
+                player
+                    .dropoff_ids
+                    .iter()
+                    .map(|id| game.dropoffs[id].position),
+            );
+
+            for ship_id in &player.ship_ids {
+                let ship = &game.ships[ship_id];
+                match previous_ships.get(ship_id) {
+                    Some(previous) => {
+                        if previous.halite > 0
+                            && ship.halite == 0
+                            && structures.contains(&ship.position)
+                        {
+                            stats.deposits += 1;
+                            stats.deposited_halite += previous.halite;
+                        }
+                    }
+                    None => stats.ships_spawned += 1,
+                }
+            }
+
+            for previous in &vanished {
+                if previous.player_id == player.id
+                    && my_losses
+                        .iter()
+                        .any(|p| game.board.distance(*p, previous.position) <= 1)
+                {
+                    stats.aggression_incidents += 1;
+                }
+            }
+
+            for dropoff_id in &player.dropoff_ids {
+                if !previous_dropoffs.contains_key(dropoff_id) {
+                    stats.dropoff_turns.push(game.turn);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixtures, Player, Position, Shipyard, ShipyardId};
+    use super::*;
+
+    #[test]
+    fn test_opponents_record() {
+        let mut game = fixtures::empty_game(8, 3, Position::new(1, 1));
+        let (me, them) = (game.my_id, PlayerId::new(1));
+        fixtures::add_ship(&mut game, Ship::new(ShipId::new(0), me, Position::new(3, 1), 0));
+        let shipyard = Shipyard {
+            id: ShipyardId::new(1),
+            player_id: them,
+            position: Position::new(4, 1),
+        };
+        game.players.insert(them, Player::new(them, shipyard));
+        let enemy = Ship::new(ShipId::new(5), them, Position::new(5, 1), 400);
+        fixtures::add_ship(&mut game, enemy);
+        let mut opponents = Opponents::new();
+
+        // A Ship that wasn't there before was spawned.
+        opponents.record(&game, &HashMap::new(), &HashMap::new());
+        assert_eq!(opponents.stats[&them].ships_spawned, 1);
+        assert!(opponents.profile(me).is_none());
+
+        // A Ship that is empty on its Shipyard deposited.
+        let previous = game.ships.clone();
+        let ship = game.ships.get_mut(&enemy.id).unwrap();
+        ship.position = Position::new(4, 1);
+        ship.halite = 0;
+        opponents.record(&game, &previous, &HashMap::new());
+
+        // A Ship destroyed next to one of ours was aggressive.
+        let previous = game.ships.clone();
+        game.ships.clear();
+        game.players.get_mut(&them).unwrap().ship_ids.clear();
+        opponents.record(&game, &previous, &HashMap::new());
+
+        let profile = opponents.profile(them).unwrap();
+        assert_eq!(profile.spawn_rate, 1.0 / 3.0);
+        assert_eq!(profile.average_return_cargo, 400.0);
+        assert_eq!(profile.aggression_incidents, 1);
+        assert_eq!(profile.first_dropoff_turn, None);
+    }
+}
@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use serde_json::{self, Value};
+
+use super::Result;
+
+/// The version of the parameter set, written to files by `Params::save`.
+///
+/// Bump it whenever a parameter is renamed or removed, and add a Migration from the previous
+/// version, so that files from earlier tuning runs can still be loaded.
+pub const VERSION: u64 = 2;
+
+/// The parameters that were renamed or removed since a version.
+struct Migration {
+    /// The version that is migrated from, to the next one.
+    from: u64,
+    /// The old name of each parameter, and its new name, or None if it was removed.
+    renames: &'static [(&'static str, Option<&'static str>)],
+}
+
+/// The migrations between consecutive versions, in order.
+///
+/// Version 1 is any file without a version, from before the Evaluator weights were added.
+/// Parameters that were only added don't need migrating, because they take their default.
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: 1,
+    renames: &[],
+}];
+
+/// Parameters that control the strategy of the bot.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Params {
+    /// Whether spare Ships should deny halite to opponents when we can't win by banking.
+    pub denial_enabled: bool,
+    /// The number of turns remaining at which denial may start.
+    pub denial_turns: usize,
+    /// The maximum cargo of a Ship that is considered spare for denial.
+    pub denial_max_cargo: usize,
+    /// The fraction of the leader's projected score below which we consider the Game hopeless.
+    pub hopeless_ratio: f64,
+    /// The weight of each unit of banked halite in the Evaluator's score.
+    pub eval_banked: f64,
+    /// The weight of each unit of halite carried by Ships in the Evaluator's score.
+    pub eval_carried: f64,
+    /// The weight of each Ship in the Evaluator's score.
+    pub eval_ships: f64,
+    /// The weight of each Dropoff, not counting the Shipyard, in the Evaluator's score.
+    pub eval_dropoffs: f64,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            denial_enabled: false,
+            denial_turns: 100,
+            denial_max_cargo: 200,
+            hopeless_ratio: 0.8,
+            eval_banked: 1.0,
+            eval_carried: 0.75,
+            eval_ships: 500.0,
+            eval_dropoffs: 1000.0,
+        }
+    }
+}
+
+impl Params {
+    /// Read Params from a JSON file of parameter names to numbers, as written by external tuners
+    /// or `Params::save`.
+    ///
+    /// See `Params::from_versioned`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path)?;
+        let values: BTreeMap<String, Value> = serde_json::from_reader(file)?;
+        Params::from_versioned(values)
+    }
+
+    /// Write the Params to a JSON file, with every parameter and the version.
+    ///
+    /// The names are sorted, so that the files of different runs can be compared with diff.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, &self.to_versioned())?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Return a flat map of every parameter name to its value, with the current version.
+    pub fn to_versioned(&self) -> BTreeMap<String, Value> {
+        let mut values = match serde_json::to_value(self) {
+            Ok(Value::Object(map)) => map.into_iter().collect::<BTreeMap<_, _>>(),
+            _ => unreachable!("Params always serialize to an object"),
+        };
+        values.insert("version".to_string(), VERSION.into());
+        values
+    }
+
+    /// Create Params from a flat map of parameter names to numbers, with an optional version.
+    ///
+    /// A map without a version is taken to be version 1. Parameters are renamed or dropped as
+    /// they were in each version since, and then the map is read with `Params::from_flat`. Maps
+    /// from a newer version than this build knows about are errors.
+    pub fn from_versioned(mut values: BTreeMap<String, Value>) -> Result<Self> {
+        let version = match values.remove("version") {
+            Some(value) => match value.as_u64() {
+                Some(version) => version,
+                None => return Err(format_err!("version must be a whole number, not {}", value)),
+            },
+            None => 1,
+        };
+        if version > VERSION {
+            return Err(format_err!(
+                "parameters are version {}, but only up to version {} is supported",
+                version,
+                VERSION
+            ));
+        }
+        migrate(&mut values, version, MIGRATIONS);
+        Params::from_flat(&values)
+    }
+
+    /// Create Params from a flat map of parameter names to numbers.
+    ///
+    /// Parameters that are missing keep their default. Booleans may be given as 0 or 1, and
+    /// integers may be given as floats as long as they are whole numbers. Unknown names and values
+    /// that are out of range are errors, so that a typo in a tuner's configuration doesn't go
+    /// unnoticed.
+    pub fn from_flat(values: &BTreeMap<String, Value>) -> Result<Self> {
+        let mut params = Params::default();
+        for (name, value) in values {
+            let number = match *value {
+                Value::Bool(b) => {
+                    if b {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                }
+                Value::Number(ref n) => n.as_f64().unwrap(),
+                _ => return Err(format_err!("parameter {} must be a number", name)),
+            };
+            match name.as_str() {
+                "denial_enabled" => params.denial_enabled = flag(name, number)?,
+                "denial_turns" => params.denial_turns = whole(name, number)?,
+                "denial_max_cargo" => params.denial_max_cargo = whole(name, number)?,
+                "hopeless_ratio" => params.hopeless_ratio = fraction(name, number)?,
+                "eval_banked" => params.eval_banked = finite(name, number)?,
+                "eval_carried" => params.eval_carried = finite(name, number)?,
+                "eval_ships" => params.eval_ships = finite(name, number)?,
+                "eval_dropoffs" => params.eval_dropoffs = finite(name, number)?,
+                _ => return Err(format_err!("unknown parameter {}", name)),
+            }
+        }
+        Ok(params)
+    }
+}
+
+/// Rename or remove parameters, from the given version up to the latest.
+fn migrate(values: &mut BTreeMap<String, Value>, version: u64, migrations: &[Migration]) {
+    for migration in migrations.iter().filter(|m| m.from >= version) {
+        for &(old, new) in migration.renames {
+            if let Some(value) = values.remove(old) {
+                match new {
+                    Some(new) => {
+                        debug!("Migrating parameter {} to {}", old, new);
+                        values.insert(new.to_string(), value);
+                    }
+                    None => debug!("Dropping removed parameter {}", old),
+                }
+            }
+        }
+    }
+}
+
+/// Convert a tuned number to a boolean, which must be 0 or 1.
+fn flag(name: &str, number: f64) -> Result<bool> {
+    if number == 0.0 || number == 1.0 {
+        Ok(number == 1.0)
+    } else {
+        Err(format_err!(
+            "parameter {} must be 0 or 1, not {}",
+            name,
+            number
+        ))
+    }
+}
+
+/// Convert a tuned number to a non-negative whole number.
+fn whole(name: &str, number: f64) -> Result<usize> {
+    if number >= 0.0 && number.fract() == 0.0 {
+        Ok(number as usize)
+    } else {
+        Err(format_err!(
+            "parameter {} must be a non-negative whole number, not {}",
+            name,
+            number
+        ))
+    }
+}
+
+/// Check that a tuned number is between 0 and 1.
+fn fraction(name: &str, number: f64) -> Result<f64> {
+    if (0.0..=1.0).contains(&number) {
+        Ok(number)
+    } else {
+        Err(format_err!(
+            "parameter {} must be between 0 and 1, not {}",
+            name,
+            number
+        ))
+    }
+}
+
+/// Check that a tuned number is finite.
+fn finite(name: &str, number: f64) -> Result<f64> {
+    if number.is_finite() {
+        Ok(number)
+    } else {
+        Err(format_err!(
+            "parameter {} must be finite, not {}",
+            name,
+            number
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flat(json: &str) -> BTreeMap<String, Value> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_params_from_flat() {
+        let params = Params::from_flat(&flat(
+            r#"{"denial_enabled": 1, "denial_turns": 80.0, "hopeless_ratio": 0.65, "eval_ships": -2}"#,
+        ))
+        .unwrap();
+        assert_eq!(
+            params,
+            Params {
+                denial_enabled: true,
+                denial_turns: 80,
+                hopeless_ratio: 0.65,
+                eval_ships: -2.0,
+                ..Params::default()
+            }
+        );
+
+        assert!(Params::from_flat(&flat(r#"{"denial_turn": 80}"#)).is_err());
+        assert!(Params::from_flat(&flat(r#"{"denial_turns": 80.5}"#)).is_err());
+        assert!(Params::from_flat(&flat(r#"{"denial_enabled": 0.5}"#)).is_err());
+        assert!(Params::from_flat(&flat(r#"{"hopeless_ratio": 1.5}"#)).is_err());
+        assert!(Params::from_flat(&flat(r#"{"hopeless_ratio": "high"}"#)).is_err());
+    }
+
+    #[test]
+    fn test_params_versioned() {
+        let params = Params {
+            denial_enabled: true,
+            eval_ships: 250.0,
+            ..Params::default()
+        };
+        let values = params.to_versioned();
+        assert_eq!(values["version"], Value::from(VERSION));
+        assert_eq!(Params::from_versioned(values).unwrap(), params);
+
+        let unversioned = flat(r#"{"denial_turns": 80}"#);
+        assert_eq!(
+            Params::from_versioned(unversioned).unwrap().denial_turns,
+            80
+        );
+        assert!(Params::from_versioned(flat(r#"{"version": 99}"#)).is_err());
+        assert!(Params::from_versioned(flat(r#"{"version": 1.5}"#)).is_err());
+
+        let migrations = [
+            Migration {
+                from: 1,
+                renames: &[("deny_turns", Some("denial_turns")), ("old", None)],
+            },
+            Migration {
+                from: 2,
+                renames: &[("denial_turns", Some("recall_turns"))],
+            },
+        ];
+        let mut values = flat(r#"{"deny_turns": 80, "old": 1}"#);
+        migrate(&mut values, 1, &migrations[..1]);
+        assert_eq!(values, flat(r#"{"denial_turns": 80}"#));
+        migrate(&mut values, 2, &migrations);
+        assert_eq!(values, flat(r#"{"recall_turns": 80}"#));
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+
+use super::super::{Game, ShipId};
+
+/// A policy that rams enemy Ships when it is profitable.
+///
+/// Each turn it looks for enemy Ships carrying a lot of halite near our deposit points, where a
+/// collision is a profitable trade, and near their own structures, where sacrificing one of our
+/// Ships stops a large deposit. It assigns a nearby Ship of ours carrying little halite to intercept
+/// each one. The Positions around each fight are added to `Game::avoided_positions` so the rest of
+/// the fleet routes around them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Aggression {
+    /// Whether this policy is active.
+    pub enabled: bool,
+    /// The maximum distance from our deposit points at which enemy Ships are targeted.
+    pub territory_radius: usize,
+    /// The maximum distance an interceptor will chase its target.
+    pub max_chase: usize,
+    /// The maximum cargo an interceptor may carry.
+    pub max_interceptor_cargo: usize,
+    /// The minimum estimated value of a collision for it to be worthwhile.
+    pub min_trade: f64,
+    /// The maximum distance from their own structures at which enemy Ships are sacrificed against.
+    pub deposit_radius: usize,
+    /// The minimum estimated value of a sacrifice for it to be worthwhile.
+    pub min_sacrifice: f64,
+    /// The enemy Ship each of our interceptors is chasing.
+    ///
+    /// This is ordered by our Ship, so that the interceptors are given commands in the same order
+    /// every time, like when replaying a transcript.
+    pub interceptions: BTreeMap<ShipId, ShipId>,
+}
+
+impl Default for Aggression {
+    fn default() -> Self {
+        Aggression::new()
+    }
+}
+
+impl Aggression {
+    /// Create a new enabled Aggression policy with reasonable defaults.
+    pub fn new() -> Self {
+        Aggression {
+            enabled: true,
+            territory_radius: 8,
+            max_chase: 3,
+            max_interceptor_cargo: 100,
+            min_trade: 0.0,
+            deposit_radius: 2,
+            min_sacrifice: 0.0,
+            interceptions: BTreeMap::new(),
+        }
+    }
+
+    /// Release any interception involving the given destroyed Ship.
+    pub fn release(&mut self, ship_id: ShipId) {
+        self.interceptions
+            .retain(|id, enemy_id| *id != ship_id && *enemy_id != ship_id);
+    }
+
+    /// Assign interceptors to new targets.
+    fn assign(&mut self, game: &Game) {
+        for enemy in game.enemy_ships() {
+            if self.interceptions.values().any(|id| *id == enemy.id) {
+                continue;
+            }
+
+            let home = game.nearest_deposit(enemy.position);
+            let near_territory = game.board.distance(enemy.position, home) <= self.territory_radius;
+            let depositing = game
+                .enemy_structures()
+                .into_iter()
+                .filter(|(player_id, _)| *player_id == enemy.player_id)
+                .any(|(_, p)| game.board.distance(enemy.position, p) <= self.deposit_radius);
+            if !near_territory && !depositing {
+                continue;
+            }
+            let worthwhile = |id: ShipId| {
+                (near_territory && game.collision_trade(id, enemy.id) >= self.min_trade)
+                    || (depositing && game.sacrifice_value(id, enemy.id) >= self.min_sacrifice)
+            };
+
+            let interceptor = game
+                .me()
+                .ship_ids
+                .iter()
+                .cloned()
+                .filter(|id| !self.interceptions.contains_key(id) && !game.has_command(*id))
+                .filter(|id| game.ships[id].halite <= self.max_interceptor_cargo)
+                .filter(|id| {
+                    game.board.distance(game.ships[id].position, enemy.position) <= self.max_chase
+                })
+                .filter(|id| worthwhile(*id))
+                .min_by_key(|id| game.board.distance(game.ships[id].position, enemy.position));
+
+            if let Some(interceptor) = interceptor {
+                info!(
+                    "Ship {} will intercept enemy Ship {}",
+                    interceptor, enemy.id
+                );
+                self.interceptions.insert(interceptor, enemy.id);
+            }
+        }
+    }
+
+    /// Give commands to the interceptors, and assign new ones when profitable.
+    pub fn step(&mut self, game: &mut Game) {
+        if !self.enabled {
+            return;
+        }
+
+        for ship_id in game.destroyed_ships() {
+            self.release(ship_id);
+        }
+
+        // Forget about interceptions where either Ship is gone or the target got away.
+        let max_chase = self.max_chase;
+        self.interceptions.retain(|id, enemy_id| {
+            match (game.ships.get(id), game.ships.get(enemy_id)) {
+                (Some(ship), Some(enemy)) => {
+                    ship.player_id == game.my_id
+                        && game.board.distance(ship.position, enemy.position) <= max_chase
+                }
+                _ => false,
+            }
+        });
+
+        self.assign(game);
+
+        for (interceptor, enemy_id) in &self.interceptions {
+            if game.has_command(*interceptor) {
+                continue;
+            }
+
+            let target = game.ships[enemy_id].position;
+            let position = game.ships[interceptor].position;
+
+            // Go straight for the target, even though it is occupied.
+            match game.board.directions_toward(position, target).first() {
+                Some(direction) => game.move_ship(*interceptor, *direction),
+                None => game.collect_halite(*interceptor),
+            }
+
+            game.avoided_positions.push(target);
+            game.avoided_positions.extend(target.surrounding());
+        }
+
+        // Make sure avoided Positions compare equal to normalized ones.
+        let (width, height) = (game.board.width, game.board.height);
+        for position in &mut game.avoided_positions {
+            *position = position.normalized(width, height);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::{
+        fixtures, Command, Direction, Player, PlayerId, Position, Ship, Shipyard, ShipyardId,
+    };
+    use super::*;
+
+    #[test]
+    fn test_aggression_step() {
+        let mut game = fixtures::empty_game(12, 1, Position::new(1, 0));
+        let (me, them, a) = (game.my_id, PlayerId::new(1), ShipId::new(0));
+        fixtures::add_ship(&mut game, Ship::new(a, me, Position::new(4, 0), 100));
+        let shipyard = Shipyard {
+            id: ShipyardId::new(1),
+            player_id: them,
+            position: Position::new(9, 0),
+        };
+        game.players.insert(them, Player::new(them, shipyard));
+        let enemy = Ship::new(ShipId::new(5), them, Position::new(3, 0), 0);
+        fixtures::add_ship(&mut game, enemy);
+        let target = enemy.position;
+        let mut aggression = Aggression::new();
+
+        // Losing our Ship costs more than we would recover.
+        assert!(game.collision_trade(a, enemy.id) < 0.0);
+        aggression.step(&mut game);
+        assert!(aggression.interceptions.is_empty());
+        assert!(game.commands.is_empty());
+
+        // A worthwhile trade is taken, and the rest of the fleet keeps clear.
+        aggression.min_trade = -500.0;
+        aggression.step(&mut game);
+        assert_eq!(aggression.interceptions[&a], enemy.id);
+        assert_eq!(game.commands.to_vec(), vec![Command::Move(a, Direction::West)]);
+        assert!(game.avoided_positions.contains(&target));
+        assert!(game.avoided_positions.contains(&Position::new(2, 0)));
+    }
+}
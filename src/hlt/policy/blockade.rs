@@ -0,0 +1,103 @@
+use super::super::{constants, Game};
+
+/// A policy that breaks blockades of our Shipyard.
+///
+/// When enemy Ships camp at our Shipyard, any camper sitting on the Shipyard is destroyed by
+/// spawning a Ship on top of it, and any camper next to it is traded with one of our nearby empty
+/// Ships.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockadeBreaker {
+    /// Whether this policy is active.
+    pub enabled: bool,
+    /// The maximum cargo a Ship sent to trade with a camper may carry.
+    pub max_trade_cargo: usize,
+    /// The maximum distance a Ship will travel to trade with a camper.
+    pub max_distance: usize,
+}
+
+impl Default for BlockadeBreaker {
+    fn default() -> Self {
+        BlockadeBreaker::new()
+    }
+}
+
+impl BlockadeBreaker {
+    /// Create a new enabled BlockadeBreaker with reasonable defaults.
+    pub fn new() -> Self {
+        BlockadeBreaker {
+            enabled: true,
+            max_trade_cargo: 50,
+            max_distance: 3,
+        }
+    }
+
+    /// Respond to any blockade of our Shipyard.
+    pub fn step(&mut self, game: &mut Game) {
+        if !self.enabled || !game.blockade.is_active() {
+            return;
+        }
+
+        let shipyard = game.me().shipyard.position;
+        for camper in game.blockade.campers.clone() {
+            let target = match game.ships.get(&camper) {
+                Some(ship) => ship.position,
+                None => continue,
+            };
+
+            if target == shipyard {
+                if !game.is_spawning()
+                    && game.me().halite >= constants::get().new_entity_halite_cost
+                {
+                    info!("Spawning onto enemy Ship {} at our Shipyard", camper);
+                    game.spawn_ship();
+                }
+                continue;
+            }
+
+            let max_trade_cargo = self.max_trade_cargo;
+            let max_distance = self.max_distance;
+            let trader = game
+                .me()
+                .ship_ids
+                .iter()
+                .cloned()
+                .filter(|id| !game.has_command(*id))
+                .filter(|id| game.ships[id].halite <= max_trade_cargo)
+                .filter(|id| game.board.distance(game.ships[id].position, target) <= max_distance)
+                .min_by_key(|id| game.board.distance(game.ships[id].position, target));
+
+            if let Some(trader) = trader {
+                let position = game.ships[&trader].position;
+                if let Some(direction) = game.board.directions_toward(position, target).first() {
+                    info!("Ship {} will trade with enemy Ship {}", trader, camper);
+                    game.move_ship(trader, *direction);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::{fixtures, Command, Direction, PlayerId, Position, Ship, ShipId};
+    use super::*;
+
+    #[test]
+    fn test_blockade_breaker_step() {
+        let mut game = fixtures::empty_game(6, 1, Position::new(4, 0));
+        let (me, a) = (game.my_id, ShipId::new(0));
+        fixtures::add_ship(&mut game, Ship::new(a, me, Position::new(1, 0), 0));
+        let camper = Ship::new(ShipId::new(5), PlayerId::new(1), Position::new(3, 0), 0);
+        game.ships.insert(camper.id, camper);
+        let mut breaker = BlockadeBreaker::new();
+
+        // Nothing happens until the Ship is camping.
+        breaker.step(&mut game);
+        assert!(game.commands.is_empty());
+
+        // A nearby empty Ship goes to trade with the camper.
+        game.blockade.campers.push(camper.id);
+        breaker.step(&mut game);
+        assert_eq!(game.commands.to_vec(), vec![Command::Move(a, Direction::East)]);
+    }
+}
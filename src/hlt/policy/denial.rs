@@ -0,0 +1,145 @@
+use super::super::{Game, Position, ShipId};
+
+/// A policy that denies halite to opponents when we can't win by banking.
+///
+/// It is enabled through `Params::denial_enabled`. Once the projected score says we can't catch
+/// the leader, spare Ships park on the richest contested Cells, so that opponents can't mine them
+/// while inspired.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Denial {
+    /// Whether denial has started.
+    pub denying: bool,
+    /// The Cell each of our spare Ships is parking on.
+    pub assignments: Vec<(ShipId, Position)>,
+}
+
+impl Denial {
+    /// Create a new Denial policy.
+    pub fn new() -> Self {
+        Denial {
+            denying: false,
+            assignments: Vec::new(),
+        }
+    }
+
+    /// Release the Cell the given destroyed Ship was parking on, so another Ship can take it.
+    pub fn release(&mut self, ship_id: ShipId) {
+        self.assignments.retain(|(id, _)| *id != ship_id);
+    }
+
+    /// Return whether the Game is hopeless according to the projected scores.
+    pub fn is_hopeless(game: &Game) -> bool {
+        let mine = game.projected_score(game.my_id);
+        let best = game
+            .enemies()
+            .map(|player| game.projected_score(player.id))
+            .fold(0.0, f64::max);
+        mine < best * game.params.hopeless_ratio
+    }
+
+    /// Give commands to spare Ships once denial has started.
+    pub fn step(&mut self, game: &mut Game) {
+        if !game.params.denial_enabled {
+            return;
+        }
+
+        if !self.denying
+            && game.turns_remaining() <= game.params.denial_turns
+            && Denial::is_hopeless(game)
+        {
+            info!("Denying halite to our opponents");
+            self.denying = true;
+        }
+        if !self.denying {
+            return;
+        }
+
+        for ship_id in game.destroyed_ships() {
+            self.release(ship_id);
+        }
+
+        // Forget about Ships that are gone for other reasons, like being converted.
+        self.assignments
+            .retain(|(id, _)| game.me().ship_ids.contains(id));
+
+        // The richest contested Cells that nobody is parking on yet.
+        let mut targets: Vec<_> = game
+            .board
+            .cells
+            .iter()
+            .flat_map(|row| row.iter())
+            .filter(|cell| game.territory.is_contested(cell.position) && !cell.has_structure())
+            .filter(|cell| !self.assignments.iter().any(|(_, p)| *p == cell.position))
+            .map(|cell| (cell.position, cell.halite))
+            .collect();
+        targets.sort_by_key(|(_, halite)| !halite);
+
+        let spare: Vec<_> = game
+            .me()
+            .ship_ids
+            .iter()
+            .cloned()
+            .filter(|id| !self.assignments.iter().any(|(a, _)| a == id))
+            .filter(|id| game.ships[id].halite <= game.params.denial_max_cargo)
+            .collect();
+
+        for (ship_id, (target, _)) in spare.into_iter().zip(targets) {
+            self.assignments.push((ship_id, target));
+        }
+
+        for (ship_id, target) in &self.assignments {
+            if game.has_command(*ship_id) {
+                continue;
+            }
+            match game.navigate_to(*ship_id, *target) {
+                Some(direction) => game.move_ship(*ship_id, direction),
+                None => game.collect_halite(*ship_id),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::scoreboard::PlayerScore;
+    use super::super::super::{
+        constants, fixtures, Command, Direction, Player, PlayerId, Ship, Shipyard, ShipyardId,
+    };
+    use super::*;
+
+    #[test]
+    fn test_denial_step() {
+        let mut game = fixtures::empty_game(6, 3, Position::new(0, 2));
+        let (me, a, them) = (game.my_id, ShipId::new(0), PlayerId::new(1));
+        fixtures::add_ship(&mut game, Ship::new(a, me, Position::new(1, 1), 0));
+        game.board[Position::new(3, 1)].halite = 300;
+        game.board[Position::new(3, 2)].halite = 100;
+        let shipyard = Shipyard {
+            id: ShipyardId::new(1),
+            player_id: them,
+            position: Position::new(5, 2),
+        };
+        game.players.insert(them, Player::new(them, shipyard));
+        game.params.denial_enabled = true;
+        game.turn = constants::get().max_turns - 10;
+        game.territory.contested[Position::new(3, 1)] = true;
+        game.territory.contested[Position::new(3, 2)] = true;
+        let mut denial = Denial::new();
+
+        // Nothing happens while we can still win.
+        denial.step(&mut game);
+        assert!(!denial.denying);
+        assert!(game.commands.is_empty());
+
+        // Spare Ships park on the richest contested Cells once it is hopeless.
+        let score = PlayerScore {
+            halite: 10000,
+            ..PlayerScore::default()
+        };
+        game.scoreboard.scores.insert(them, score);
+        denial.step(&mut game);
+        assert!(denial.denying);
+        assert_eq!(denial.assignments, vec![(a, Position::new(3, 1))]);
+        assert_eq!(game.commands.to_vec(), vec![Command::Move(a, Direction::East)]);
+    }
+}
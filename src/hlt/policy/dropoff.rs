@@ -0,0 +1,236 @@
+use super::super::{constants, Game, Grid, Position, ShipId};
+
+/// The number of richest regions to evaluate as candidate Dropoff locations each turn.
+const CANDIDATES: usize = 10;
+
+/// A policy that automatically builds Dropoffs.
+///
+/// Each turn it looks for a rich region, away from our existing deposit points and near enough
+/// of our Ships, that would pay for a Dropoff quickly. It then picks the closest Ship as the
+/// builder, reserves the halite, routes the builder to the location, and converts it.
+///
+/// The builder is given its commands in `step`, so the rest of the strategy should skip any Ship
+/// for which `Game::has_command` returns true.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DropoffBuilder {
+    /// Whether this policy is active.
+    pub enabled: bool,
+    /// The radius of the region that a Dropoff is expected to serve.
+    pub radius: usize,
+    /// The minimum distance between a new Dropoff and our existing deposit points.
+    pub min_distance: usize,
+    /// The minimum number of our Ships that must be within twice the radius of the location.
+    pub min_ships: usize,
+    /// The maximum number of turns a Dropoff may take to pay for itself.
+    pub max_payback: usize,
+    /// The maximum number of Dropoffs this policy will build.
+    pub max_dropoffs: usize,
+    /// The Ship that is on its way to build a Dropoff.
+    pub builder: Option<ShipId>,
+    /// The location where the builder is going to build a Dropoff.
+    pub target: Option<Position>,
+    /// The halite within the radius of each Cell, if it was computed in the background.
+    ///
+    /// This is from the end of the previous turn, which is close enough to rank the candidates.
+    pub density: Option<Grid<f64>>,
+}
+
+impl Default for DropoffBuilder {
+    fn default() -> Self {
+        DropoffBuilder::new()
+    }
+}
+
+impl DropoffBuilder {
+    /// Create a new enabled DropoffBuilder with reasonable defaults.
+    pub fn new() -> Self {
+        DropoffBuilder {
+            enabled: true,
+            radius: 6,
+            min_distance: 12,
+            min_ships: 4,
+            max_payback: 100,
+            max_dropoffs: 3,
+            builder: None,
+            target: None,
+            density: None,
+        }
+    }
+
+    /// Return the amount of halite this policy has reserved for a Dropoff under construction.
+    pub fn reserved(&self) -> usize {
+        if self.enabled && self.target.is_some() {
+            constants::get().dropoff_cost
+        } else {
+            0
+        }
+    }
+
+    /// Release the given destroyed Ship, if it was the builder.
+    ///
+    /// The build is kept, and a new builder is picked in the same turn.
+    pub fn release(&mut self, ship_id: ShipId) {
+        if self.builder == Some(ship_id) {
+            info!("Dropoff builder Ship {} was destroyed", ship_id);
+            self.builder = None;
+        }
+    }
+
+    /// Abandon the current build, if there is one.
+    fn abandon(&mut self, game: &mut Game) {
+        if let Some(target) = self.target.take() {
+            info!("Abandoning Dropoff at {:?}", target);
+            game.cancel_planned_dropoff(target);
+        }
+        self.builder = None;
+    }
+
+    /// Return the best location for a new Dropoff, if there is a worthwhile one.
+    fn choose_target(&self, game: &Game) -> Option<Position> {
+        let deposits = game.deposit_positions();
+
+        let mut regions = Vec::new();
+        for row in &game.board.cells {
+            for cell in row {
+                let far_enough = deposits
+                    .iter()
+                    .all(|p| game.board.distance(cell.position, *p) >= self.min_distance);
+                if far_enough && !cell.has_structure() {
+                    let halite = match self.density {
+                        Some(ref density) => density[cell.position] as usize,
+                        None => game.board.halite_within(cell.position, self.radius),
+                    };
+                    regions.push((cell.position, halite));
+                }
+            }
+        }
+
+        // Only evaluate the richest regions.
+        regions.sort_by_key(|(_, halite)| !halite);
+        regions
+            .into_iter()
+            .take(CANDIDATES)
+            .filter(|(position, _)| {
+                let nearby = game
+                    .me()
+                    .ship_ids
+                    .iter()
+                    .filter(|id| {
+                        game.board.distance(game.ships[id].position, *position) <= 2 * self.radius
+                    })
+                    .count();
+                nearby >= self.min_ships
+            })
+            .filter_map(|(position, _)| {
+                game.dropoff_payback_turns(position, self.radius)
+                    .map(|turns| (position, turns))
+            })
+            .filter(|(_, turns)| *turns <= self.max_payback)
+            .min_by_key(|(_, turns)| *turns)
+            .map(|(position, _)| position)
+    }
+
+    /// Give commands to the builder Ship, and start new builds when worthwhile.
+    pub fn step(&mut self, game: &mut Game) {
+        if !self.enabled {
+            return;
+        }
+
+        for ship_id in game.destroyed_ships() {
+            self.release(ship_id);
+        }
+
+        if self.target.is_none() {
+            let turns_remaining = constants::get().max_turns.saturating_sub(game.turn);
+            if game.me().dropoff_ids.len() >= self.max_dropoffs
+                || turns_remaining < self.max_payback
+            {
+                return;
+            }
+            match self.choose_target(game) {
+                Some(target) => {
+                    game.plan_dropoff(target);
+                    self.target = Some(target);
+                }
+                None => return,
+            }
+        }
+
+        if self.builder.is_none() {
+            let target = self.target.unwrap();
+            let closest = game
+                .me()
+                .ship_ids
+                .iter()
+                .cloned()
+                .filter(|id| !game.has_command(*id))
+                .min_by_key(|id| game.board.distance(game.ships[id].position, target));
+            match closest {
+                Some(builder) => {
+                    info!("Ship {} will build a Dropoff at {:?}", builder, target);
+                    self.builder = Some(builder);
+                }
+                None => return self.abandon(game),
+            }
+        }
+
+        let builder = self.builder.unwrap();
+        let target = self.target.unwrap();
+        let ship = game.ships[&builder];
+
+        if ship.position == target {
+            // The halite carried by the Ship and the halite in the Cell are credited.
+            let cell = &game.board[ship.position];
+            let funds = game.me().halite + ship.halite + cell.halite;
+            if cell.has_structure() {
+                self.abandon(game);
+            } else if funds >= constants::get().dropoff_cost {
+                info!("Ship {} is converting into a Dropoff", builder);
+                game.convert_to_dropoff(builder);
+                self.builder = None;
+                self.target = None;
+            } else {
+                game.collect_halite(builder);
+            }
+        } else if let Some(direction) = game.navigate_to(builder, target) {
+            game.move_ship(builder, direction);
+        } else {
+            game.collect_halite(builder);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::{fixtures, Command, Direction, Ship};
+    use super::*;
+
+    #[test]
+    fn test_dropoff_builder_step() {
+        let mut game = fixtures::empty_game(8, 1, Position::new(4, 0));
+        let (me, a, target) = (game.my_id, ShipId::new(0), Position::new(3, 0));
+        fixtures::add_ship(&mut game, Ship::new(a, me, Position::new(1, 0), 0));
+        let mut builder = DropoffBuilder::new();
+        builder.builder = Some(a);
+        builder.target = Some(target);
+        game.plan_dropoff(target);
+        assert_eq!(builder.reserved(), constants::get().dropoff_cost);
+
+        // The builder goes to the target.
+        builder.step(&mut game);
+        assert_eq!(game.commands.to_vec(), vec![Command::Move(a, Direction::East)]);
+
+        // Once it is there, it converts when we can afford it.
+        game.commands.clear();
+        game.move_ship(a, Direction::East);
+        game.commands.clear();
+        builder.step(&mut game);
+        assert_eq!(game.commands.to_vec(), vec![Command::Collect(a)]);
+        game.commands.clear();
+        game.players.get_mut(&me).unwrap().halite = constants::get().dropoff_cost;
+        builder.step(&mut game);
+        assert_eq!(game.commands.to_vec(), vec![Command::ConvertToDropoff(a)]);
+        assert_eq!(builder.target, None);
+        assert_eq!(builder.reserved(), 0);
+    }
+}
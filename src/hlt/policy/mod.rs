@@ -0,0 +1,11 @@
+mod aggression;
+mod blockade;
+mod denial;
+mod dropoff;
+mod recall;
+
+pub use self::aggression::Aggression;
+pub use self::blockade::BlockadeBreaker;
+pub use self::denial::Denial;
+pub use self::dropoff::DropoffBuilder;
+pub use self::recall::Recall;
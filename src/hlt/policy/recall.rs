@@ -0,0 +1,96 @@
+use super::super::Game;
+
+/// A policy that brings every Ship home at the end of the Game.
+///
+/// Once the number of turns remaining is within the turns it takes our furthest Ship to get home
+/// plus a margin, every Ship heads for its closest structure, and stacking onto our own structures
+/// is allowed so Ships don't gridlock one Cell away from home.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recall {
+    /// Whether this policy is active.
+    pub enabled: bool,
+    /// The number of extra turns allowed for Ships to get home.
+    pub margin: usize,
+    /// Whether the recall has started.
+    pub recalling: bool,
+}
+
+impl Default for Recall {
+    fn default() -> Self {
+        Recall::new()
+    }
+}
+
+impl Recall {
+    /// Create a new enabled Recall policy with reasonable defaults.
+    pub fn new() -> Self {
+        Recall {
+            enabled: true,
+            margin: 5,
+            recalling: false,
+        }
+    }
+
+    /// Give commands to all our Ships, once it is time to recall them.
+    pub fn step(&mut self, game: &mut Game) {
+        if !self.enabled {
+            return;
+        }
+
+        let furthest = game
+            .me()
+            .ship_ids
+            .iter()
+            .map(|id| game.turns_to_home(*id))
+            .max()
+            .unwrap_or(0);
+
+        if !self.recalling && game.turns_remaining() <= furthest + self.margin {
+            info!(
+                "Recalling all Ships with {} turns remaining",
+                game.turns_remaining()
+            );
+            self.recalling = true;
+            game.navigation.allow_stacking = true;
+        }
+        if !self.recalling {
+            return;
+        }
+
+        for ship_id in game.me().ship_ids.clone() {
+            if game.has_command(ship_id) {
+                continue;
+            }
+
+            let home = game.nearest_structure(game.ships[&ship_id].position);
+            match game.navigate_to(ship_id, home) {
+                Some(direction) => game.move_ship(ship_id, direction),
+                None => game.collect_halite(ship_id),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::{constants, fixtures, Command, Direction, Position, Ship, ShipId};
+    use super::*;
+
+    #[test]
+    fn test_recall_step() {
+        let mut game = fixtures::empty_game(6, 1, Position::new(3, 0));
+        let (me, a) = (game.my_id, ShipId::new(0));
+        fixtures::add_ship(&mut game, Ship::new(a, me, Position::new(1, 0), 0));
+        let mut recall = Recall::new();
+
+        recall.step(&mut game);
+        assert!(!recall.recalling);
+        assert!(game.commands.is_empty());
+
+        // Near the end of the Game every Ship heads home.
+        game.turn = constants::get().max_turns + 1 - recall.margin;
+        recall.step(&mut game);
+        assert!(recall.recalling);
+        assert_eq!(game.commands.to_vec(), vec![Command::Move(a, Direction::East)]);
+    }
+}
@@ -0,0 +1,71 @@
+use std::cmp;
+
+use super::board::Structure;
+use super::{constants, Board, Command, DropoffId, Game};
+
+impl Game {
+    /// Return a copy of the Board with all the currently queued commands applied.
+    ///
+    /// Moves and Spawns are already applied to the Board when they are queued. On top of that, our
+    /// Ships that aren't moving mine their Cell, at the inspired extract ratio where they are
+    /// inspired, and conversions put a Dropoff on the Board and remove the Ship. Dropoffs get made
+    /// up identifiers. Opponents' Ships stay where they are.
+    pub fn preview(&self) -> Board {
+        let constants = constants::get();
+        let mut board = self.board.clone();
+        let mut next_dropoff_id = self.dropoffs.keys().max().map_or(0, |id| id.0 + 1);
+
+        for ship_id in &self.me().ship_ids {
+            let ship = &self.ships[ship_id];
+            match self.command(*ship_id) {
+                Some(Command::Move(..)) => {}
+                Some(Command::ConvertToDropoff(_)) => {
+                    let cell = &mut board[ship.position];
+                    cell.remove_ship(*ship_id);
+                    cell.structure = Some(Structure::Dropoff(DropoffId::new(next_dropoff_id)));
+                    cell.halite = 0;
+                    next_dropoff_id += 1;
+                }
+                Some(Command::Collect(_)) | Some(Command::Spawn) | None => {
+                    let cell = &mut board[ship.position];
+                    if !cell.has_structure() {
+                        let ratio = if self.inspiration.is_inspired(ship.position) {
+                            constants.inspired_extract_ratio
+                        } else {
+                            constants.extract_ratio
+                        };
+                        let extracted = cell.halite.div_ceil(ratio);
+                        let space = constants.max_halite.saturating_sub(ship.halite);
+                        cell.halite -= cmp::min(extracted, space);
+                    }
+                }
+            }
+        }
+
+        board
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixtures, Direction, Position, ShipId};
+
+    #[test]
+    fn test_game_preview() {
+        let mut game = fixtures::game(". a100 b100 c100 Y");
+        game.move_ship(ShipId::new(0), Direction::West);
+        game.convert_to_dropoff(ShipId::new(2));
+
+        let board = game.preview();
+        assert!(board[Position::new(0, 0)].has_ship(ShipId::new(0)));
+        assert_eq!(board[Position::new(1, 0)].halite, 100);
+        assert_eq!(board[Position::new(2, 0)].halite, 75);
+        assert!(board[Position::new(3, 0)].has_structure());
+        assert!(!board[Position::new(3, 0)].is_occupied());
+        assert_eq!(board[Position::new(3, 0)].halite, 0);
+
+        // The Game itself is unchanged.
+        assert_eq!(game.board[Position::new(2, 0)].halite, 100);
+        assert!(game.board[Position::new(3, 0)].has_ship(ShipId::new(2)));
+    }
+}
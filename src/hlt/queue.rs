@@ -0,0 +1,104 @@
+use std::ops::Deref;
+use std::slice;
+
+use super::{Command, ShipId};
+
+/// The commands queued for this turn, with at most one for each Ship.
+///
+/// The commands are kept in the order they were queued, and can be looked at as a slice.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CommandQueue {
+    commands: Vec<Command>,
+}
+
+impl CommandQueue {
+    /// Create a new empty CommandQueue.
+    pub fn new() -> Self {
+        CommandQueue {
+            commands: Vec::new(),
+        }
+    }
+
+    /// Add a command to the end of the queue, replacing any command for the same Ship.
+    ///
+    /// The command is normalized first. Returns the replaced command, if any.
+    pub fn push(&mut self, command: Command) -> Option<Command> {
+        let command = command.normalized();
+        let replaced = command.ship_id().and_then(|id| self.remove(id));
+        self.commands.push(command);
+        replaced
+    }
+
+    /// Return the command queued for the given Ship, if any.
+    pub fn get(&self, ship_id: ShipId) -> Option<Command> {
+        self.index_of(ship_id).map(|index| self.commands[index])
+    }
+
+    /// Return where in the queue the command for the given Ship is, if any.
+    pub fn index_of(&self, ship_id: ShipId) -> Option<usize> {
+        self.commands
+            .iter()
+            .position(|command| command.ship_id() == Some(ship_id))
+    }
+
+    /// Remove and return the command queued for the given Ship, if any.
+    pub fn remove(&mut self, ship_id: ShipId) -> Option<Command> {
+        self.index_of(ship_id)
+            .map(|index| self.commands.remove(index))
+    }
+
+    /// Remove all the commands.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+}
+
+impl Deref for CommandQueue {
+    type Target = [Command];
+
+    fn deref(&self) -> &[Command] {
+        &self.commands
+    }
+}
+
+impl<'a> IntoIterator for &'a CommandQueue {
+    type Item = &'a Command;
+    type IntoIter = slice::Iter<'a, Command>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.commands.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Direction;
+    use super::*;
+
+    #[test]
+    fn test_command_queue() {
+        let (a, b) = (ShipId::new(0), ShipId::new(1));
+        let mut queue = CommandQueue::new();
+        assert_eq!(queue.push(Command::Move(a, Direction::Still)), None);
+        assert_eq!(queue.push(Command::Spawn), None);
+        assert_eq!(queue.push(Command::Collect(b)), None);
+        assert_eq!(queue.push(Command::Spawn), None);
+        assert_eq!(
+            queue.push(Command::Move(a, Direction::East)),
+            Some(Command::Collect(a))
+        );
+        assert_eq!(
+            &queue[..],
+            &[
+                Command::Spawn,
+                Command::Collect(b),
+                Command::Spawn,
+                Command::Move(a, Direction::East),
+            ]
+        );
+        assert_eq!(queue.get(b), Some(Command::Collect(b)));
+        assert_eq!(queue.remove(b), Some(Command::Collect(b)));
+        assert_eq!(queue.get(b), None);
+        assert_eq!(queue.len(), 3);
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use serde_json;
+use zstd;
+
+use super::board::Structure;
+use super::constants::Constants;
+use super::{
+    Board, Dropoff, DropoffId, PlayerId, Position, Result, Ship, ShipId, Shipyard, ShipyardId,
+};
+
+/// A location as it appears in a replay.
+#[derive(Clone, Copy, Debug, Deserialize)]
+struct RawLocation {
+    x: isize,
+    y: isize,
+}
+
+impl From<RawLocation> for Position {
+    fn from(location: RawLocation) -> Position {
+        Position::new(location.x, location.y)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawPlayer {
+    player_id: usize,
+    name: String,
+    factory_location: RawLocation,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawCell {
+    energy: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawProductionMap {
+    width: isize,
+    height: isize,
+    grid: Vec<Vec<RawCell>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawCellUpdate {
+    x: isize,
+    y: isize,
+    production: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawEntity {
+    x: isize,
+    y: isize,
+    energy: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    id: Option<usize>,
+    #[serde(default)]
+    owner_id: Option<usize>,
+    #[serde(default)]
+    location: Option<RawLocation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawFrame {
+    #[serde(default)]
+    cells: Vec<RawCellUpdate>,
+    #[serde(default)]
+    energy: HashMap<String, usize>,
+    #[serde(default)]
+    entities: HashMap<String, HashMap<String, RawEntity>>,
+    #[serde(default)]
+    events: Vec<RawEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawReplay {
+    #[serde(rename = "GAME_CONSTANTS")]
+    constants: Constants,
+    players: Vec<RawPlayer>,
+    production_map: RawProductionMap,
+    full_frames: Vec<RawFrame>,
+}
+
+/// A Player in a replay.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReplayPlayer {
+    /// The Player's identifier.
+    pub id: PlayerId,
+    /// The name of the bot.
+    pub name: String,
+    /// The Player's Shipyard.
+    pub shipyard: Shipyard,
+}
+
+/// The state of the Game on a single turn of a replay.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplayFrame {
+    /// The Board, including structures and Ships.
+    pub board: Board,
+    /// All the Ships.
+    pub ships: BTreeMap<ShipId, Ship>,
+    /// All the Dropoffs.
+    pub dropoffs: BTreeMap<DropoffId, Dropoff>,
+    /// The halite banked by each Player.
+    pub halite: BTreeMap<PlayerId, usize>,
+}
+
+/// A Game replay, as written by the Halite engine.
+#[derive(Debug)]
+pub struct Replay {
+    /// The constants the Game was played with.
+    pub constants: Constants,
+    /// The Players in the Game.
+    pub players: Vec<ReplayPlayer>,
+    /// The state of the Game on each turn, starting with the initial state.
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    /// Read a zstd compressed replay file.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Replay::from_reader(File::open(path)?)
+    }
+
+    /// Read a zstd compressed replay.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
+        Replay::from_json(&zstd::decode_all(reader)?)
+    }
+
+    /// Read an uncompressed replay.
+    pub fn from_json(json: &[u8]) -> Result<Self> {
+        let raw: RawReplay = serde_json::from_slice(json)?;
+        let map = &raw.production_map;
+
+        let players: Vec<_> = raw
+            .players
+            .iter()
+            .map(|p| ReplayPlayer {
+                id: PlayerId::new(p.player_id),
+                name: p.name.clone(),
+                shipyard: Shipyard {
+                    id: ShipyardId::new(p.player_id),
+                    player_id: PlayerId::new(p.player_id),
+                    position: p.factory_location.into(),
+                },
+            })
+            .collect();
+
+        let mut board = Board::new(map.width, map.height);
+        for (y, row) in map.grid.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                let position = Position::new(x as isize, y as isize);
+                board
+                    .get_mut(position)
+                    .ok_or_else(|| format_err!("replay cell {:?} is outside the map", position))?
+                    .halite = cell.energy;
+            }
+        }
+        for player in &players {
+            let shipyard = &player.shipyard;
+            board[shipyard.position].structure = Some(Structure::Shipyard(shipyard.id));
+        }
+
+        let mut frames = Vec::with_capacity(raw.full_frames.len());
+        let mut dropoffs = BTreeMap::new();
+        let mut halite: BTreeMap<_, _> = players
+            .iter()
+            .map(|p| (p.id, raw.constants.initial_halite))
+            .collect();
+
+        for raw_frame in &raw.full_frames {
+            for update in &raw_frame.cells {
+                board[Position::new(update.x, update.y)].halite = update.production;
+            }
+            for event in raw_frame.events.iter().filter(|e| e.kind == "construct") {
+                if let (Some(id), Some(owner), Some(location)) =
+                    (event.id, event.owner_id, event.location)
+                {
+                    let dropoff =
+                        Dropoff::new(DropoffId::new(id), PlayerId::new(owner), location.into());
+                    board[dropoff.position].structure = Some(Structure::Dropoff(dropoff.id));
+                    dropoffs.insert(dropoff.id, dropoff);
+                }
+            }
+            for (player, energy) in &raw_frame.energy {
+                halite.insert(PlayerId::new(player.parse()?), *energy);
+            }
+
+            let mut frame_board = board.clone();
+            let mut ships = BTreeMap::new();
+            for (player, entities) in &raw_frame.entities {
+                let player_id = PlayerId::new(player.parse()?);
+                for (id, entity) in entities {
+                    let position = Position::new(entity.x, entity.y);
+                    let ship =
+                        Ship::new(ShipId::new(id.parse()?), player_id, position, entity.energy);
+                    frame_board[position].add_ship(ship.id);
+                    ships.insert(ship.id, ship);
+                }
+            }
+
+            frames.push(ReplayFrame {
+                board: frame_board,
+                ships,
+                dropoffs: dropoffs.clone(),
+                halite: halite.clone(),
+            });
+        }
+
+        Ok(Replay {
+            constants: raw.constants,
+            players,
+            frames,
+        })
+    }
+
+    /// Return the final state of the Game.
+    pub fn last_frame(&self) -> Option<&ReplayFrame> {
+        self.frames.last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures::REPLAY;
+    use super::*;
+
+    #[test]
+    fn test_replay_from_reader() {
+        let compressed = zstd::encode_all(REPLAY.as_bytes(), 0).unwrap();
+        let replay = Replay::from_reader(&compressed[..]).unwrap();
+        assert_eq!(replay.constants.game_seed, 42);
+        assert_eq!(replay.players[1].name, "Other");
+        assert_eq!(replay.players[1].shipyard.position, Position::new(3, 3));
+        assert_eq!(replay.frames.len(), 3);
+
+        let first = &replay.frames[0];
+        assert_eq!(first.board[Position::new(3, 0)].halite, 40);
+        assert!(first.board[Position::new(1, 1)].has_structure());
+        assert_eq!(first.halite[&PlayerId::new(0)], 5000);
+
+        let second = &replay.frames[1];
+        assert_eq!(second.ships.len(), 2);
+        assert_eq!(
+            second.board[Position::new(3, 3)].ship(),
+            Some(ShipId::new(1))
+        );
+
+        let last = replay.last_frame().unwrap();
+        assert_eq!(last.ships[&ShipId::new(1)].halite, 30);
+        assert_eq!(last.halite[&PlayerId::new(0)], 10);
+        assert_eq!(last.halite[&PlayerId::new(1)], 4000);
+        assert_eq!(last.board[Position::new(2, 1)].halite, 0);
+        assert_eq!(
+            last.dropoffs[&DropoffId::new(2)].position,
+            Position::new(2, 1)
+        );
+        assert!(last.board[Position::new(2, 1)].has_structure());
+    }
+}
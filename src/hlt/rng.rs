@@ -0,0 +1,76 @@
+use rand::rngs::StdRng;
+use rand::{Error, RngCore, SeedableRng};
+
+/// The single source of randomness for a Game.
+///
+/// It is seeded from the game seed by default, so that a Game can be reproduced by replaying it
+/// with the same seed.
+#[derive(Clone, Debug)]
+pub struct GameRng {
+    /// The seed this GameRng was created with.
+    seed: u64,
+    /// The underlying random number generator.
+    rng: StdRng,
+}
+
+impl GameRng {
+    /// Create a new GameRng from the given seed.
+    pub fn new(seed: u64) -> Self {
+        let mut bytes = [0; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = (seed >> (8 * (i % 8))) as u8;
+        }
+        GameRng {
+            seed,
+            rng: StdRng::from_seed(bytes),
+        }
+    }
+
+    /// Return the seed this GameRng was created with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+/// Two GameRngs are considered equal if they were created with the same seed.
+impl PartialEq for GameRng {
+    fn eq(&self, other: &GameRng) -> bool {
+        self.seed == other.seed
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_game_rng_is_reproducible() {
+        let mut a = GameRng::new(1539764156);
+        let mut b = GameRng::new(1539764156);
+        let mut c = GameRng::new(1539764157);
+        let xs: Vec<u32> = (0..10).map(|_| a.gen()).collect();
+        let ys: Vec<u32> = (0..10).map(|_| b.gen()).collect();
+        let zs: Vec<u32> = (0..10).map(|_| c.gen()).collect();
+        assert_eq!(xs, ys);
+        assert_ne!(xs, zs);
+    }
+}
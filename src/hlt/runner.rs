@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json;
+
+use super::Result;
+
+/// The environment variable that can be used to point to the `halite` binary.
+pub const HALITE_BINARY_VAR: &str = "HALITE_BINARY";
+
+/// The result for a single Player in a match.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct PlayerStats {
+    /// The final rank of the Player, starting at 1.
+    pub rank: usize,
+    /// The final halite of the Player.
+    pub score: usize,
+}
+
+/// The results of a match, as reported by the Halite engine with `--results-as-json`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct MatchResult {
+    /// The width of the map.
+    pub map_width: usize,
+    /// The height of the map.
+    pub map_height: usize,
+    /// The seed used to generate the map.
+    pub map_seed: u64,
+    /// The location of the replay file, if one was written.
+    #[serde(default)]
+    pub replay: Option<String>,
+    /// The location of the error log for each Player that errored.
+    #[serde(default)]
+    pub error_logs: HashMap<String, String>,
+    /// The results for each Player, by Player identifier.
+    pub stats: HashMap<String, PlayerStats>,
+    /// Whether each Player was kicked out of the match, by Player identifier.
+    #[serde(default)]
+    pub terminated: HashMap<String, bool>,
+}
+
+impl MatchResult {
+    /// Parse the JSON results printed by the Halite engine.
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Return the results for the given Player.
+    pub fn player(&self, player: usize) -> Option<&PlayerStats> {
+        self.stats.get(&player.to_string())
+    }
+
+    /// Return the Player that came first.
+    pub fn winner(&self) -> Option<usize> {
+        self.stats
+            .iter()
+            .find(|(_, stats)| stats.rank == 1)
+            .and_then(|(player, _)| player.parse().ok())
+    }
+
+    /// Return whether the given Player was kicked out of the match.
+    pub fn is_terminated(&self, player: usize) -> bool {
+        self.terminated
+            .get(&player.to_string())
+            .cloned()
+            .unwrap_or(false)
+    }
+}
+
+/// Return the location of the `halite` binary.
+///
+/// The `HALITE_BINARY` environment variable is used if it is set, otherwise the current directory
+/// and then each directory in `PATH` is searched.
+pub fn find_halite() -> Result<PathBuf> {
+    if let Some(path) = env::var_os(HALITE_BINARY_VAR) {
+        return Ok(PathBuf::from(path));
+    }
+
+    let mut directories = vec![PathBuf::from(".")];
+    if let Some(path) = env::var_os("PATH") {
+        directories.extend(env::split_paths(&path));
+    }
+    directories
+        .into_iter()
+        .map(|directory| directory.join("halite"))
+        .find(|path| path.is_file())
+        .ok_or_else(|| format_err!("unable to find the halite binary"))
+}
+
+/// The width and height of the map in a mini-game.
+pub const MINI_GAME_SIZE: usize = 16;
+
+/// The number of turns in a mini-game.
+pub const MINI_GAME_TURNS: usize = 100;
+
+/// Runs local matches between bots using the official Halite engine.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchRunner {
+    /// The location of the `halite` binary.
+    pub halite: PathBuf,
+    /// The width and height of the map, otherwise the engine picks one.
+    pub size: Option<(usize, usize)>,
+    /// The seed used to generate the map, otherwise the engine picks one.
+    pub seed: Option<u64>,
+    /// The maximum number of turns, otherwise the default for the map size is used.
+    pub turn_limit: Option<usize>,
+    /// The directory to write replays to, otherwise no replays are written.
+    pub replay_directory: Option<PathBuf>,
+    /// The command used to run each bot.
+    pub bots: Vec<String>,
+}
+
+impl MatchRunner {
+    /// Create a new MatchRunner that uses the given `halite` binary.
+    pub fn new<P: AsRef<Path>>(halite: P) -> Self {
+        MatchRunner {
+            halite: halite.as_ref().to_path_buf(),
+            size: None,
+            seed: None,
+            turn_limit: None,
+            replay_directory: None,
+            bots: Vec::new(),
+        }
+    }
+
+    /// Add a bot to the match, given the command to run it.
+    pub fn bot(mut self, command: &str) -> Self {
+        self.bots.push(command.to_string());
+        self
+    }
+
+    /// Set the size of the map.
+    pub fn size(mut self, width: usize, height: usize) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+
+    /// Set the seed used to generate the map.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Set the maximum number of turns.
+    pub fn turn_limit(mut self, turn_limit: usize) -> Self {
+        self.turn_limit = Some(turn_limit);
+        self
+    }
+
+    /// Play a mini-game, on the smallest map for a limited number of turns.
+    ///
+    /// Mini-games take a fraction of the time of a full match, which is enough for smoke tests
+    /// and for curricula that need many games. The engine's other rules, like inspiration, are
+    /// unchanged.
+    pub fn mini_game(self) -> Self {
+        self.size(MINI_GAME_SIZE, MINI_GAME_SIZE)
+            .turn_limit(MINI_GAME_TURNS)
+    }
+
+    /// Return the arguments to pass to the `halite` binary.
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec!["--results-as-json".to_string(), "--no-logs".to_string()];
+        match self.replay_directory {
+            Some(ref directory) => {
+                args.push("--replay-directory".to_string());
+                args.push(directory.display().to_string());
+            }
+            None => args.push("--no-replay".to_string()),
+        }
+        if let Some((width, height)) = self.size {
+            args.extend(vec![
+                "--width".to_string(),
+                width.to_string(),
+                "--height".to_string(),
+                height.to_string(),
+            ]);
+        }
+        if let Some(seed) = self.seed {
+            args.push("--seed".to_string());
+            args.push(seed.to_string());
+        }
+        if let Some(turn_limit) = self.turn_limit {
+            args.push("--turn-limit".to_string());
+            args.push(turn_limit.to_string());
+        }
+        args.extend(self.bots.iter().cloned());
+        args
+    }
+
+    /// Run the match and return the results.
+    pub fn run(&self) -> Result<MatchResult> {
+        if self.bots.len() != 2 && self.bots.len() != 4 {
+            return Err(format_err!(
+                "a match needs 2 or 4 bots, not {}",
+                self.bots.len()
+            ));
+        }
+
+        let output = Command::new(&self.halite).args(self.args()).output()?;
+        if !output.status.success() {
+            return Err(format_err!(
+                "halite exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        MatchResult::from_json(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_result_from_json() {
+        let json = r#"{
+            "error_logs": {"1": "errorlog-1.log"},
+            "execution_time": 1450,
+            "map_generator": "basic",
+            "map_height": 32,
+            "map_seed": 1539764156,
+            "map_width": 32,
+            "replay": "replay-20181026-1.hlt",
+            "stats": {
+                "0": {"rank": 1, "score": 20815},
+                "1": {"rank": 2, "score": 1000}
+            },
+            "terminated": {"0": false, "1": true}
+        }"#;
+        let result = MatchResult::from_json(json).unwrap();
+        assert_eq!(result.map_width, 32);
+        assert_eq!(result.winner(), Some(0));
+        assert_eq!(result.player(1).unwrap().score, 1000);
+        assert!(result.is_terminated(1));
+        assert!(!result.is_terminated(0));
+    }
+
+    #[test]
+    fn test_match_runner_args() {
+        let runner = MatchRunner::new("halite")
+            .size(32, 32)
+            .seed(7)
+            .bot("./old_bot")
+            .bot("./my_bot");
+        assert_eq!(
+            runner.args().join(" "),
+            "--results-as-json --no-logs --no-replay --width 32 --height 32 --seed 7 \
+             ./old_bot ./my_bot"
+        );
+
+        let runner = MatchRunner::new("halite").mini_game().bot("./my_bot");
+        assert_eq!(
+            runner.args().join(" "),
+            "--results-as-json --no-logs --no-replay --width 16 --height 16 --turn-limit 100 \
+             ./my_bot"
+        );
+    }
+
+    /// Play the release build against itself, run with `cargo test -- --ignored` once the
+    /// `halite` binary is available and `cargo build --release` has been run.
+    #[test]
+    #[ignore]
+    fn test_match_self_play() {
+        let bot = concat!(env!("CARGO_MANIFEST_DIR"), "/target/release/my_bot");
+        let result = MatchRunner::new(find_halite().unwrap())
+            .size(32, 32)
+            .seed(1)
+            .bot(bot)
+            .bot(bot)
+            .run()
+            .unwrap();
+        assert!(!result.is_terminated(0) && !result.is_terminated(1));
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+
+use super::{constants, Game, PlayerId, Ship, ShipId};
+
+/// The spread of the final scores, as a fraction of the halite left on the Board.
+const SPREAD: f64 = 0.1;
+
+/// Derived statistics about a Player, updated every turn.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PlayerScore {
+    /// The amount of halite the Player has banked.
+    pub halite: usize,
+    /// The total number of Ships the Player has ever built.
+    pub ships_built: usize,
+    /// The number of Ships the Player currently has.
+    pub ships: usize,
+    /// An estimate of the total halite the Player's Ships have mined.
+    pub halite_mined: usize,
+    /// The total halite currently carried by the Player's Ships.
+    pub halite_carried: usize,
+    /// The number of structures (Shipyard and Dropoffs) the Player has.
+    pub structures: usize,
+}
+
+/// A live scoreboard of all the Players in the Game.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Scoreboard {
+    /// The score for each Player.
+    pub scores: HashMap<PlayerId, PlayerScore>,
+}
+
+impl Scoreboard {
+    /// Create a new empty Scoreboard.
+    pub fn new() -> Self {
+        Scoreboard {
+            scores: HashMap::new(),
+        }
+    }
+
+    /// Return the score of the given Player.
+    pub fn get(&self, player_id: PlayerId) -> Option<&PlayerScore> {
+        self.scores.get(&player_id)
+    }
+
+    /// Return the Players ordered from the most banked halite to the least.
+    pub fn ranking(&self) -> Vec<PlayerId> {
+        let mut players: Vec<_> = self.scores.keys().cloned().collect();
+        players.sort_by_key(|id| (!self.scores[id].halite, *id));
+        players
+    }
+
+    /// Update the Scoreboard, given the Ships from the previous turn.
+    pub fn record(&mut self, game: &Game, previous_ships: &HashMap<ShipId, Ship>) {
+        for player in game.players.values() {
+            let score = self.scores.entry(player.id).or_default();
+            score.halite = player.halite;
+            score.ships = player.ship_ids.len();
+            score.structures = 1 + player.dropoff_ids.len();
+            score.halite_carried = 0;
+
+            for ship_id in &player.ship_ids {
+                let ship = &game.ships[ship_id];
+                score.halite_carried += ship.halite;
+                match previous_ships.get(ship_id) {
+                    // Any increase in cargo must have been mined.
+                    Some(previous) => {
+                        score.halite_mined += ship.halite.saturating_sub(previous.halite)
+                    }
+                    None => score.ships_built += 1,
+                }
+            }
+        }
+    }
+}
+
+/// Return the probability of the first score beating all the other scores.
+///
+/// Each score is compared against ours with a logistic curve of the given spread, and the
+/// comparisons are treated as independent.
+fn win_probability(mine: f64, others: &[f64], spread: f64) -> f64 {
+    let spread = spread.max(1.0);
+    others
+        .iter()
+        .map(|other| 1.0 / (1.0 + ((other - mine) / spread).exp()))
+        .product()
+}
+
+impl Game {
+    /// Project the final banked halite of the given Player.
+    ///
+    /// Most of the halite currently carried gets banked, and halite keeps being mined at the
+    /// average rate so far, capped by the Player's share of the halite left on the Board.
+    pub fn projected_score(&self, player_id: PlayerId) -> f64 {
+        let score = match self.scoreboard.get(player_id) {
+            Some(score) => score,
+            None => return 0.0,
+        };
+        let fleet = self.ships.len().max(1) as f64;
+        let banked = score.halite as f64 + 0.8 * score.halite_carried as f64;
+        let rate = score.halite_mined as f64 / self.turn.max(1) as f64;
+        let share = self.board_halite() as f64 * score.ships as f64 / fleet;
+        banked + (rate * self.turns_remaining() as f64).min(share)
+    }
+
+    /// Estimate the probability that we win the Game.
+    ///
+    /// Each Player's final score is projected with `projected_score()`. The uncertainty shrinks as
+    /// the halite left and the turns remaining run out.
+    pub fn win_probability(&self) -> f64 {
+        let mine = self.projected_score(self.my_id);
+        let others: Vec<_> = self
+            .players
+            .keys()
+            .filter(|id| **id != self.my_id)
+            .map(|id| self.projected_score(*id))
+            .collect();
+        let fraction = self.turns_remaining() as f64 / constants::get().max_turns.max(1) as f64;
+        let spread = SPREAD * self.board_halite() as f64 * fraction;
+
+        win_probability(mine, &others, spread)
+    }
+
+    /// Return the total halite left on the Board.
+    fn board_halite(&self) -> usize {
+        self.board
+            .cells
+            .iter()
+            .flat_map(|row| row.iter())
+            .map(|cell| cell.halite)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixtures, Position};
+    use super::*;
+
+    #[test]
+    fn test_scoreboard_record() {
+        let mut game = fixtures::empty_game(4, 1, Position::new(3, 0));
+        let (me, a) = (game.my_id, ShipId::new(0));
+        fixtures::add_ship(&mut game, Ship::new(a, me, Position::new(1, 0), 0));
+        let mut scoreboard = Scoreboard::new();
+        scoreboard.record(&game, &HashMap::new());
+        assert_eq!(scoreboard.get(me).unwrap().ships_built, 1);
+
+        // Cargo that was gained was mined.
+        let previous = game.ships.clone();
+        game.ships.get_mut(&a).unwrap().halite = 50;
+        game.players.get_mut(&me).unwrap().halite = 100;
+        scoreboard.record(&game, &previous);
+        let score = scoreboard.get(me).unwrap();
+        assert_eq!(
+            *score,
+            PlayerScore {
+                halite: 100,
+                ships_built: 1,
+                ships: 1,
+                halite_mined: 50,
+                halite_carried: 50,
+                structures: 1,
+            }
+        );
+        assert_eq!(scoreboard.ranking(), vec![me]);
+    }
+
+    #[test]
+    fn test_projected_score() {
+        let mut game = fixtures::game("400 a . Y");
+        let me = game.my_id;
+        game.turn = constants::get().max_turns + 1 - 40;
+        let score = PlayerScore {
+            halite: 100,
+            ships: 1,
+            halite_mined: 5 * game.turn,
+            halite_carried: 50,
+            ..PlayerScore::default()
+        };
+        game.scoreboard.scores.insert(me, score);
+        assert_eq!(game.projected_score(me), 100.0 + 40.0 + 5.0 * 40.0);
+
+        // Future mining can't take more than the halite left on the Board.
+        game.turn -= 100;
+        assert_eq!(game.projected_score(me), 100.0 + 40.0 + 400.0);
+        assert_eq!(game.projected_score(PlayerId(1)), 0.0);
+    }
+
+    #[test]
+    fn test_win_probability() {
+        assert_eq!(win_probability(1000.0, &[], 100.0), 1.0);
+        assert_eq!(win_probability(1000.0, &[1000.0], 100.0), 0.5);
+        assert!(win_probability(2000.0, &[1000.0], 100.0) > 0.99);
+        assert!(win_probability(1000.0, &[2000.0], 100.0) < 0.01);
+
+        // Being ahead matters less when there is more uncertainty.
+        let certain = win_probability(1100.0, &[1000.0], 10.0);
+        let uncertain = win_probability(1100.0, &[1000.0], 1000.0);
+        assert!(certain > uncertain && uncertain > 0.5);
+
+        // It is harder to beat more opponents.
+        assert_eq!(win_probability(1000.0, &[1000.0, 1000.0], 100.0), 0.25);
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde_json;
+
+use super::engine::Engine;
+use super::{Game, Position, Result, Ship, StringIo};
+
+/// How long a write to a client may take before the client is dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_millis(20);
+
+/// The state of the Game streamed to viewers each turn.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DebugFrame {
+    /// The current turn.
+    pub turn: usize,
+    /// The width of the Board.
+    pub width: isize,
+    /// The height of the Board.
+    pub height: isize,
+    /// The halite in each Cell, row by row.
+    pub halite: Vec<Vec<usize>>,
+    /// All the Ships, ordered by identifier.
+    pub ships: Vec<Ship>,
+    /// Our commands this turn, as they are sent to the engine.
+    pub commands: Vec<String>,
+    /// Where we are planning to build Dropoffs.
+    pub planned_dropoffs: Vec<Position>,
+    /// The values of each overlay, by name, row by row.
+    pub overlays: BTreeMap<String, Vec<Vec<f64>>>,
+}
+
+impl DebugFrame {
+    /// Capture the current turn of the Game.
+    pub fn new(game: &Game) -> Self {
+        let board = &game.board;
+        let overlay = |f: &dyn Fn(Position) -> f64| -> Vec<Vec<f64>> {
+            board
+                .cells
+                .iter()
+                .map(|row| row.iter().map(|cell| f(cell.position)).collect())
+                .collect()
+        };
+        let mut overlays = BTreeMap::new();
+        overlays.insert("danger".to_string(), overlay(&|p| game.danger[p]));
+        overlays.insert("inspiration".to_string(), overlay(&|p| game.inspiration[p]));
+        overlays.insert(
+            "gradient".to_string(),
+            overlay(&|p| game.gradient.smoothed(p)),
+        );
+        overlays.insert(
+            "visits".to_string(),
+            overlay(&|p| game.visits.visits(p) as f64),
+        );
+
+        let mut ships: Vec<_> = game.ships.values().cloned().collect();
+        ships.sort_by_key(|ship| ship.id);
+        let commands = game
+            .commands
+            .iter()
+            .map(|command| {
+                let mut io = StringIo::new("");
+                Engine::new(&mut io).send(command);
+                io.output.trim().to_string()
+            }).collect();
+
+        DebugFrame {
+            turn: game.turn,
+            width: board.width,
+            height: board.height,
+            halite: board
+                .cells
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.halite).collect())
+                .collect(),
+            ships,
+            commands,
+            planned_dropoffs: game.planned_dropoffs.clone(),
+            overlays,
+        }
+    }
+}
+
+/// A tiny TCP server that streams the state of the Game to viewers during local games.
+///
+/// Every connected client receives one `DebugFrame` as a line of JSON each time `publish` is
+/// called, so a viewer can watch the bot think. Clients that can't keep up are dropped, so that
+/// they never slow the bot down. This is only available with the `debug-server` feature.
+pub struct DebugServer {
+    /// The address the server is listening on.
+    address: SocketAddr,
+    /// The connected clients.
+    clients: Arc<Mutex<Vec<TcpStream>>>,
+}
+
+impl DebugServer {
+    /// Start listening on the given address, accepting clients on a separate thread.
+    pub fn bind<A: ToSocketAddrs>(address: A) -> Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        let clients = Arc::new(Mutex::new(Vec::new()));
+        let accepted = clients.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream.and_then(|s| s.set_write_timeout(Some(WRITE_TIMEOUT)).map(|_| s)) {
+                    Ok(stream) => accepted.lock().unwrap().push(stream),
+                    Err(err) => warn!("Unable to accept debug client: {}", err),
+                }
+            }
+        });
+        info!("Debug server listening on {}", address);
+        Ok(DebugServer { address, clients })
+    }
+
+    /// Return the address the server is listening on.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Send the current turn of the Game to every connected client.
+    pub fn publish(&self, game: &Game) -> Result<()> {
+        let mut line = serde_json::to_string(&DebugFrame::new(game))?;
+        line.push('\n');
+        self.clients
+            .lock()
+            .unwrap()
+            .retain(|mut client| client.write_all(line.as_bytes()).is_ok());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader};
+
+    use super::super::fixtures;
+    use super::*;
+
+    #[test]
+    fn test_debug_server() {
+        let game = fixtures::game(". a Y .");
+        let server = DebugServer::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(server.address()).unwrap();
+
+        // Wait for the client to be accepted.
+        while server.clients.lock().unwrap().is_empty() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        server.publish(&game).unwrap();
+
+        let mut line = String::new();
+        BufReader::new(client).read_line(&mut line).unwrap();
+        let frame: DebugFrame = serde_json::from_str(&line).unwrap();
+        assert_eq!(frame, DebugFrame::new(&game));
+        assert_eq!(frame.ships.len(), 1);
+        assert_eq!(frame.overlays.len(), 4);
+    }
+}
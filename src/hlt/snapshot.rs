@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+
+use bincode::{self, Options};
+use serde_json;
+
+use super::board::Structure;
+use super::constants::{self, Constants};
+use super::{
+    Board, Dropoff, Game, NavigationOptions, Params, Player, PlayerId, Position, Result, Ship,
+};
+
+/// The most bytes a snapshot read by `Game::from_bytes` or `Game::restore_bytes` may take, so
+/// that a corrupt dump can't make it allocate huge amounts of memory.
+const MAX_SNAPSHOT_BYTES: u64 = 16 << 20;
+
+/// Return the bincode options for snapshots.
+fn bincode_options() -> impl Options {
+    bincode::DefaultOptions::new().with_limit(MAX_SNAPSHOT_BYTES)
+}
+
+/// The complete state of a Game at the start of a turn, as written by `Game::to_json`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Snapshot {
+    /// The Game constants.
+    ///
+    /// These are written with the same names as the engine sends them, which is also how they
+    /// were written when this field was untyped JSON, so older snapshots still load.
+    pub constants: Constants,
+    /// The current bot's identifier.
+    pub my_id: PlayerId,
+    /// The current turn.
+    pub turn: usize,
+    /// All the Players, ordered by identifier.
+    pub players: Vec<Player>,
+    /// All the Ships, ordered by identifier.
+    pub ships: Vec<Ship>,
+    /// All the Dropoffs, ordered by identifier.
+    pub dropoffs: Vec<Dropoff>,
+    /// The halite in each Cell, row by row.
+    pub halite: Vec<Vec<usize>>,
+    /// Where we are planning to build Dropoffs.
+    pub planned_dropoffs: Vec<Position>,
+    /// The strategy parameters.
+    pub params: Params,
+    /// The navigation options.
+    pub navigation: NavigationOptions,
+    /// The seed of the Game's source of randomness.
+    pub rng_seed: u64,
+}
+
+impl Game {
+    /// Capture the state of the Game, so that a turn can be re-run later.
+    pub fn snapshot(&self) -> Snapshot {
+        let mut players: Vec<_> = self.players.values().cloned().collect();
+        players.sort_by_key(|player| player.id);
+        let mut ships: Vec<_> = self.ships.values().cloned().collect();
+        ships.sort_by_key(|ship| ship.id);
+        let mut dropoffs: Vec<_> = self.dropoffs.values().cloned().collect();
+        dropoffs.sort_by_key(|dropoff| dropoff.id);
+
+        Snapshot {
+            constants: constants::get().clone(),
+            my_id: self.my_id,
+            turn: self.turn,
+            players,
+            ships,
+            dropoffs,
+            halite: self
+                .board
+                .cells
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.halite).collect())
+                .collect(),
+            planned_dropoffs: self.planned_dropoffs.clone(),
+            params: self.params.clone(),
+            navigation: self.navigation,
+            rng_seed: self.rng.seed(),
+        }
+    }
+
+    /// Restore a Game from a Snapshot.
+    ///
+    /// The constants are set if they haven't been already, or else they must be the same as the
+    /// snapshot's. Everything derived from the state, like the DangerMap, is recomputed, but
+    /// anything learned over previous turns, like the Occupancy history, starts over.
+    pub fn from_snapshot(snapshot: Snapshot) -> Result<Self> {
+        let mut game = Game::restore(snapshot)?;
+        let (ships, dropoffs, board) = (
+            game.ships.clone(),
+            game.dropoffs.clone(),
+            game.board.clone(),
+        );
+        game.analyze(&ships, &dropoffs, &board);
+        Ok(game)
+    }
+
+    /// Restore a Game from a Snapshot without recomputing anything derived from the state.
+    ///
+    /// This is much faster than `from_snapshot`, for restoring many states in a turn, for example
+    /// in a search that only looks at the Board and the Ships. The DangerMap and the other
+    /// analysis are left empty until the next update.
+    pub fn restore(snapshot: Snapshot) -> Result<Self> {
+        match constants::try_get() {
+            Some(constants) if *constants != snapshot.constants => {
+                return Err(format_err!(
+                    "snapshot constants are different from the Game constants"
+                ));
+            }
+            Some(_) => {}
+            None => constants::set(snapshot.constants),
+        }
+
+        let height = snapshot.halite.len();
+        let width = snapshot.halite.first().map_or(0, |row| row.len());
+        if height == 0 || snapshot.halite.iter().any(|row| row.len() != width) {
+            return Err(format_err!("snapshot halite must be a non-empty grid"));
+        }
+        let mut board = Board::new(width as isize, height as isize);
+        for (row, halite) in board.cells.iter_mut().zip(&snapshot.halite) {
+            for (cell, halite) in row.iter_mut().zip(halite) {
+                cell.halite = *halite;
+            }
+        }
+
+        let mut players = HashMap::new();
+        for player in snapshot.players {
+            let shipyard = player.shipyard;
+            board[shipyard.position].structure = Some(Structure::Shipyard(shipyard.id));
+            players.insert(player.id, player);
+        }
+        if !players.contains_key(&snapshot.my_id) {
+            return Err(format_err!(
+                "snapshot does not contain Player {}",
+                snapshot.my_id
+            ));
+        }
+
+        if let Some(ship) = snapshot
+            .ships
+            .iter()
+            .find(|ship| !players.contains_key(&ship.player_id))
+        {
+            return Err(format_err!(
+                "snapshot Ship {} belongs to unknown Player {}",
+                ship.id,
+                ship.player_id
+            ));
+        }
+
+        let mut game = Game::new(snapshot.my_id, players, board);
+        for ship in snapshot.ships {
+            game.board[ship.position].add_ship(ship.id);
+            game.ships.insert(ship.id, ship);
+        }
+        for dropoff in snapshot.dropoffs {
+            game.board[dropoff.position].structure = Some(Structure::Dropoff(dropoff.id));
+            game.dropoffs.insert(dropoff.id, dropoff);
+        }
+        game.turn = snapshot.turn;
+        game.planned_dropoffs = snapshot.planned_dropoffs;
+        game.params = snapshot.params;
+        game.navigation = snapshot.navigation;
+        game.seed_rng(snapshot.rng_seed);
+        Ok(game)
+    }
+
+    /// Serialize the state of the Game as JSON, for example to attach to a bug report.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.snapshot()).unwrap()
+    }
+
+    /// Restore a Game from JSON written by `Game::to_json`.
+    pub fn from_json(json: &str) -> Result<Self> {
+        Game::from_snapshot(serde_json::from_str(json)?)
+    }
+
+    /// Serialize the state of the Game in a compact binary format.
+    ///
+    /// This is much smaller and faster than JSON, for checkpointing many states in a turn or
+    /// keeping crash dumps small, but isn't readable and only works with the same version of the
+    /// bot.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode_options().serialize(&self.snapshot()).unwrap()
+    }
+
+    /// Restore a Game from bytes written by `Game::to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Game::from_snapshot(bincode_options().deserialize_from(bytes)?)
+    }
+
+    /// Restore a Game from bytes written by `Game::to_bytes`, without the analysis, see `restore`.
+    pub fn restore_bytes(bytes: &[u8]) -> Result<Self> {
+        Game::restore(bincode_options().deserialize_from(bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures;
+    use super::*;
+
+    #[test]
+    fn test_game_json_round_trip() {
+        let (mut game, mut io) = fixtures::start(fixtures::get("2p-48x48"));
+        while !io.input.is_empty() {
+            game.update_with(&mut io).unwrap();
+        }
+        game.seed_rng(7);
+
+        let json = game.to_json();
+        let restored = Game::from_json(&json).unwrap();
+        assert_eq!(restored.turn, game.turn);
+        assert_eq!(restored.players, game.players);
+        assert_eq!(restored.ships, game.ships);
+        assert_eq!(restored.dropoffs, game.dropoffs);
+        assert_eq!(restored.board, game.board);
+        assert_eq!(restored.danger, game.danger);
+        assert_eq!(restored.to_json(), json);
+
+        assert!(Game::from_json("{}").is_err());
+    }
+
+    #[test]
+    fn test_game_bytes_round_trip() {
+        let (mut game, mut io) = fixtures::start(fixtures::get("2p-32x32"));
+        while !io.input.is_empty() {
+            game.update_with(&mut io).unwrap();
+        }
+
+        let bytes = game.to_bytes();
+        assert!(bytes.len() < game.to_json().len());
+        let restored = Game::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.snapshot(), game.snapshot());
+        assert_eq!(restored.board, game.board);
+
+        assert!(Game::from_bytes(&bytes[..10]).is_err());
+
+        let restored = Game::restore_bytes(&bytes).unwrap();
+        assert_eq!(restored.snapshot(), game.snapshot());
+        assert_eq!(restored.board, game.board);
+
+        // A corrupt length can't make it allocate more than the limit.
+        let corrupt = [0xfc, 0xff, 0xff, 0xff, 0xff];
+        let err = bincode_options().deserialize_from::<_, String>(&corrupt[..]);
+        match *err.unwrap_err() {
+            bincode::ErrorKind::SizeLimit => {}
+            ref other => panic!("expected the size limit, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_game_snapshot_validation() {
+        let game = fixtures::game(". a Y");
+        assert!(Game::restore(game.snapshot()).is_ok());
+
+        let mut snapshot = game.snapshot();
+        snapshot.constants.max_turns += 1;
+        assert!(Game::restore(snapshot).is_err());
+
+        let mut snapshot = game.snapshot();
+        snapshot.ships[0].player_id = PlayerId(9);
+        assert!(Game::restore(snapshot).is_err());
+    }
+
+    #[test]
+    fn test_game_json_untyped_constants() {
+        let (game, _) = fixtures::start(fixtures::get("2p-32x32"));
+        let mut snapshot = serde_json::to_value(game.snapshot()).unwrap();
+        snapshot["constants"] = serde_json::to_value(constants::get()).unwrap();
+        let restored = Game::from_json(&snapshot.to_string()).unwrap();
+        assert_eq!(restored.snapshot(), game.snapshot());
+    }
+}
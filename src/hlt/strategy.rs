@@ -0,0 +1,141 @@
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+use super::board::Offset;
+use super::{Board, Direction, Position};
+
+/// The largest Board dimension generated.
+const MAX_DIMENSION: isize = 64;
+
+/// Generate a Board dimension.
+pub fn dimension() -> impl Strategy<Value = isize> {
+    1..=MAX_DIMENSION
+}
+
+/// Generate a Position, possibly well outside the given dimensions.
+pub fn position(width: isize, height: isize) -> impl Strategy<Value = Position> {
+    (-2 * width..3 * width, -2 * height..3 * height).prop_map(|(x, y)| Position::new(x, y))
+}
+
+/// Generate an Offset, possibly spanning more than the given dimensions.
+pub fn offset(width: isize, height: isize) -> impl Strategy<Value = Offset> {
+    (-2 * width..=2 * width, -2 * height..=2 * height).prop_map(|(dx, dy)| Offset::new(dx, dy))
+}
+
+/// Generate a Direction.
+pub fn direction() -> impl Strategy<Value = Direction> {
+    prop_oneof![
+        Just(Direction::North),
+        Just(Direction::East),
+        Just(Direction::South),
+        Just(Direction::West),
+        Just(Direction::Still),
+    ]
+}
+
+/// Generate a Board with random halite in each Cell.
+pub fn board() -> impl Strategy<Value = Board> {
+    (dimension(), dimension()).prop_flat_map(|(width, height)| {
+        vec(0..=1000usize, (width * height) as usize).prop_map(move |halite| {
+            let mut board = Board::new(width, height);
+            for (cell, halite) in board.cells.iter_mut().flat_map(|r| r.iter_mut()).zip(halite) {
+                cell.halite = halite;
+            }
+            board
+        })
+    })
+}
+
+/// Generate a Board along with two Positions.
+pub fn board_and_positions() -> impl Strategy<Value = (Board, Position, Position)> {
+    board().prop_flat_map(|board| {
+        let (width, height) = (board.width, board.height);
+        (Just(board), position(width, height), position(width, height))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The wrapped distance along one dimension, computed the obvious way.
+    fn wrapped(a: isize, b: isize, dimension: isize) -> usize {
+        let d = ((a - b) % dimension + dimension) % dimension;
+        d.min(dimension - d) as usize
+    }
+
+    proptest! {
+        #[test]
+        fn test_normalized_is_idempotent(
+            (width, height, p) in (dimension(), dimension())
+                .prop_flat_map(|(w, h)| (Just(w), Just(h), position(w, h)))
+        ) {
+            let normalized = p.normalized(width, height);
+            prop_assert_eq!(normalized.normalized(width, height), normalized);
+            prop_assert!(normalized.x >= 0 && normalized.x < width);
+            prop_assert!(normalized.y >= 0 && normalized.y < height);
+        }
+
+        #[test]
+        fn test_reduce_is_distance((board, a, b) in board_and_positions()) {
+            let expected = wrapped(a.x, b.x, board.width) + wrapped(a.y, b.y, board.height);
+            prop_assert_eq!((a - b).reduce(board.width, board.height).len(), expected);
+            prop_assert_eq!(board.distance(a, b), expected);
+            prop_assert_eq!(board.distance(b, a), expected);
+        }
+
+        #[test]
+        fn test_offset_reaches_target((board, a, b) in board_and_positions()) {
+            let (width, height) = (board.width, board.height);
+            let reached = (a + board.offset(a, b)).normalized(width, height);
+            prop_assert_eq!(reached, b.normalized(width, height));
+        }
+
+        #[test]
+        fn test_reduce_is_shortest(
+            (width, height, o) in (dimension(), dimension())
+                .prop_flat_map(|(w, h)| (Just(w), Just(h), offset(w, h)))
+        ) {
+            let reduced = o.reduce(width, height);
+            prop_assert!(reduced.len() <= o.len());
+            prop_assert!(reduced.dx.abs() * 2 <= width && reduced.dy.abs() * 2 <= height);
+            prop_assert_eq!((reduced.dx - o.dx) % width, 0);
+            prop_assert_eq!((reduced.dy - o.dy) % height, 0);
+        }
+
+        #[test]
+        fn test_directions_toward_get_closer((board, a, b) in board_and_positions()) {
+            let distance = board.distance(a, b);
+            for direction in board.directions_toward(a, b) {
+                prop_assert_eq!(board.distance(a + direction, b) + 1, distance);
+            }
+            prop_assert_eq!(board.directions_toward(a, b).is_empty(), distance == 0);
+        }
+
+        #[test]
+        fn test_moving_changes_distance_by_one(
+            (board, a, b) in board_and_positions(),
+            direction in direction()
+        ) {
+            let before = board.distance(a, b) as isize;
+            let after = board.distance(a + direction, b) as isize;
+            prop_assert!((before - after).abs() <= 1);
+        }
+
+        #[test]
+        fn test_positions_within(
+            (board, center) in board()
+                .prop_flat_map(|b| { let (w, h) = (b.width, b.height); (Just(b), position(w, h)) }),
+            radius in 0usize..6
+        ) {
+            let positions = board.positions_within(center, radius);
+            let expected = board
+                .cells
+                .iter()
+                .flat_map(|row| row.iter())
+                .filter(|cell| board.distance(center, cell.position) <= radius)
+                .count();
+            prop_assert_eq!(positions.len(), expected);
+        }
+    }
+}
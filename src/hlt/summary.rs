@@ -0,0 +1,16 @@
+use std::time::Duration;
+
+use super::{Command, ShipId};
+
+/// What was sent to the Halite engine at the end of a turn.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TurnSummary {
+    /// The turn that ended.
+    pub turn: usize,
+    /// The commands that were sent.
+    pub commands: Vec<Command>,
+    /// Our Ships that weren't given a command, so they stay still and collect halite.
+    pub uncommanded: Vec<ShipId>,
+    /// How long the turn took, from reading the frame to sending the commands.
+    pub elapsed: Duration,
+}
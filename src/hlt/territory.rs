@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use super::{Board, Grid, PlayerId, Position};
+
+/// The default difference in distance below which a Cell is considered contested.
+const DEFAULT_CONTEST_MARGIN: usize = 2;
+
+/// A partition of the Board by which Player has the closest structure.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Territory {
+    /// The Player controlling each Position, if any single Player has the closest structure.
+    pub owners: Grid<Option<PlayerId>>,
+    /// Whether each Position is about as close to another Player's structures as to its owner's.
+    pub contested: Grid<bool>,
+    /// The total halite in each Player's territory.
+    pub halite: HashMap<PlayerId, usize>,
+}
+
+impl Territory {
+    /// Create a new Territory where nothing is controlled.
+    pub fn new(width: isize, height: isize) -> Self {
+        Territory {
+            owners: Grid::new(width, height, None),
+            contested: Grid::new(width, height, false),
+            halite: HashMap::new(),
+        }
+    }
+
+    /// Compute the Territory given the Positions of every Player's structures.
+    pub fn compute(board: &Board, structures: &[(PlayerId, Position)]) -> Self {
+        let mut territory = Territory::new(board.width, board.height);
+
+        for row in &board.cells {
+            for cell in row {
+                // The closest structure distance for each Player.
+                let mut distances: HashMap<PlayerId, usize> = HashMap::new();
+                for (player_id, position) in structures {
+                    let distance = board.distance(cell.position, *position);
+                    let entry = distances.entry(*player_id).or_insert(distance);
+                    if distance < *entry {
+                        *entry = distance;
+                    }
+                }
+
+                let mut distances: Vec<_> = distances.into_iter().map(|(p, d)| (d, p)).collect();
+                distances.sort();
+
+                let position = cell.position;
+                match (distances.first(), distances.get(1)) {
+                    (Some((d1, _)), Some((d2, _))) if d1 == d2 => {
+                        territory.contested[position] = true;
+                    }
+                    (Some((d1, owner)), second) => {
+                        territory.owners[position] = Some(*owner);
+                        territory.contested[position] =
+                            second.is_some_and(|(d2, _)| d2 - d1 <= DEFAULT_CONTEST_MARGIN);
+                        *territory.halite.entry(*owner).or_insert(0) += cell.halite;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        territory
+    }
+
+    /// Return the Player controlling the given Position, if any.
+    pub fn owner(&self, position: Position) -> Option<PlayerId> {
+        self.owners[position]
+    }
+
+    /// Return whether the given Position is contested.
+    pub fn is_contested(&self, position: Position) -> bool {
+        self.contested[position]
+    }
+
+    /// Return the total halite in the given Player's territory.
+    pub fn halite(&self, player_id: PlayerId) -> usize {
+        self.halite.get(&player_id).cloned().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_territory_compute() {
+        let board = Board::from_ascii("10 10 10 10 10 10 10 10 10 10").unwrap();
+        let structures = [
+            (PlayerId::new(0), Position::new(0, 0)),
+            (PlayerId::new(1), Position::new(4, 0)),
+        ];
+
+        let territory = Territory::compute(&board, &structures);
+        assert_eq!(territory.owner(Position::new(1, 0)), Some(PlayerId::new(0)));
+        assert_eq!(territory.owner(Position::new(3, 0)), Some(PlayerId::new(1)));
+        assert_eq!(territory.owner(Position::new(2, 0)), None);
+        assert!(territory.is_contested(Position::new(2, 0)));
+        assert!(territory.is_contested(Position::new(1, 0)));
+        assert!(!territory.is_contested(Position::new(5, 0)));
+        assert_eq!(territory.halite(PlayerId::new(0)), 40);
+        assert_eq!(territory.halite(PlayerId::new(1)), 40);
+    }
+}
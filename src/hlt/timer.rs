@@ -0,0 +1,149 @@
+use std::time::{Duration, Instant};
+
+/// The clock for the current turn, started when the last of the frame has been read.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TurnTimer {
+    /// When the turn started.
+    pub started: Instant,
+}
+
+impl Default for TurnTimer {
+    fn default() -> Self {
+        TurnTimer::new()
+    }
+}
+
+impl TurnTimer {
+    /// Create a new TurnTimer started now.
+    pub fn new() -> Self {
+        TurnTimer {
+            started: Instant::now(),
+        }
+    }
+
+    /// Restart the TurnTimer.
+    pub fn restart(&mut self) {
+        self.started = Instant::now();
+    }
+
+    /// Return how long it has been since the turn started.
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Return how much of the given budget is left, or zero if it has run out.
+    pub fn remaining(&self, budget: Duration) -> Duration {
+        budget
+            .checked_sub(self.elapsed())
+            .unwrap_or(Duration::from_secs(0))
+    }
+}
+
+/// The time limits of the Halite engine, with a safety margin kept back for talking to it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TurnBudget {
+    /// The engine's limit for each turn.
+    pub turn_limit: Duration,
+    /// The engine's limit for initialization, until we send our name.
+    pub init_limit: Duration,
+    /// The time kept back from each limit for reading frames and sending commands.
+    pub margin: Duration,
+}
+
+impl Default for TurnBudget {
+    fn default() -> Self {
+        TurnBudget::new()
+    }
+}
+
+impl TurnBudget {
+    /// Create a new TurnBudget with the engine's default limits.
+    pub fn new() -> Self {
+        TurnBudget {
+            turn_limit: Duration::from_secs(2),
+            init_limit: Duration::from_secs(30),
+            margin: Duration::from_millis(200),
+        }
+    }
+
+    /// Return how long we may spend on a turn, with the margin applied.
+    pub fn turn(&self) -> Duration {
+        self.turn_limit
+            .checked_sub(self.margin)
+            .unwrap_or(Duration::from_secs(0))
+    }
+
+    /// Return how long we may spend on initialization, with the margin applied.
+    pub fn init(&self) -> Duration {
+        self.init_limit
+            .checked_sub(self.margin)
+            .unwrap_or(Duration::from_secs(0))
+    }
+}
+
+/// A point in time by which some work should be finished.
+///
+/// Anytime algorithms check it regularly and return the best result so far once it has passed.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Deadline {
+    at: Option<Instant>,
+}
+
+impl Deadline {
+    /// Create a Deadline that never passes.
+    pub fn never() -> Self {
+        Deadline { at: None }
+    }
+
+    /// Create a Deadline at the given instant.
+    pub fn at(instant: Instant) -> Self {
+        Deadline { at: Some(instant) }
+    }
+
+    /// Create a Deadline the given duration from now.
+    pub fn after(duration: Duration) -> Self {
+        Deadline::at(Instant::now() + duration)
+    }
+
+    /// Return whether the Deadline has passed.
+    pub fn is_expired(&self) -> bool {
+        self.at.is_some_and(|at| Instant::now() >= at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turn_timer() {
+        let mut timer = TurnTimer::new();
+        timer.started -= Duration::from_millis(500);
+        assert!(timer.elapsed() >= Duration::from_millis(500));
+        assert!(timer.remaining(Duration::from_secs(2)) <= Duration::from_millis(1500));
+        assert_eq!(
+            timer.remaining(Duration::from_millis(100)),
+            Duration::from_secs(0)
+        );
+
+        timer.restart();
+        assert!(timer.elapsed() < Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_turn_budget() {
+        let mut budget = TurnBudget::new();
+        assert_eq!(budget.turn(), Duration::from_millis(1800));
+        assert_eq!(budget.init(), Duration::from_millis(29800));
+
+        budget.margin = Duration::from_secs(5);
+        assert_eq!(budget.turn(), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn test_deadline() {
+        assert!(!Deadline::never().is_expired());
+        assert!(!Deadline::after(Duration::from_secs(60)).is_expired());
+        assert!(Deadline::at(Instant::now()).is_expired());
+    }
+}
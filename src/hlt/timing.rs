@@ -0,0 +1,219 @@
+use std::cell::RefCell;
+use std::time::Duration;
+
+/// Time a block as a phase of the turn, recording it in the Timings for this thread.
+///
+/// The value of the block is returned, so a computation can be timed where it is used:
+///
+/// ```ignore
+/// let costs = timed!{ "costs" => { CostMap::compute(&game.board, &sources) } };
+/// ```
+#[macro_export]
+macro_rules! timed {
+    ($name:expr => $body:block) => {{
+        let started = ::std::time::Instant::now();
+        let value = $body;
+        $crate::hlt::timing::record($name, started.elapsed());
+        value
+    }};
+}
+
+thread_local! {
+    /// The Timings for the Game played on this thread.
+    static TIMINGS: RefCell<Timings> = RefCell::new(Timings::new());
+}
+
+/// How long one phase of the turn took, on every turn it ran.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Phase {
+    /// The name of the phase.
+    pub name: String,
+    /// The total time spent in the phase, for each turn it ran.
+    pub durations: Vec<Duration>,
+    /// The last turn the phase ran.
+    last_turn: usize,
+}
+
+/// Summary statistics for one phase, across all turns it ran.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PhaseSummary {
+    /// The name of the phase.
+    pub name: String,
+    /// The number of turns the phase ran.
+    pub turns: usize,
+    /// The fastest turn.
+    pub min: Duration,
+    /// The median turn.
+    pub median: Duration,
+    /// The slowest turn.
+    pub max: Duration,
+    /// The total time spent in the phase.
+    pub total: Duration,
+}
+
+/// Per-phase timings across all turns of the Game.
+///
+/// A phase that runs several times on the same turn, like assigning deposit points, counts as
+/// one sample with the total time.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Timings {
+    /// The current turn.
+    pub turn: usize,
+    /// The phases, in the order they first ran.
+    pub phases: Vec<Phase>,
+    /// Phases slower than this are logged as they are recorded.
+    pub slow: Option<Duration>,
+}
+
+impl Timings {
+    /// Create new empty Timings.
+    pub fn new() -> Self {
+        Timings::default()
+    }
+
+    /// Start a new turn.
+    pub fn start_turn(&mut self, turn: usize) {
+        self.turn = turn;
+    }
+
+    /// Record that the given phase took the given duration on the current turn.
+    pub fn record(&mut self, name: &str, duration: Duration) {
+        if self.slow.is_some_and(|slow| duration > slow) {
+            warn!("Slow phase {} took {}", name, format_millis(duration));
+        }
+
+        let turn = self.turn;
+        match self.phases.iter_mut().find(|phase| phase.name == name) {
+            Some(ref mut phase) if phase.last_turn == turn => {
+                *phase.durations.last_mut().unwrap() += duration;
+            }
+            Some(phase) => {
+                phase.durations.push(duration);
+                phase.last_turn = turn;
+            }
+            None => self.phases.push(Phase {
+                name: name.to_string(),
+                durations: vec![duration],
+                last_turn: turn,
+            }),
+        }
+    }
+
+    /// Summarize every phase, in the order they first ran.
+    pub fn summaries(&self) -> Vec<PhaseSummary> {
+        self.phases
+            .iter()
+            .map(|phase| {
+                let mut durations = phase.durations.clone();
+                durations.sort();
+                PhaseSummary {
+                    name: phase.name.clone(),
+                    turns: durations.len(),
+                    min: durations[0],
+                    median: durations[durations.len() / 2],
+                    max: durations[durations.len() - 1],
+                    total: durations.iter().sum(),
+                }
+            }).collect()
+    }
+
+    /// Log a summary of every phase.
+    pub fn report(&self) {
+        info!("Timings per turn:");
+        info!(
+            "{:<16} {:>6} {:>10} {:>10} {:>10} {:>10}",
+            "phase", "turns", "min", "median", "max", "total"
+        );
+        for s in self.summaries() {
+            info!(
+                "{:<16} {:>6} {:>10} {:>10} {:>10} {:>10}",
+                s.name,
+                s.turns,
+                format_millis(s.min),
+                format_millis(s.median),
+                format_millis(s.max),
+                format_millis(s.total)
+            );
+        }
+    }
+}
+
+/// Format a Duration in milliseconds.
+fn format_millis(duration: Duration) -> String {
+    format!("{:.2}ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Start a new turn in the Timings for this thread.
+pub fn start_turn(turn: usize) {
+    TIMINGS.with(|timings| timings.borrow_mut().start_turn(turn));
+}
+
+/// Record that the given phase took the given duration in the Timings for this thread.
+pub fn record(name: &str, duration: Duration) {
+    TIMINGS.with(|timings| timings.borrow_mut().record(name, duration));
+}
+
+/// Log phases slower than the given duration as they are recorded on this thread, or stop logging
+/// them if it is `None`.
+pub fn set_slow(slow: Option<Duration>) {
+    TIMINGS.with(|timings| timings.borrow_mut().slow = slow);
+}
+
+/// Return a copy of the Timings for this thread.
+pub fn get() -> Timings {
+    TIMINGS.with(|timings| timings.borrow().clone())
+}
+
+/// Log a summary of the Timings for this thread.
+pub fn report() {
+    TIMINGS.with(|timings| timings.borrow().report());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timings() {
+        let ms = Duration::from_millis;
+        let mut timings = Timings::new();
+        for turn in 1..4 {
+            timings.start_turn(turn);
+            timings.record("parse", ms(turn as u64));
+            timings.record("assignment", ms(1));
+            timings.record("assignment", ms(2));
+        }
+        timings.start_turn(4);
+        timings.record("parse", ms(10));
+
+        let summaries = timings.summaries();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(
+            summaries[0],
+            PhaseSummary {
+                name: "parse".to_string(),
+                turns: 4,
+                min: ms(1),
+                median: ms(3),
+                max: ms(10),
+                total: ms(16),
+            }
+        );
+        assert_eq!(summaries[1].name, "assignment");
+        assert_eq!(summaries[1].turns, 3);
+        assert_eq!(summaries[1].max, ms(3));
+    }
+
+    #[test]
+    fn test_timed() {
+        start_turn(1);
+        let value = timed!{ "sum" => { (0..10).sum::<usize>() } };
+        assert_eq!(value, 45);
+        timed!{ "sum" => {} };
+
+        let timings = get();
+        assert_eq!(timings.phases.len(), 1);
+        assert_eq!(timings.phases[0].name, "sum");
+        assert_eq!(timings.phases[0].durations.len(), 1);
+    }
+}
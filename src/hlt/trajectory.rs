@@ -0,0 +1,169 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use serde_json;
+
+use super::features::{FeatureOptions, Features};
+use super::{Game, Result, ShipId};
+
+/// The reward for one turn, from our point of view.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Reward {
+    /// The change in our banked halite since the previous step.
+    pub banked: i64,
+    /// The number of our Ships that disappeared since the previous step, not counting conversions.
+    pub ships_lost: usize,
+    /// On the last step, 1 if we won and -1 if we didn't, where ties count as not winning.
+    pub terminal: Option<f64>,
+}
+
+/// One step of a trajectory, as written on one line of the export.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Step {
+    /// The turn.
+    pub turn: usize,
+    /// The Features of the Game at the start of the turn, empty on the last step.
+    pub features: Vec<f32>,
+    /// The reward for what happened since the previous step.
+    pub reward: Reward,
+}
+
+/// Writes the Features and reward for every turn as JSON lines, for reinforcement learning.
+///
+/// Record each turn right after the update, before any commands are given. The reward on a line is
+/// the result of the commands given on the previous line's turn, and after the Game is over
+/// `finish` writes a last line with no Features and the terminal reward, so every trajectory is
+/// complete.
+pub struct Trajectory<W: Write = BufWriter<File>> {
+    writer: W,
+    /// How the Features are extracted.
+    pub options: FeatureOptions,
+    /// Our banked halite, Ships, and Dropoff count at the previous step.
+    previous: Option<(usize, Vec<ShipId>, usize)>,
+}
+
+impl Trajectory<BufWriter<File>> {
+    /// Create a Trajectory export that writes to the file at the given path.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Trajectory::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> Trajectory<W> {
+    /// Create a Trajectory export that writes to the given writer, with the default Features.
+    pub fn new(writer: W) -> Self {
+        Trajectory {
+            writer,
+            options: FeatureOptions::default(),
+            previous: None,
+        }
+    }
+
+    /// Return the reward since the previous step, and remember the current state for the next.
+    fn reward(&mut self, game: &Game) -> Reward {
+        let me = game.me();
+        let current = (me.halite, me.ship_ids.clone(), me.dropoff_ids.len());
+        let reward = match self.previous {
+            Some((halite, ref ship_ids, dropoffs)) => {
+                let gone = ship_ids
+                    .iter()
+                    .filter(|id| !me.ship_ids.contains(id))
+                    .count();
+                let converted = me.dropoff_ids.len().saturating_sub(dropoffs);
+                Reward {
+                    banked: me.halite as i64 - halite as i64,
+                    ships_lost: gone.saturating_sub(converted),
+                    terminal: None,
+                }
+            }
+            None => Reward {
+                banked: 0,
+                ships_lost: 0,
+                terminal: None,
+            },
+        };
+        self.previous = Some(current);
+        reward
+    }
+
+    /// Write a Step and flush it, so the export is complete even if the bot is killed.
+    fn write(&mut self, step: &Step) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, step)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Write the Features of the current turn of the Game, and the reward since the previous one.
+    pub fn record(&mut self, game: &Game) -> Result<()> {
+        let step = Step {
+            turn: game.turn,
+            features: Features::extract(game, &self.options).values,
+            reward: self.reward(game),
+        };
+        self.write(&step)
+    }
+
+    /// Write the last step, after the Game is over.
+    pub fn finish(&mut self, game: &Game) -> Result<()> {
+        let me = game.me();
+        let won = game
+            .players
+            .values()
+            .all(|player| player.id == me.id || player.halite < me.halite);
+        let mut reward = self.reward(game);
+        reward.terminal = Some(if won { 1.0 } else { -1.0 });
+        let step = Step {
+            turn: game.turn + 1,
+            features: Vec::new(),
+            reward,
+        };
+        self.write(&step)
+    }
+
+    /// Return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures;
+    use super::super::{PlayerId, ShipId};
+    use super::*;
+
+    #[test]
+    fn test_trajectory() {
+        let mut game = fixtures::game(". a Y b .");
+        let mut trajectory = Trajectory::new(Vec::new());
+        trajectory.options.size = 5;
+        trajectory.record(&game).unwrap();
+
+        game.turn += 1;
+        game.players.get_mut(&PlayerId(0)).unwrap().halite = 300;
+        game.players.get_mut(&PlayerId(0)).unwrap().ship_ids = vec![ShipId(0)];
+        trajectory.record(&game).unwrap();
+        trajectory.finish(&game).unwrap();
+
+        let output = String::from_utf8(trajectory.into_inner()).unwrap();
+        let steps: Vec<Step> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[0].features.len(), 6 * 5 * 5);
+        assert_eq!(
+            steps[1].reward,
+            Reward {
+                banked: 300,
+                ships_lost: 1,
+                terminal: None,
+            }
+        );
+        assert_eq!(steps[2].turn, 2);
+        assert!(steps[2].features.is_empty());
+        assert_eq!(steps[2].reward.terminal, Some(1.0));
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use serde_json;
+
+use super::{EngineIo, Result, StdIo};
+
+/// One entry in a transcript, written as a line of JSON.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Entry {
+    /// A line read from the engine, including the trailing newline.
+    Input(String),
+    /// Everything written to the engine between two flushes.
+    Output(String),
+    /// How long a turn took, in seconds, as fed to the Effort controller.
+    Elapsed(f64),
+    /// The Watchdog sent the output for this turn, because it ran past its budget.
+    Fallback(usize),
+}
+
+/// An output that was different when replaying a transcript.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Divergence {
+    /// The number of outputs before this one.
+    pub index: usize,
+    /// What was written when the transcript was recorded, if anything.
+    pub expected: Option<String>,
+    /// What was written when replaying.
+    pub actual: String,
+}
+
+/// Where a TranscriptIo reads its input from.
+enum Source {
+    /// The Halite engine.
+    Engine(StdIo),
+    /// The entries of a recorded transcript that have not been replayed yet.
+    Replay(VecDeque<Entry>),
+}
+
+/// Talks to the Halite engine while recording a transcript, or replays one instead.
+///
+/// A transcript holds everything read from and written to the engine, and how long each turn
+/// took. Replaying it feeds the bot the same input and the same turn times, so with the Game's
+/// seeded randomness it gives the same commands, which makes a bad decision seen in a real Game
+/// easy to step through locally. Any output that comes out differently is kept as a Divergence.
+pub struct TranscriptIo {
+    /// Where the input comes from.
+    source: Source,
+    /// Where the transcript is recorded to, if it is being recorded.
+    recorder: Option<Box<dyn Write + Send>>,
+    /// What has been written since the last flush.
+    buffer: String,
+    /// The number of outputs flushed so far.
+    outputs: usize,
+    /// The outputs that were different from the transcript.
+    pub divergences: Vec<Divergence>,
+}
+
+impl TranscriptIo {
+    /// Talk to the Halite engine without recording anything.
+    pub fn engine() -> Self {
+        TranscriptIo {
+            source: Source::Engine(StdIo::new()),
+            recorder: None,
+            buffer: String::new(),
+            outputs: 0,
+            divergences: Vec::new(),
+        }
+    }
+
+    /// Talk to the Halite engine and record a transcript to the given writer.
+    pub fn record<W: Write + Send + 'static>(writer: W) -> Self {
+        TranscriptIo {
+            recorder: Some(Box::new(writer)),
+            ..TranscriptIo::engine()
+        }
+    }
+
+    /// Talk to the Halite engine and record a transcript to the file at the given path.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(TranscriptIo::record(BufWriter::new(File::create(path)?)))
+    }
+
+    /// Replay the given transcript entries instead of talking to the engine.
+    pub fn replay(entries: Vec<Entry>) -> Self {
+        TranscriptIo {
+            source: Source::Replay(entries.into_iter().collect()),
+            ..TranscriptIo::engine()
+        }
+    }
+
+    /// Replay the transcript in the file at the given path.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut entries = Vec::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            entries.push(serde_json::from_str(&line?)?);
+        }
+        Ok(TranscriptIo::replay(entries))
+    }
+
+    /// Return whether a transcript is being replayed.
+    pub fn is_replaying(&self) -> bool {
+        match self.source {
+            Source::Engine(_) => false,
+            Source::Replay(_) => true,
+        }
+    }
+
+    /// Return how long the turn should be taken to have lasted, given how long it really took.
+    ///
+    /// When recording this is the real time, which is written to the transcript. When replaying
+    /// it is the recorded time, so that everything that depends on it decides the same way.
+    pub fn elapsed(&mut self, elapsed: Duration) -> Result<Duration> {
+        if let Source::Replay(ref mut entries) = self.source {
+            // Outputs that weren't replayed are skipped, but inputs are left for the next turn.
+            loop {
+                match entries.pop_front() {
+                    Some(Entry::Elapsed(seconds)) => return Ok(Duration::from_secs_f64(seconds)),
+                    Some(Entry::Output(_)) | Some(Entry::Fallback(_)) => {}
+                    Some(entry) => {
+                        entries.push_front(entry);
+                        return Ok(elapsed);
+                    }
+                    None => return Ok(elapsed),
+                }
+            }
+        }
+        self.write_entry(&Entry::Elapsed(elapsed.as_secs_f64()))?;
+        Ok(elapsed)
+    }
+
+    /// Return an error describing every output that was different from the transcript, if any.
+    pub fn check_divergences(&self) -> Result<()> {
+        if self.divergences.is_empty() {
+            return Ok(());
+        }
+        let mut message = format!(
+            "the replay differed from the transcript {} times",
+            self.divergences.len()
+        );
+        for divergence in &self.divergences {
+            message.push_str(&format!(
+                "\n    output {} differs, expected {:?} but sent {:?}",
+                divergence.index, divergence.expected, divergence.actual
+            ));
+        }
+        Err(format_err!("{}", message))
+    }
+
+    /// Record that the Watchdog sent the output for the given turn.
+    ///
+    /// Replaying runs every turn to the end, so the output of these turns is expected to differ.
+    pub fn record_fallback(&mut self, turn: usize) -> Result<()> {
+        if self.is_replaying() {
+            return Ok(());
+        }
+        self.write_entry(&Entry::Fallback(turn))
+    }
+
+    /// Write an entry to the transcript, if it is being recorded.
+    fn write_entry(&mut self, entry: &Entry) -> Result<()> {
+        if let Some(ref mut recorder) = self.recorder {
+            serde_json::to_writer(&mut *recorder, entry)?;
+            recorder.write_all(b"\n")?;
+            recorder.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl EngineIo for TranscriptIo {
+    fn read_line(&mut self) -> Result<String> {
+        let line = match self.source {
+            Source::Engine(ref mut io) => io.read_line()?,
+            Source::Replay(ref mut entries) => {
+                // Skip anything recorded before the next input, like the times of late turns.
+                let mut line = String::new();
+                while let Some(entry) = entries.pop_front() {
+                    if let Entry::Input(input) = entry {
+                        line = input;
+                        break;
+                    }
+                }
+                return Ok(line);
+            }
+        };
+        self.write_entry(&Entry::Input(line.clone()))?;
+        Ok(line)
+    }
+
+    fn write(&mut self, output: &str) {
+        self.buffer.push_str(output);
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let output = ::std::mem::take(&mut self.buffer);
+        let index = self.outputs;
+        self.outputs += 1;
+        match self.source {
+            Source::Engine(ref mut io) => {
+                io.write(&output);
+                io.flush()?;
+            }
+            Source::Replay(ref mut entries) => {
+                let expected = match entries.pop_front() {
+                    Some(Entry::Output(expected)) => Some(expected),
+                    Some(entry) => {
+                        entries.push_front(entry);
+                        None
+                    }
+                    None => None,
+                };
+                if expected.as_ref() != Some(&output) {
+                    self.divergences.push(Divergence {
+                        index,
+                        expected,
+                        actual: output,
+                    });
+                }
+                return Ok(());
+            }
+        }
+        self.write_entry(&Entry::Output(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcript_replay() {
+        let mut io = TranscriptIo::replay(vec![
+            Entry::Input("1 0\n".to_string()),
+            Entry::Output("MyBot\n".to_string()),
+            Entry::Input("2\n".to_string()),
+            Entry::Fallback(1),
+            Entry::Elapsed(0.25),
+            Entry::Output("m 0 n\n".to_string()),
+        ]);
+        assert!(io.is_replaying());
+        assert_eq!(io.read_line().unwrap(), "1 0\n");
+        io.write("MyBot\n");
+        io.flush().unwrap();
+        assert_eq!(io.read_line().unwrap(), "2\n");
+        assert_eq!(
+            io.elapsed(Duration::from_secs(1)).unwrap(),
+            Duration::from_millis(250)
+        );
+        io.write("m 0 s\n");
+        io.flush().unwrap();
+        assert_eq!(io.read_line().unwrap(), "");
+
+        assert_eq!(
+            io.divergences,
+            vec![Divergence {
+                index: 1,
+                expected: Some("m 0 n\n".to_string()),
+                actual: "m 0 s\n".to_string(),
+            }]
+        );
+        assert_eq!(
+            io.check_divergences().unwrap_err().to_string(),
+            "the replay differed from the transcript 1 times\n    output 1 differs, expected \
+             Some(\"m 0 n\\n\") but sent \"m 0 s\\n\""
+        );
+    }
+
+    #[test]
+    fn test_entry_json() {
+        let entry = Entry::Input("1 0\n".to_string());
+        let json = serde_json::to_string(&entry).unwrap();
+        assert_eq!(json, r#"{"input":"1 0\n"}"#);
+        assert_eq!(serde_json::from_str::<Entry>(&json).unwrap(), entry);
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use super::{Grid, Position, Ship, ShipId};
+
+/// Counters of how often our Ships visited each Cell and how much halite they mined there.
+///
+/// This is useful to check whether the fleet is spread well over the Board.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Visits {
+    /// The number of turns one of our Ships spent at each Position.
+    pub visits: Grid<usize>,
+    /// The halite our Ships mined at each Position.
+    pub mined: Grid<usize>,
+}
+
+impl Visits {
+    /// Create a new Visits where nothing has been visited.
+    pub fn new(width: isize, height: isize) -> Self {
+        Visits {
+            visits: Grid::new(width, height, 0),
+            mined: Grid::new(width, height, 0),
+        }
+    }
+
+    /// Record a turn, given our current Ships and the state of them on the previous turn.
+    pub fn record<'a, I>(&mut self, ships: I, previous_ships: &HashMap<ShipId, Ship>)
+    where
+        I: IntoIterator<Item = &'a Ship>,
+    {
+        for ship in ships {
+            self.visits[ship.position] += 1;
+            if let Some(previous) = previous_ships.get(&ship.id) {
+                if previous.position == ship.position && ship.halite > previous.halite {
+                    self.mined[ship.position] += ship.halite - previous.halite;
+                }
+            }
+        }
+    }
+
+    /// Return the number of turns our Ships spent at the given Position.
+    pub fn visits(&self, position: Position) -> usize {
+        self.visits[position]
+    }
+
+    /// Return the halite our Ships mined at the given Position.
+    pub fn mined(&self, position: Position) -> usize {
+        self.mined[position]
+    }
+
+    /// Return the fraction of Cells that have been visited at least once.
+    pub fn coverage(&self) -> f64 {
+        let visited = self.visits.values().filter(|v| **v > 0).count();
+        visited as f64 / (self.visits.width * self.visits.height) as f64
+    }
+
+    /// Log the counters as grids, one row per line.
+    pub fn dump(&self) {
+        info!("Visited {:.1}% of the Board", 100.0 * self.coverage());
+        info!("Visits per Cell:");
+        for row in self.visits.rows() {
+            info!("{}", format_row(row));
+        }
+        info!("Halite mined per Cell:");
+        for row in self.mined.rows() {
+            info!("{}", format_row(row));
+        }
+    }
+}
+
+/// Format a row of counters as space separated, right aligned columns.
+fn format_row(row: &[usize]) -> String {
+    row.iter()
+        .map(|v| format!("{:>4}", v))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visits_record() {
+        let mut visits = Visits::new(4, 4);
+        let previous: HashMap<_, _> = vec![
+            (
+                0.into(),
+                Ship::new(0.into(), 0.into(), Position::new(1, 1), 100),
+            ),
+            (
+                1.into(),
+                Ship::new(1.into(), 0.into(), Position::new(2, 2), 100),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let ships = [
+            Ship::new(0.into(), 0.into(), Position::new(1, 1), 150),
+            Ship::new(1.into(), 0.into(), Position::new(3, 2), 90),
+            Ship::new(2.into(), 0.into(), Position::new(1, 1), 0),
+        ];
+
+        visits.record(&ships, &previous);
+        assert_eq!(visits.visits(Position::new(1, 1)), 2);
+        assert_eq!(visits.mined(Position::new(1, 1)), 50);
+        assert_eq!(visits.visits(Position::new(-1, 2)), 1);
+        assert_eq!(visits.mined(Position::new(3, 2)), 0);
+        assert_eq!(visits.coverage(), 2.0 / 16.0);
+    }
+}
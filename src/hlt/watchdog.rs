@@ -0,0 +1,172 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::engine::Engine;
+use super::{Command, EngineIo, Game, StdIo};
+
+/// How often the watchdog thread checks the time.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// The state shared between the Watchdog and its thread.
+struct Shared<W> {
+    /// Where the fallback commands are sent.
+    io: W,
+    /// When the current turn started, or `None` if the Watchdog is not armed.
+    started: Option<Instant>,
+    /// The current turn.
+    turn: usize,
+    /// The commands to send if the budget runs out.
+    commands: Vec<Command>,
+    /// Whether the commands for the current turn were sent by the Watchdog.
+    fired: bool,
+    /// The turns the Watchdog sent the commands for.
+    fired_turns: Vec<usize>,
+}
+
+/// Submits fallback commands on a separate thread if a turn runs past its budget.
+///
+/// Arm the Watchdog after each update, offer it the Game whenever the queued commands change
+/// significantly, and disarm it before ending the turn. If the budget runs out first, the last
+/// commands offered are sent, together with Collects for the rest of our Ships, so that a
+/// pathological turn costs a bad move rather than the whole Game. The Watchdog only works with
+/// batched submission.
+///
+/// Give it a `SharedIo` clone of the EngineIo the Game uses, so that the fallback commands go
+/// wherever the Game's commands go, like into a transcript.
+pub struct Watchdog<W = StdIo> {
+    shared: Arc<Mutex<Shared<W>>>,
+}
+
+impl Watchdog<StdIo> {
+    /// Start a Watchdog that sends to the Halite engine after the given budget.
+    pub fn new(budget: Duration) -> Self {
+        Watchdog::with_io(budget, StdIo::new())
+    }
+}
+
+impl<W: EngineIo + Send + 'static> Watchdog<W> {
+    /// Start a Watchdog that sends to the given EngineIo after the given budget.
+    pub fn with_io(budget: Duration, io: W) -> Self {
+        let shared = Arc::new(Mutex::new(Shared {
+            io,
+            started: None,
+            turn: 0,
+            commands: Vec::new(),
+            fired: false,
+            fired_turns: Vec::new(),
+        }));
+        let weak = Arc::downgrade(&shared);
+        thread::spawn(move || {
+            // Stop once the Watchdog has been dropped.
+            while let Some(shared) = weak.upgrade() {
+                {
+                    let mut shared = match shared.lock() {
+                        Ok(shared) => shared,
+                        Err(_) => break,
+                    };
+                    if shared.started.is_some_and(|s| s.elapsed() >= budget) {
+                        warn!("Turn ran past {:?}, sending fallback commands", budget);
+                        let shared = &mut *shared;
+                        let mut engine = Engine::new(&mut shared.io);
+                        for command in &shared.commands {
+                            engine.send(command);
+                        }
+                        if let Err(err) = engine.flush() {
+                            error!("Unable to send fallback commands: {}", err);
+                        }
+                        shared.started = None;
+                        shared.fired = true;
+                        let turn = shared.turn;
+                        shared.fired_turns.push(turn);
+                    }
+                }
+                drop(shared);
+                thread::sleep(POLL_INTERVAL);
+            }
+        });
+        Watchdog { shared }
+    }
+}
+
+impl<W> Watchdog<W> {
+    /// Arm the Watchdog for the current turn of the Game.
+    pub fn arm(&self, game: &Game) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.started = Some(game.timer.started);
+        shared.turn = game.turn;
+        shared.commands = game.fallback_commands();
+        shared.fired = false;
+    }
+
+    /// Update the commands to send if the budget runs out.
+    pub fn offer(&self, game: &Game) {
+        let mut shared = self.shared.lock().unwrap();
+        if shared.started.is_some() {
+            shared.commands = game.fallback_commands();
+        }
+    }
+
+    /// Disarm the Watchdog, returning whether the turn should still be ended.
+    ///
+    /// If this returns false, the Watchdog already sent commands for this turn, and sending any
+    /// more would confuse the engine.
+    pub fn disarm(&self) -> bool {
+        let mut shared = self.shared.lock().unwrap();
+        shared.started = None;
+        !shared.fired
+    }
+
+    /// Return the turns the Watchdog sent the fallback commands for, in order.
+    pub fn fired_turns(&self) -> Vec<usize> {
+        self.shared.lock().unwrap().fired_turns.clone()
+    }
+}
+
+impl Game {
+    /// Return the commands to send if a turn runs out of time.
+    ///
+    /// These are the legal queued commands, and Collects for our Ships that don't have a command.
+    pub fn fallback_commands(&self) -> Vec<Command> {
+        let mut commands = self.legal_commands();
+        let uncommanded = self.uncommanded_ships();
+        commands.extend(uncommanded.into_iter().map(Command::Collect));
+        commands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixtures, Direction, SharedIo, ShipId, StringIo};
+    use super::*;
+
+    #[test]
+    fn test_watchdog_fires() {
+        let mut game = fixtures::game(". a Y b .");
+        game.turn = 7;
+        game.move_ship(ShipId::new(0), Direction::West);
+
+        let io = SharedIo::new(StringIo::new(""));
+        let watchdog = Watchdog::with_io(Duration::from_millis(0), io.clone());
+        watchdog.arm(&game);
+        let started = Instant::now();
+        while watchdog.shared.lock().unwrap().started.is_some() {
+            assert!(started.elapsed() < Duration::from_secs(1));
+            thread::sleep(POLL_INTERVAL);
+        }
+        assert!(!watchdog.disarm());
+        assert_eq!(io.lock().output, "m 0 w m 1 o \n");
+        assert_eq!(watchdog.fired_turns(), vec![7]);
+    }
+
+    #[test]
+    fn test_watchdog_disarmed() {
+        let game = fixtures::game(". a Y b .");
+        let watchdog = Watchdog::with_io(Duration::from_secs(60), StringIo::new(""));
+        watchdog.arm(&game);
+        assert!(watchdog.disarm());
+        thread::sleep(POLL_INTERVAL * 2);
+        assert_eq!(watchdog.shared.lock().unwrap().io.output, "");
+        assert!(watchdog.fired_turns().is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+use rand::RngCore;
+
+use super::{Board, GameRng, PlayerId, Position, Ship};
+
+/// The amount of halite in each bucket of Cell halite.
+pub const HALITE_BUCKET_SIZE: usize = 100;
+
+/// The number of buckets of Cell halite, the last one holds everything above.
+const HALITE_BUCKETS: usize = 16;
+
+/// The amount of halite in each bucket of Ship cargo.
+pub const CARGO_BUCKET_SIZE: usize = 100;
+
+/// The number of buckets of Ship cargo, the last one holds everything above.
+const CARGO_BUCKETS: usize = 11;
+
+/// The most Players a Game can have.
+const MAX_PLAYERS: usize = 4;
+
+/// Random keys for Zobrist hashing the state of a Board.
+///
+/// The hash of a state is the XOR of one key for the halite bucket of every Cell and one key for
+/// the owner, Position, and cargo bucket of every Ship. Because XOR is its own inverse, a hash
+/// can be updated when something changes by XORing out the old key and XORing in the new one,
+/// which is much cheaper than hashing the whole Board again. Halite is bucketed so that small
+/// changes, like a little regeneration, don't make otherwise identical states look different.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Zobrist {
+    /// The width of the Board.
+    pub width: isize,
+    /// The height of the Board.
+    pub height: isize,
+    /// The keys for each halite bucket of each Cell.
+    halite: Vec<u64>,
+    /// The keys for each Player and cargo bucket of a Ship at each Cell.
+    ships: Vec<u64>,
+}
+
+impl Zobrist {
+    /// Create new keys for a Board of the given size, generated from the given seed.
+    pub fn new(width: isize, height: isize, seed: u64) -> Self {
+        let mut rng = GameRng::new(seed);
+        let cells = (width * height) as usize;
+        let halite = (0..cells * HALITE_BUCKETS)
+            .map(|_| rng.next_u64())
+            .collect();
+        let ships = (0..cells * MAX_PLAYERS * CARGO_BUCKETS)
+            .map(|_| rng.next_u64())
+            .collect();
+        Zobrist {
+            width,
+            height,
+            halite,
+            ships,
+        }
+    }
+
+    /// Return the index of the given Position, wrapping around at the edges.
+    fn cell(&self, position: Position) -> usize {
+        let normalized = position.normalized(self.width, self.height);
+        (normalized.y * self.width + normalized.x) as usize
+    }
+
+    /// Return the key for a Cell with the given halite.
+    pub fn halite_key(&self, position: Position, halite: usize) -> u64 {
+        let bucket = (halite / HALITE_BUCKET_SIZE).min(HALITE_BUCKETS - 1);
+        self.halite[self.cell(position) * HALITE_BUCKETS + bucket]
+    }
+
+    /// Return the key for a Ship of the given Player at a Position with the given cargo.
+    pub fn ship_key(&self, player_id: PlayerId, position: Position, cargo: usize) -> u64 {
+        let player = usize::from(player_id) % MAX_PLAYERS;
+        let bucket = (cargo / CARGO_BUCKET_SIZE).min(CARGO_BUCKETS - 1);
+        self.ships[(self.cell(position) * MAX_PLAYERS + player) * CARGO_BUCKETS + bucket]
+    }
+
+    /// Return the hash of a whole Board and the given Ships.
+    pub fn hash<'a, I>(&self, board: &Board, ships: I) -> u64
+    where
+        I: IntoIterator<Item = &'a Ship>,
+    {
+        let mut hash = 0;
+        for row in &board.cells {
+            for cell in row {
+                hash ^= self.halite_key(cell.position, cell.halite);
+            }
+        }
+        for ship in ships {
+            hash ^= self.ship_key(ship.player_id, ship.position, ship.halite);
+        }
+        hash
+    }
+
+    /// Return the hash of the Cells within the given distance of a center Position.
+    ///
+    /// Positions are taken relative to the center, so the same pattern of halite and Ships has
+    /// the same hash wherever it is on the Board. This finds repeated local situations, for
+    /// example in a replay.
+    pub fn local_hash<'a, I>(&self, board: &Board, ships: I, center: Position, radius: usize) -> u64
+    where
+        I: IntoIterator<Item = &'a Ship>,
+    {
+        let relative = |position: Position| {
+            let offset = board.offset(center, position);
+            Position::new(offset.dx, offset.dy)
+        };
+        let mut hash = 0;
+        for position in board.positions_within(center, radius) {
+            hash ^= self.halite_key(relative(position), board[position].halite);
+        }
+        for ship in ships {
+            if board.distance(center, ship.position) <= radius {
+                hash ^= self.ship_key(ship.player_id, relative(ship.position), ship.halite);
+            }
+        }
+        hash
+    }
+
+    /// Update a hash for a Cell's halite changing.
+    pub fn update_halite(&self, hash: u64, position: Position, from: usize, to: usize) -> u64 {
+        hash ^ self.halite_key(position, from) ^ self.halite_key(position, to)
+    }
+
+    /// Update a hash for a Ship moving or its cargo changing, given its old and new state.
+    pub fn update_ship(&self, hash: u64, from: &Ship, to: &Ship) -> u64 {
+        hash ^ self.ship_key(from.player_id, from.position, from.halite)
+            ^ self.ship_key(to.player_id, to.position, to.halite)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ShipId;
+    use super::*;
+
+    #[test]
+    fn test_zobrist_incremental() {
+        let mut board = Board::from_ascii(
+            "\
+             500 .   .
+             .   300 .
+             .   .   900",
+        ).unwrap();
+        let zobrist = Zobrist::new(board.width, board.height, 7);
+        let ship = Ship::new(ShipId(0), PlayerId(1), Position::new(0, 0), 0);
+        let hash = zobrist.hash(&board, &[ship]);
+        assert_eq!(hash, Zobrist::new(3, 3, 7).hash(&board, &[ship]));
+        assert_ne!(hash, zobrist.hash(&board, &[]));
+
+        let mut moved = ship;
+        moved.position = Position::new(1, 0);
+        moved.halite = 250;
+        let hash = zobrist.update_ship(hash, &ship, &moved);
+        let before = board[Position::new(1, 1)].halite;
+        board[Position::new(1, 1)].halite = 1200;
+        let hash = zobrist.update_halite(hash, Position::new(1, 1), before, 1200);
+        assert_eq!(hash, zobrist.hash(&board, &[moved]));
+
+        // A change within a bucket doesn't change the hash.
+        board[Position::new(1, 1)].halite = 1250;
+        assert_eq!(hash, zobrist.hash(&board, &[moved]));
+    }
+
+    #[test]
+    fn test_zobrist_local_hash() {
+        let board = Board::from_ascii(
+            "\
+             900 .   .   .   .
+             .   .   .   .   .
+             .   .   900 .   .
+             .   .   .   .   .
+             .   .   .   .   .",
+        ).unwrap();
+        let zobrist = Zobrist::new(board.width, board.height, 7);
+        let a = Ship::new(ShipId(0), PlayerId(0), Position::new(1, 0), 300);
+        let b = Ship::new(ShipId(1), PlayerId(0), Position::new(3, 2), 300);
+        let ships = [a, b];
+        let hash = |center| zobrist.local_hash(&board, &ships, center, 1);
+        assert_eq!(hash(Position::new(0, 0)), hash(Position::new(2, 2)));
+        assert_ne!(hash(Position::new(0, 0)), hash(Position::new(1, 1)));
+    }
+}
@@ -0,0 +1,19 @@
+extern crate bincode;
+#[macro_use]
+extern crate derive_more;
+#[macro_use]
+extern crate failure;
+#[macro_use]
+extern crate log;
+#[cfg(any(test, feature = "proptest"))]
+extern crate proptest;
+extern crate rand;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+extern crate simplelog;
+extern crate zstd;
+
+#[macro_use]
+pub mod hlt;
@@ -1,27 +1,17 @@
 #[macro_use]
 extern crate clap;
 #[macro_use]
-extern crate derive_more;
-#[macro_use]
-extern crate failure;
-#[macro_use]
 extern crate log;
-extern crate rand;
-extern crate serde;
-#[macro_use]
-extern crate serde_derive;
-extern crate serde_json;
-extern crate simplelog;
-
-mod hlt;
+extern crate my_bot;
 
 use std::process;
+use std::time::Duration;
 
-use clap::{App, Arg};
-use rand::Rng;
+use clap::{App, Arg, SubCommand};
 
-use hlt::util::{configure_logger, pretty_error, Result};
-use hlt::*;
+use my_bot::hlt::transcript::TranscriptIo;
+use my_bot::hlt::util::{pretty_error, Result};
+use my_bot::hlt::{analysis, bot, compare, SharedIo};
 
 fn run() -> Result<()> {
     // Parse command line arguments.
@@ -39,73 +29,151 @@ fn run() -> Result<()> {
                 .long("--log-file")
                 .takes_value(true)
                 .help("Override the name of the log file"),
+        ).arg(
+            Arg::with_name("analytics")
+                .short("-a")
+                .long("--analytics")
+                .takes_value(true)
+                .help("Write statistics for every turn to the given JSON lines file"),
+        ).arg(
+            Arg::with_name("metrics")
+                .short("-m")
+                .long("--metrics")
+                .takes_value(true)
+                .help("Write metrics for every turn to the given CSV file"),
+        ).arg(
+            Arg::with_name("heatmaps")
+                .short("-H")
+                .long("--heatmaps")
+                .takes_value(true)
+                .help("Write heatmap images for every turn to the given directory"),
+        ).arg(
+            Arg::with_name("transcript")
+                .short("-T")
+                .long("--transcript")
+                .takes_value(true)
+                .conflicts_with("replay")
+                .help("Record everything sent to and from the engine to the given file"),
+        ).arg(
+            Arg::with_name("replay")
+                .short("-r")
+                .long("--replay")
+                .takes_value(true)
+                .help("Replay a recorded transcript instead of talking to the engine"),
+        ).arg(
+            Arg::with_name("trajectory")
+                .short("-t")
+                .long("--trajectory")
+                .takes_value(true)
+                .help("Write features and rewards for every turn to the given JSON lines file"),
+        ).arg(
+            Arg::with_name("params")
+                .short("-p")
+                .long("--params")
+                .takes_value(true)
+                .help("Load strategy parameters from the given JSON file"),
         ).arg(
             Arg::with_name("name")
                 .short("-n")
                 .long("--name")
                 .takes_value(true)
                 .help("Override the name of the bot"),
+        ).arg(
+            Arg::with_name("slow")
+                .long("--slow")
+                .takes_value(true)
+                .help("Log phases of a turn that take longer than the given milliseconds"),
+        ).subcommand(
+            SubCommand::with_name("analyze")
+                .about("Report how each player played a downloaded replay")
+                .arg(
+                    Arg::with_name("replay")
+                        .required(true)
+                        .help("The .hlt replay file"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("compare")
+                .about("Play two bots against each other and report the win rate")
+                .arg(
+                    Arg::with_name("seeds")
+                        .short("-s")
+                        .long("--seeds")
+                        .takes_value(true)
+                        .default_value("10")
+                        .help("The number of map seeds to play, each once from both sides"),
+                ).arg(
+                    Arg::with_name("mini")
+                        .long("--mini")
+                        .help("Play mini-games on small maps with fewer turns"),
+                ).arg(
+                    Arg::with_name("size")
+                        .long("--size")
+                        .takes_value(true)
+                        .help("The width and height of the maps"),
+                ).arg(
+                    Arg::with_name("candidate")
+                        .required(true)
+                        .help("The command to run the bot being tested"),
+                ).arg(
+                    Arg::with_name("baseline")
+                        .required(true)
+                        .help("The command to run the bot to compare against"),
+                ),
         ).get_matches();
 
-    // The name of our bot.
-    let default_name = format!("MyBot-{}", crate_version!());
-    let name = cli.value_of("name").unwrap_or(&default_name);
-
-    // Start a new Game, by reading the game information from the Halite engine.
-    let mut game = Game::start()?;
-
-    // Configure the logger, so that we can use debug! and other log macros.
-    // It will log to a file called "MyBot-<game-seed>-<my-id>.log"
-    if cli.is_present("debug") {
-        let log_filename = format!("{}-{}-{}.log", name, constants::get().game_seed, game.my_id);
-        configure_logger(cli.value_of("filename").unwrap_or(&log_filename))?;
+    // Analyze a replay instead of playing, if requested.
+    if let Some(matches) = cli.subcommand_matches("analyze") {
+        print!("{}", analysis::analyze_file(matches.value_of("replay").unwrap())?);
+        return Ok(());
     }
 
-    // At this point "game" variable is populated with initial map data.
-    // This is a good place to do computationally expensive start-up pre-processing.
-    // ...
-    // ...
-    // ...
-
-    // This bot is a random bot, so we need a randomizer to pick the ship direction.
-    let mut rng = rand::thread_rng();
-
-    // Call "ready" function below, the 2 second per turn timer will start now.
-    game.ready(name);
-    info!(
-        "Successfully initialized {}! Player ID is {}",
-        name, game.my_id
-    );
-
-    loop {
-        // Get the updated Game from the Halite engine.
-        game.update()?;
-
-        // Get our Player.
-        let me = game.me().clone();
-
-        // Loop through all of our Ships and randomly generate the direction.
-        for ship_id in me.ship_ids {
-            let ship = game.ships[&ship_id];
-            let cell = game.board[ship.position];
-
-            if cell.halite < constants::get().max_halite / 10 || ship.is_full() {
-                game.move_ship(ship_id, Direction::all()[rng.gen_range(0, 4)]);
-            } else {
-                game.collect_halite(ship_id);
-            }
-        }
-
-        // If we have enough halite, spawn a new ship!
-        if game.turn <= 400
-            && me.halite >= constants::get().new_entity_halite_cost
-            && !game.board[me.shipyard.position].is_occupied()
-        {
-            game.spawn_ship();
-        }
-
-        game.end_turn();
+    // Compare two bots instead of playing, if requested.
+    if let Some(matches) = cli.subcommand_matches("compare") {
+        let size = match matches.value_of("size") {
+            Some(size) => Some(size.parse()?),
+            None => None,
+        };
+        let comparison = compare::compare_locally(
+            matches.value_of("candidate").unwrap(),
+            matches.value_of("baseline").unwrap(),
+            matches.value_of("seeds").unwrap().parse()?,
+            matches.is_present("mini"),
+            size,
+            |result, comparison| eprintln!("seed {}: {}", result.map_seed, comparison),
+        )?;
+        println!("{}", comparison);
+        return Ok(());
     }
+
+    let options = bot::Options {
+        name: cli
+            .value_of("name")
+            .map(String::from)
+            .unwrap_or_else(|| format!("MyBot-{}", crate_version!())),
+        debug: cli.is_present("debug"),
+        log_file: cli.value_of("filename").map(String::from),
+        analytics: cli.value_of("analytics").map(String::from),
+        metrics: cli.value_of("metrics").map(String::from),
+        heatmaps: cli.value_of("heatmaps").map(String::from),
+        trajectory: cli.value_of("trajectory").map(String::from),
+        params: cli.value_of("params").map(String::from),
+        slow: match cli.value_of("slow") {
+            Some(millis) => Some(Duration::from_millis(millis.parse()?)),
+            None => None,
+        },
+    };
+
+    // Talk to the Halite engine, recording a transcript if requested.
+    let io = SharedIo::new(match (cli.value_of("transcript"), cli.value_of("replay")) {
+        (Some(filename), _) => TranscriptIo::create(filename)?,
+        (None, Some(filename)) => TranscriptIo::open(filename)?,
+        (None, None) => TranscriptIo::engine(),
+    });
+    bot::play(&io, options)?;
+
+    // Report where a replay gave different commands from the recorded Game.
+    io.lock().check_divergences()?;
+    Ok(())
 }
 
 fn main() {
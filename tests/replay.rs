@@ -0,0 +1,46 @@
+//! Replaying a transcript must give the same commands every time. Each process seeds its hashers
+//! differently, so this catches strategies that depend on the order a HashMap iterates in.
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process::{self, Command, Stdio};
+
+/// Return a Command that runs the bot.
+fn bot() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_my_bot"))
+}
+
+#[test]
+// The fixture frames aren't from real Games, and break invariants that strict checks panic on.
+#[cfg_attr(feature = "strict-checks", ignore)]
+fn test_replay_is_deterministic() {
+    for fixture in &["2p-32x32", "4p-40x40"] {
+        let input = format!("{}/tests/fixtures/{}.txt", env!("CARGO_MANIFEST_DIR"), fixture);
+        let transcript =
+            env::temp_dir().join(format!("my_bot-{}-{}.jsonl", fixture, process::id()));
+
+        // Record a Game against the fixture input.
+        let mut child = bot()
+            .arg("--transcript")
+            .arg(&transcript)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+        let input = fs::read(input).unwrap();
+        child.stdin.take().unwrap().write_all(&input).unwrap();
+        assert!(child.wait().unwrap().success());
+
+        // Replay it twice, in separate processes. The bot fails if any output differs.
+        for _ in 0..2 {
+            let output = bot().arg("--replay").arg(&transcript).output().unwrap();
+            assert!(
+                output.status.success(),
+                "{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        fs::remove_file(transcript).unwrap();
+    }
+}